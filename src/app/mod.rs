@@ -1,19 +1,32 @@
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyModifiers};
-use ratatui::DefaultTerminal;
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Style},
     widgets::{Block, Paragraph},
     Frame,
 };
+use ratatui::{DefaultTerminal, Terminal};
 use std::cell::RefCell;
+use std::fs;
+use std::io::{stdout, Write};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::time::Duration;
 use std::time::Instant;
 use tui_input::Input;
 
-use crate::app::app_error::AppResult;
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::clipboard_helper::copy_to_clipboard;
+use crate::message_holder::command_holder::describe_status;
+use crate::message_holder::editor_holder::{EditorRequest, EditorTask};
+use crate::message_holder::history_store::HistoryStore;
+use crate::message_holder::watch_holder::WatchHolder;
 use crate::message_holder::MessageHolder;
 use crate::state_holder::{InputMode, StateHolder, ViewMode};
 
@@ -25,8 +38,14 @@ pub mod app_error;
 /// Submodules:
 /// - `normal_search` - Normal input mode with search view
 /// - `normal_file_view` - Normal input mode with file viewing
+/// - `normal_file_history_view` - Normal input mode with git history of the opened file
+/// - `normal_confirm_view` - Normal input mode with the confirmation popup
+/// - `normal_command_view` - Normal input mode with the output of a shell command
 /// - `edit_search` - Edit input mode with search view
 /// - `edit_history_folder_view` - Edit input mode with history/folder view
+/// - `edit_bookmark_view` - Edit input mode with the bookmark picker
+/// - `edit_file_view` - Edit input mode with the log time range of the opened file
+/// - `edit_prompt_view` - Edit input mode with the name of a renamed or new entry, or a shell command
 pub mod state_handler;
 
 /// Minimum width for the input area (in terminal columns)
@@ -38,6 +57,9 @@ const INPUT_WIDTH_PADDING: u16 = 3;
 /// Duration between event polls (100ms)
 const TICK_RATE: Duration = Duration::from_millis(100);
 
+/// Rows scrolled by PageUp/PageDown, and left shown by End
+const PAGE_ROWS: usize = 30;
+
 /// Main application struct that manages the TUI state and rendering
 ///
 /// # Fields
@@ -49,6 +71,10 @@ const TICK_RATE: Duration = Duration::from_millis(100);
 /// - `timer`: Performance tracking timer
 /// - `duration`: Elapsed time since last operation
 /// - `log_message`: Current status/error message for display
+/// - `pending_key`: Prefix key waiting for a mark letter (`m` or `'`) or a depth (`E`)
+/// - `watch_holder`: Filesystem watcher refreshing the view (if enabled)
+/// - `pending_editor`: File to open in the external editor once the event is handled
+/// - `keyboard_enhanced`: Whether the terminal reports Ctrl+I apart from Tab
 #[derive(Debug)]
 pub struct App {
     pub state_holder: Rc<RefCell<StateHolder>>,
@@ -59,6 +85,10 @@ pub struct App {
     pub duration: Duration,
     pub log_message: String,
     state_changed: bool,
+    pending_key: Option<char>,
+    watch_holder: Option<WatchHolder>,
+    pub pending_editor: Option<EditorRequest>,
+    pub keyboard_enhanced: bool,
 }
 
 impl App {
@@ -95,12 +125,440 @@ impl App {
             duration: Duration::default(),
             log_message: "".into(),
             state_changed: true,
+            pending_key: None,
+            watch_holder: None,
+            pending_editor: None,
+            keyboard_enhanced: false,
         })
     }
+
+    /// Restores the directory history saved by a previous session
+    ///
+    /// The history is saved back to the same store when the application exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `history_store` - State file to load from and save to
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be read
+    pub fn load_history(&mut self, history_store: HistoryStore) -> AppResult<()> {
+        self.message_holder.load_history(history_store)
+    }
+
+    /// Restores the named marks saved by a previous session
+    ///
+    /// # Arguments
+    ///
+    /// * `store_path` - State file to load from and save to on every change
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be read
+    pub fn load_bookmarks(&mut self, store_path: PathBuf) -> AppResult<()> {
+        self.message_holder.load_bookmarks(store_path)
+    }
+
+    /// Starts watching the displayed directories and the opened file
+    ///
+    /// Changes refresh the view automatically, see [`App::handle_file_changes`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the
+    /// watcher cannot be created
+    pub fn watch_files(&mut self) -> AppResult<()> {
+        let mut watch_holder = WatchHolder::new()?;
+        watch_holder.watch_paths(self.message_holder.watched_paths());
+        self.watch_holder = Some(watch_holder);
+        Ok(())
+    }
+
+    /// Applies debounced filesystem changes and follows the displayed paths
+    ///
+    /// Does nothing unless [`App::watch_files`] was called.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain errors from reloading the
+    /// listing or the opened file
+    pub fn handle_file_changes(&mut self) -> AppResult<()> {
+        let Some(watch_holder) = &mut self.watch_holder else {
+            return Ok(());
+        };
+        let changed = watch_holder.poll();
+        if let Some(changed) = &changed {
+            self.state_changed = true;
+            self.message_holder.apply_file_changes(changed)?;
+        }
+        if self.state_changed {
+            let paths = self.message_holder.watched_paths();
+            if let Some(watch_holder) = &mut self.watch_holder {
+                watch_holder.watch_paths(paths);
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows lines appended to the followed file
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain errors from reading the
+    /// followed file
+    pub fn handle_follow(&mut self) -> AppResult<()> {
+        if self.message_holder.poll_follow()? {
+            self.state_changed = true;
+        }
+        Ok(())
+    }
+
+    /// Shows directory entries read in the background, and the unreadable
+    /// paths skipped
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Parse` if a
+    /// directory cannot be read
+    pub fn handle_directory_loads(&mut self) -> AppResult<()> {
+        if self.message_holder.poll_loading()? {
+            self.state_changed = true;
+        }
+        if let Some(report) = self.message_holder.folder_holder.take_skipped_report() {
+            self.log_message = report;
+            self.state_changed = true;
+        }
+        Ok(())
+    }
+
+    /// Shows the progress of the paste or delete in progress, and its result once finished
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Path` listing the
+    /// items that could not be pasted or deleted
+    pub fn handle_transfers(&mut self) -> AppResult<()> {
+        if !self.message_holder.is_transferring() && !self.message_holder.is_deleting() {
+            return Ok(());
+        }
+        self.state_changed = true;
+        if let Some(message) = self.message_holder.poll_transfer()? {
+            self.log_message = message;
+        }
+        if let Some(message) = self.message_holder.poll_delete()? {
+            self.log_message = message;
+        }
+        Ok(())
+    }
+
+    /// Copies the yanked paths to the terminal clipboard
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Output of the terminal
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the
+    /// terminal cannot be written
+    pub fn handle_clipboard(&mut self, writer: &mut impl Write) -> AppResult<()> {
+        let Some(text) = self.message_holder.pending_clipboard.take() else {
+            return Ok(());
+        };
+        copy_to_clipboard(writer, &text)
+    }
+
+    /// Runs the external editor asked for, with the terminal suspended
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - Terminal restored while the editor runs
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the terminal cannot be set up again
+    /// - errors of [`App::finish_editor`]
+    pub fn handle_editor(&mut self, terminal: &mut DefaultTerminal) -> AppResult<()> {
+        let Some(request) = self.pending_editor.take() else {
+            return Ok(());
+        };
+        let result = self.suspend(terminal, || request.run())?;
+        self.finish_editor(request, result)
+    }
+
+    /// Runs the shell command typed with a `!` prefix, with the terminal suspended
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - Terminal restored while the command runs
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the shell cannot be run or the terminal cannot
+    ///   be set up again
+    /// - `AppError::Parse`: If a directory cannot be read
+    pub fn handle_interactive_command(&mut self, terminal: &mut DefaultTerminal) -> AppResult<()> {
+        let Some(command) = self.message_holder.pending_command.take() else {
+            return Ok(());
+        };
+        let status = self.suspend(terminal, || command.run_interactive())?;
+        self.message_holder.finish_command(&command)?;
+        self.log_message = format!(
+            "{} ended with {}",
+            command.command,
+            describe_status(status?)
+        );
+        Ok(())
+    }
+
+    /// Gives the terminal to another program while it runs
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - Terminal restored while the program runs
+    /// * `run` - Runs the program
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<T>` with the result of `run`, which may contain
+    /// `AppError::Io` if the terminal cannot be set up again
+    fn suspend<T>(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        run: impl FnOnce() -> T,
+    ) -> AppResult<T> {
+        if self.keyboard_enhanced {
+            let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+        }
+        ratatui::restore();
+        let result = run();
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        if self.keyboard_enhanced {
+            let _ = execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            );
+        }
+        terminal.clear()?;
+        self.state_changed = true;
+        Ok(result)
+    }
+
+    /// Shows the output of the running command, and its exit status once it exits
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain errors from waiting for the
+    /// command or refreshing the paths it was given
+    pub fn handle_command_output(&mut self) -> AppResult<()> {
+        if !self.message_holder.is_command_running() {
+            return Ok(());
+        }
+        self.state_changed = true;
+        if let Some(message) = self.message_holder.poll_command()? {
+            self.log_message = message;
+        }
+        Ok(())
+    }
+
+    /// Handles the files edited in the external editor once it exited
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The files that were edited
+    /// * `result` - Whether the editor ran and exited successfully
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the editor failed
+    /// - `AppError::Io`: If an edited file cannot be read
+    /// - `AppError::Parse`, `AppError::Path`: If the bulk renames are invalid
+    pub fn finish_editor(
+        &mut self,
+        request: EditorRequest,
+        result: AppResult<()>,
+    ) -> AppResult<()> {
+        match request.task {
+            EditorTask::BulkRename { buffer, originals } => {
+                let text = fs::read_to_string(&buffer);
+                let _ = fs::remove_file(&buffer);
+                result?;
+                self.log_message = self.message_holder.finish_bulk_rename(&originals, &text?)?;
+            }
+            EditorTask::Open { n_skipped } => {
+                self.message_holder.reload_paths(&request.paths)?;
+                result?;
+                self.log_message = match request.paths.as_slice() {
+                    [path] => format!("Edited {}", path.display()),
+                    paths => format!("Edited {} files", paths.len()),
+                };
+                if n_skipped > 0 {
+                    let directories = if n_skipped == 1 {
+                        "directory"
+                    } else {
+                        "directories"
+                    };
+                    self.log_message
+                        .push_str(&format!(", skipped {} {}", n_skipped, directories));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops reading directories, pasting or deleting in the background, if any
+    ///
+    /// # Returns
+    ///
+    /// Returns whether something was cancelled
+    pub fn cancel_loading(&mut self) -> bool {
+        let is_loading = self.message_holder.cancel_loading();
+        if is_loading {
+            self.log_message = "Loading cancelled".into();
+        }
+        let is_transferring = self.message_holder.cancel_transfer();
+        if is_transferring {
+            self.log_message = "Paste cancelled".into();
+        }
+        let is_deleting = self.message_holder.cancel_delete();
+        if is_deleting {
+            self.log_message = "Delete cancelled".into();
+        }
+        is_loading || is_transferring || is_deleting
+    }
+
+    /// Puts the filter back in the input once a name was typed in it
+    pub fn restore_filter_input(&mut self) {
+        self.input = Input::new(self.message_holder.folder_holder.input.clone());
+    }
+
+    /// Handles the scroll keys shared by the file and command views
+    ///
+    /// `j/k/h/l` and the arrows scroll by one, `Home` goes to the top left,
+    /// `End` to the last page and `PageUp/PageDown` scroll by a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_code` - The pressed key
+    /// * `scroll` - Vertical and horizontal scroll positions to move
+    /// * `n_rows` - Number of rows of the view
+    /// * `max_line_length` - Length of the longest line of the view
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the key was consumed
+    pub fn handle_scroll_key(
+        key_code: KeyCode,
+        scroll: (&mut usize, &mut usize),
+        n_rows: usize,
+        max_line_length: usize,
+    ) -> bool {
+        let (vertical_scroll, horizontal_scroll) = scroll;
+        match key_code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                *vertical_scroll = vertical_scroll.saturating_add(1).min(n_rows);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                *vertical_scroll = vertical_scroll.saturating_sub(1);
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                *horizontal_scroll = horizontal_scroll.saturating_sub(1);
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                *horizontal_scroll = horizontal_scroll.saturating_add(1).min(max_line_length);
+            }
+            KeyCode::Home => {
+                *horizontal_scroll = 0;
+                *vertical_scroll = 0;
+            }
+            KeyCode::End => *vertical_scroll = n_rows.saturating_sub(PAGE_ROWS),
+            KeyCode::PageDown => {
+                *vertical_scroll = vertical_scroll.saturating_add(PAGE_ROWS).min(n_rows);
+            }
+            KeyCode::PageUp => *vertical_scroll = vertical_scroll.saturating_sub(PAGE_ROWS),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handles vim-style mark keys shared by the normal modes
+    ///
+    /// `m<letter>` sets a mark, `'<letter>` jumps to it and `M` opens the
+    /// bookmark picker.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_code` - The pressed key
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>`, `true` if the key was consumed, which may
+    /// contain errors from setting or jumping to a mark
+    pub fn handle_mark_key(&mut self, key_code: KeyCode) -> AppResult<bool> {
+        if let Some(prefix) = self.pending_key.take() {
+            if let KeyCode::Char(name) = key_code {
+                if prefix == 'm' {
+                    self.message_holder.set_bookmark(name)?;
+                    self.log_message = format!("Mark {} set", name);
+                } else {
+                    self.message_holder.jump_to_bookmark(name)?;
+                    self.input.reset();
+                }
+            }
+            return Ok(true);
+        }
+        match key_code {
+            KeyCode::Char(prefix @ ('m' | '\'')) => self.pending_key = Some(prefix),
+            KeyCode::Char('M') => {
+                self.message_holder.bookmark_holder.update(Some("".into()));
+                self.input.reset();
+                self.state_holder.borrow_mut().to_bookmark_search()?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Handles the jump list keys shared by the normal modes
+    ///
+    /// `Ctrl+O` goes back and `Ctrl+I` goes forward. Terminals without
+    /// keyboard enhancement report `Ctrl+I` as `Tab`, so `Ctrl+N` goes
+    /// forward too.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_event` - The pressed key
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>`, `true` if the key was consumed, which may
+    /// contain errors from restoring the location
+    pub fn handle_jump_key(&mut self, key_event: &KeyEvent) -> AppResult<bool> {
+        if !key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(false);
+        }
+        match key_event.code {
+            KeyCode::Char('o') => self.message_holder.jump_back()?,
+            KeyCode::Char('i') | KeyCode::Char('n') => self.message_holder.jump_forward()?,
+            _ => return Ok(false),
+        }
+        self.input.reset();
+        Ok(true)
+    }
+
     /// Runs the main application loop
     ///
     /// This method handles the event loop, rendering, and error handling
-    /// until the user exits (Ctrl+Z) or a terminal error occurs.
+    /// until the user exits (Ctrl+Z) or a terminal error occurs. Filesystem
+    /// changes, directories read in the background and command output are
+    /// applied between events. The directory history is saved on exit.
     ///
     /// # Arguments
     ///
@@ -112,18 +570,62 @@ impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> app_error::AppResult<()> {
         loop {
             if self.state_changed {
-                terminal.draw(|frame| self.draw(frame).expect("Unexpected!"))?;
+                self.render(terminal)?;
                 self.state_changed = false;
             }
             let result = self.handle_event();
             if let Err(err) = result {
                 self.handle_error(err)
             }
+            if let Err(err) = self.handle_clipboard(&mut stdout()) {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_editor(terminal) {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_interactive_command(terminal) {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_file_changes() {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_follow() {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_directory_loads() {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_transfers() {
+                self.handle_error(err)
+            }
+            if let Err(err) = self.handle_command_output() {
+                self.handle_error(err)
+            }
+            self.handle_git_status();
+            self.handle_preview();
             if self.exit {
-                return Ok(());
+                return self.message_holder.save_history();
             }
         }
     }
+
+    /// Redraws once the preview of the selected entry is due, or a directory
+    /// preview is read
+    pub fn handle_preview(&mut self) {
+        if self.message_holder.poll_previews() {
+            self.state_changed = true;
+        }
+    }
+
+    /// Shows the git status and the line changes of the opened file once read in the background
+    pub fn handle_git_status(&mut self) {
+        if self.message_holder.folder_holder.poll_git_status()
+            | self.message_holder.poll_file_git_info()
+        {
+            self.state_changed = true;
+        }
+    }
+
     /// Handles errors by updating the log message and potentially exiting
     ///
     /// # Arguments
@@ -137,6 +639,25 @@ impl App {
         }
     }
 
+    /// Draws a frame, showing a drawing error in the log area
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - The terminal to render to
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the
+    /// terminal cannot be written
+    pub fn render<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> AppResult<()> {
+        let mut draw_result = Ok(());
+        terminal.draw(|frame| draw_result = self.draw(frame))?;
+        if let Err(err) = draw_result {
+            self.handle_error(err);
+        }
+        Ok(())
+    }
+
     /// Renders the current application state to the terminal frame
     ///
     /// This method draws all UI components including:
@@ -151,7 +672,8 @@ impl App {
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain `AppError::Terminal` on render errors
+    /// Returns `AppResult<()>` which may contain `AppError::Terminal` on render errors,
+    /// or `AppError::State` for a mode without a view, once the other areas are drawn
     pub fn draw(&mut self, frame: &mut Frame) -> AppResult<()> {
         use InputMode::*;
         use ViewMode::*;
@@ -166,18 +688,35 @@ impl App {
         let input_mode = self.state_holder.borrow().input_mode;
         let view_mode = self.state_holder.borrow().view_mode;
 
+        let mut result = Ok(());
         match (input_mode, view_mode) {
             (Normal, Search) => self.draw_help_normal_search(help_area, frame),
             (Normal, FileView) => self.draw_help_normal_file_view(help_area, frame),
+            (Normal, FileHistoryView) => self.draw_help_normal_file_history_view(help_area, frame),
+            (Normal, ConfirmView) => self.draw_help_normal_confirm_view(help_area, frame),
+            (Normal, CommandView) => self.draw_help_normal_command_view(help_area, frame),
+            (Normal, HelpView) => self.draw_help_normal_help_view(help_area, frame),
+            (Edit, PromptView) => self.draw_help_edit_prompt_view(help_area, frame),
             (Edit, HistoryFolderView) => self.draw_help_edit_history_folder_view(help_area, frame),
             (Edit, Search) => self.draw_edit_search(help_area, frame),
-            _ => (),
+            (Edit, BookmarkView) => self.draw_help_edit_bookmark_view(help_area, frame),
+            (Edit, FileView) => self.draw_help_edit_file_view(help_area, frame),
+            (input_mode, view_mode) => {
+                result = Err(AppError::State(format!(
+                    "Unexpected mode {:?}+{:?}",
+                    input_mode, view_mode
+                )))
+            }
         }
         self.draw_input_area(input_area, frame);
-        self.message_holder.draw(messages_area, frame)?;
         self.draw_log_area(log_area, frame);
+        if self.state_holder.borrow().is_help_view() {
+            self.draw_normal_help_view(messages_area, frame)?;
+        } else {
+            self.message_holder.draw(messages_area, frame)?;
+        }
 
-        Ok(())
+        result
     }
 
     /// Renders the input area with the current input buffer
@@ -197,10 +736,14 @@ impl App {
             Style::default()
         };
 
+        let title = match &self.message_holder.pending_prompt {
+            Some(prompt) => prompt.to_string(),
+            None => "Input".into(),
+        };
         let input = Paragraph::new(self.input.value())
             .style(style)
             .scroll((0, scroll as u16))
-            .block(Block::bordered().title("Input"));
+            .block(Block::bordered().title(title));
         frame.render_widget(input, area);
 
         // https://github.com/sayanarijit/tui-input/blob/main/examples/ratatui_crossterm_input.rs
@@ -272,9 +815,21 @@ impl App {
             match (input_mode, view_mode) {
                 (Normal, Search) => self.handle_normal_search_event(event)?,
                 (Normal, FileView) => self.handle_normal_file_view_event(event)?,
+                (Normal, FileHistoryView) => self.handle_normal_file_history_view_event(event)?,
+                (Normal, ConfirmView) => self.handle_normal_confirm_view_event(event)?,
+                (Normal, CommandView) => self.handle_normal_command_view_event(event)?,
+                (Normal, HelpView) => self.handle_normal_help_view_event(event)?,
+                (Edit, PromptView) => self.handle_edit_prompt_view_event(event)?,
                 (Edit, HistoryFolderView) => self.handle_edit_history_folder_view_event(event)?,
                 (Edit, Search) => self.handle_edit_search_event(event)?,
-                _ => (),
+                (Edit, BookmarkView) => self.handle_edit_bookmark_view_event(event)?,
+                (Edit, FileView) => self.handle_edit_file_view_event(event)?,
+                (input_mode, view_mode) => {
+                    return Err(AppError::State(format!(
+                        "Unexpected mode {:?}+{:?}",
+                        input_mode, view_mode
+                    )))
+                }
             }
 
             if is_key_press_event {
@@ -0,0 +1,60 @@
+//! Event handling and rendering for Edit+BookmarkView mode
+//!
+//! This mode lists the named marks, filtered by the typed query.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_input::backend::crossterm::EventHandler;
+
+use crate::app::app_error::AppResult;
+use crate::app::App;
+
+impl App {
+    pub fn handle_edit_bookmark_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            let bookmark_holder = &mut self.message_holder.bookmark_holder;
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.input.reset();
+                    self.state_holder.borrow_mut().pop_mode()?;
+                }
+                KeyCode::Up => {
+                    bookmark_holder.raw_highlight_index =
+                        bookmark_holder.raw_highlight_index.saturating_sub(1);
+                }
+                KeyCode::Down => {
+                    bookmark_holder.raw_highlight_index =
+                        bookmark_holder.raw_highlight_index.saturating_add(1);
+                }
+                KeyCode::Enter => {
+                    self.message_holder.submit_bookmark()?;
+                    self.input.reset();
+                }
+                _ => {
+                    self.input.handle_event(&event);
+                    bookmark_holder.update(Some(self.input.value().to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn draw_help_edit_bookmark_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "Bookmarks ".bold(),
+            " Jump ".into(),
+            "<Enter>".light_blue().bold(),
+            " Back ".into(),
+            "<Esc>".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
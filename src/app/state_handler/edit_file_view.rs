@@ -0,0 +1,53 @@
+//! Event handling and rendering for Edit+FileView mode
+//!
+//! This mode edits the time range shown in the log view of the opened file.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_input::backend::crossterm::EventHandler;
+
+use crate::app::app_error::AppResult;
+use crate::app::App;
+
+impl App {
+    pub fn handle_edit_file_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.input.reset();
+                    self.state_holder.borrow_mut().pop_mode()?;
+                }
+                KeyCode::Enter => {
+                    self.message_holder.set_log_time_range(self.input.value())?;
+                    self.input.reset();
+                    self.state_holder.borrow_mut().pop_mode()?;
+                }
+                _ => {
+                    self.input.handle_event(&event);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn draw_help_edit_file_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "Log Time Range ".bold(),
+            " Apply ".into(),
+            "<Enter>".light_blue().bold(),
+            " Back ".into(),
+            "<Esc>".light_blue().bold(),
+            " Format ".into(),
+            "FROM..TO".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
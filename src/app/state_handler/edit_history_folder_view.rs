@@ -19,7 +19,7 @@ impl App {
     pub fn handle_edit_history_folder_view_event(&mut self, event: Event) -> AppResult<()> {
         if let Event::Key(key_event) = event {
             match key_event.code {
-                KeyCode::Tab => self.state_holder.borrow_mut().to_search(),
+                KeyCode::Tab => self.state_holder.borrow_mut().to_search()?,
                 KeyCode::Up => self.message_holder.move_up(),
                 KeyCode::Down => self.message_holder.move_down(),
                 KeyCode::Enter => {
@@ -0,0 +1,55 @@
+//! Event handling and rendering for Edit+PromptView mode
+//!
+//! This mode types the name of a renamed entry, of a file or directory to
+//! create, or a shell command to run, in the input. The search filter is
+//! put back afterwards.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_input::backend::crossterm::EventHandler;
+
+use crate::app::app_error::AppResult;
+use crate::app::App;
+
+impl App {
+    pub fn handle_edit_prompt_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Esc => {
+                    self.message_holder.cancel_prompt();
+                    self.restore_filter_input();
+                    self.state_holder.borrow_mut().pop_mode()?;
+                }
+                KeyCode::Enter => {
+                    let text = self.input.value().to_string();
+                    self.restore_filter_input();
+                    self.state_holder.borrow_mut().pop_mode()?;
+                    self.log_message = self.message_holder.submit_prompt(&text)?;
+                }
+                _ => {
+                    self.input.handle_event(&event);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn draw_help_edit_prompt_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "Prompt ".bold(),
+            " Apply ".into(),
+            "<Enter>".light_blue().bold(),
+            " Back ".into(),
+            "<Esc>".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
@@ -19,7 +19,13 @@ impl App {
     pub fn handle_edit_search_event(&mut self, event: Event) -> AppResult<()> {
         if let Event::Key(key_event) = event {
             match key_event.code {
-                KeyCode::Tab => self.state_holder.borrow_mut().to_search(),
+                KeyCode::Tab => self.state_holder.borrow_mut().to_search()?,
+                KeyCode::Esc => {
+                    if !self.cancel_loading() && self.message_holder.close_nested_search()? {
+                        self.input.reset();
+                    }
+                }
+                KeyCode::F(1) => self.state_holder.borrow_mut().to_help()?,
                 KeyCode::Up => self.message_holder.move_up(),
                 KeyCode::Down => self.message_holder.move_down(),
                 KeyCode::Enter => {
@@ -27,7 +33,11 @@ impl App {
                     self.input.reset();
                 }
                 _ => {
-                    if (key_event.code == KeyCode::Char('c'))
+                    if (key_event.code == KeyCode::Char('p'))
+                        & key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        self.message_holder.toggle_quick_look();
+                    } else if (key_event.code == KeyCode::Char('c'))
                         & key_event.modifiers.contains(KeyModifiers::CONTROL)
                     {
                         self.input.reset();
@@ -43,16 +53,26 @@ impl App {
         Ok(())
     }
     pub fn draw_edit_search(&mut self, help_area: Rect, frame: &mut Frame) {
-        let instructions = Text::from(Line::from(vec![
+        let instructions = Text::from(Self::help_line_edit_search());
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+
+    /// Returns the key bindings of the mode, also listed by the help view
+    pub fn help_line_edit_search() -> Line<'static> {
+        Line::from(vec![
             "FileSearch ".bold(),
             "Switch to".into(),
             " Normal ".bold(),
             "<Tab>".light_blue().bold(),
             " Clear ".bold(),
             "<CTRL+C>".light_blue().bold(),
-        ]));
-
-        let help_message = Paragraph::new(instructions);
-        frame.render_widget(help_message, help_area);
+            " Preview ".bold(),
+            "<CTRL+P>".light_blue().bold(),
+            " Help ".bold(),
+            "<F1>".light_blue().bold(),
+            " Cancel Load/Back to File ".bold(),
+            "<Esc>".light_blue().bold(),
+        ])
     }
 }
@@ -7,10 +7,24 @@
 //!
 //! - `normal_search` - Normal input mode with search view
 //! - `normal_file_view` - Normal input mode with file viewing
+//! - `normal_file_history_view` - Normal input mode with git history of the opened file
+//! - `normal_confirm_view` - Normal input mode with the confirmation popup
+//! - `normal_command_view` - Normal input mode with the output of a shell command
+//! - `normal_help_view` - Normal input mode with every key binding of the mode below
 //! - `edit_search` - Edit input mode with search view
 //! - `edit_history_folder_view` - Edit input mode with history/folder view
+//! - `edit_bookmark_view` - Edit input mode with the bookmark picker
+//! - `edit_file_view` - Edit input mode with the log time range of the opened file
+//! - `edit_prompt_view` - Edit input mode with the name of a renamed or new entry, or a shell command
 
+pub mod edit_bookmark_view;
+pub mod edit_file_view;
 pub mod edit_history_folder_view;
+pub mod edit_prompt_view;
 pub mod edit_search;
+pub mod normal_command_view;
+pub mod normal_confirm_view;
+pub mod normal_file_history_view;
 pub mod normal_file_view;
+pub mod normal_help_view;
 pub mod normal_search;
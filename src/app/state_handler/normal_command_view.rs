@@ -0,0 +1,66 @@
+//! Event handling and rendering for Normal+CommandView mode
+//!
+//! This mode shows the output of a shell command as it runs, scrolled
+//! like a file. Closing the view stops the command.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::app::App;
+
+impl App {
+    pub fn handle_normal_command_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            let command_holder = self
+                .message_holder
+                .command_holder
+                .as_mut()
+                .ok_or(AppError::State("No command is shown".into()))?;
+
+            let n_rows = command_holder.lines.len();
+            let max_line_length = command_holder.max_line_length;
+            let scroll = (
+                &mut command_holder.vertical_scroll,
+                &mut command_holder.horizontal_scroll,
+            );
+            if Self::handle_scroll_key(key_event.code, scroll, n_rows, max_line_length) {
+                return Ok(());
+            }
+            match key_event.code {
+                KeyCode::Char('q') => self.message_holder.close_command()?,
+                KeyCode::Esc => {
+                    if self.message_holder.cancel_command() {
+                        self.log_message = "Command stopped".into();
+                    } else {
+                        self.message_holder.close_command()?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn draw_help_normal_command_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "Command ".bold(),
+            " Scroll ".into(),
+            "<J/K/H/L>".light_blue().bold(),
+            " Top/Bottom ".into(),
+            "<Home/End>".light_blue().bold(),
+            " Stop ".into(),
+            "<Esc>".light_blue().bold(),
+            " Back ".into(),
+            "<Q>".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
@@ -0,0 +1,98 @@
+//! Event handling and rendering for Normal+ConfirmView mode
+//!
+//! This mode shows a popup asking to confirm a delete or a bulk rename, or
+//! how to paste over existing items. Deleted items go to the trash unless a
+//! permanent delete is asked for.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::app_error::AppResult;
+use crate::app::App;
+use crate::message_holder::dialog_holder::{Confirmation, ConflictChoice};
+
+impl App {
+    pub fn handle_normal_confirm_view_event(&mut self, event: Event) -> AppResult<()> {
+        let Event::Key(key_event) = event else {
+            return Ok(());
+        };
+        let pending_confirm = &self.message_holder.pending_confirm;
+        if let KeyCode::Char('n') | KeyCode::Esc = key_event.code {
+            self.log_message = match pending_confirm {
+                Some(Confirmation::Conflict(_)) => "Paste cancelled".into(),
+                Some(Confirmation::Rename(_)) => "Rename cancelled".into(),
+                _ => "Delete cancelled".into(),
+            };
+            self.state_holder.borrow_mut().pop_mode()?;
+            self.message_holder.cancel_confirm();
+            return Ok(());
+        }
+        let answer = match (pending_confirm, key_event.code) {
+            (Some(Confirmation::Delete(_)), KeyCode::Char('y') | KeyCode::Enter) => {
+                self.state_holder.borrow_mut().pop_mode()?;
+                self.message_holder.confirm_delete(false)
+            }
+            (Some(Confirmation::Delete(_)), KeyCode::Char('D')) => {
+                self.state_holder.borrow_mut().pop_mode()?;
+                self.message_holder.confirm_delete(true)
+            }
+            (Some(Confirmation::Conflict(_)), KeyCode::Char(key @ ('o' | 's' | 'k'))) => {
+                let choice = match key {
+                    'o' => ConflictChoice::Overwrite,
+                    's' => ConflictChoice::Skip,
+                    _ => ConflictChoice::KeepBoth,
+                };
+                self.state_holder.borrow_mut().pop_mode()?;
+                self.message_holder.resolve_conflict(choice)
+            }
+            (Some(Confirmation::Rename(_)), KeyCode::Char('y') | KeyCode::Enter) => {
+                self.state_holder.borrow_mut().pop_mode()?;
+                self.message_holder.confirm_rename()
+            }
+            _ => return Ok(()),
+        };
+        self.log_message = answer?;
+        Ok(())
+    }
+
+    pub fn draw_help_normal_confirm_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let answers = match self.message_holder.pending_confirm {
+            Some(Confirmation::Conflict(_)) => vec![
+                "Paste Conflict ".bold(),
+                " Overwrite ".into(),
+                "<O>".light_blue().bold(),
+                " Skip ".into(),
+                "<S>".light_blue().bold(),
+                " Keep Both ".into(),
+                "<K>".light_blue().bold(),
+            ],
+            Some(Confirmation::Rename(_)) => vec![
+                "Confirm ".bold(),
+                " Rename ".into(),
+                "<Y/Enter>".light_blue().bold(),
+            ],
+            _ => vec![
+                "Confirm ".bold(),
+                " Trash ".into(),
+                "<Y/Enter>".light_blue().bold(),
+                " Delete ".into(),
+                "<Shift+D>".light_blue().bold(),
+            ],
+        };
+        let instructions = Text::from(Line::from(
+            [
+                answers,
+                vec![" Cancel ".into(), "<N/Esc>".light_blue().bold()],
+            ]
+            .concat(),
+        ));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
@@ -0,0 +1,84 @@
+//! Event handling and rendering for Normal+FileHistoryView mode
+//!
+//! This mode lists the commits that touched the opened file and shows
+//! the file content or diff at a selected commit.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::app::App;
+
+impl App {
+    pub fn handle_normal_file_history_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            let file_history_holder = self
+                .message_holder
+                .file_history_holder
+                .as_mut()
+                .ok_or(AppError::State("File history is not opened".into()))?;
+
+            let Some(revision) = &file_history_holder.revision else {
+                match key_event.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        self.message_holder.close_file_history()?
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        file_history_holder.raw_highlight_index =
+                            file_history_holder.raw_highlight_index.saturating_sub(1);
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        file_history_holder.raw_highlight_index =
+                            file_history_holder.raw_highlight_index.saturating_add(1);
+                    }
+                    KeyCode::Char('d') => {
+                        file_history_holder.show_diff = true;
+                        self.message_holder.open_file_revision()?;
+                    }
+                    KeyCode::Enter => {
+                        file_history_holder.show_diff = false;
+                        self.message_holder.open_file_revision()?;
+                    }
+                    _ => (),
+                }
+                return Ok(());
+            };
+
+            let n_rows = revision.n_rows;
+            let max_line_length = revision.max_line_length;
+            let scroll = (
+                &mut file_history_holder.vertical_scroll,
+                &mut file_history_holder.horizontal_scroll,
+            );
+            if Self::handle_scroll_key(key_event.code, scroll, n_rows, max_line_length) {
+                return Ok(());
+            }
+            match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => file_history_holder.close_revision(),
+                KeyCode::Char('d') => self.message_holder.toggle_file_revision_diff()?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn draw_help_normal_file_history_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "FileHistory ".bold(),
+            " Show ".into(),
+            "<Enter>".light_blue().bold(),
+            " Content/Diff ".into(),
+            "<D>".light_blue().bold(),
+            " Back ".into(),
+            "<Q>".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
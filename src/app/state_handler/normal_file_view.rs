@@ -1,6 +1,9 @@
 //! Event handling and rendering for Normal+FileView mode
 //!
-//! This mode allows viewing file contents with scrolling.
+//! This mode allows viewing file contents with scrolling, editing them in
+//! the external editor or running a shell command on them, and following
+//! files as they grow. Scrolling pauses following. Log files can be
+//! filtered by level and time range.
 
 use ratatui::crossterm::event::{Event, KeyCode};
 use ratatui::{
@@ -10,6 +13,7 @@ use ratatui::{
     widgets::Paragraph,
     Frame,
 };
+use tui_input::Input;
 
 use crate::app::app_error::{AppError, AppResult};
 use crate::app::App;
@@ -17,91 +21,62 @@ use crate::app::App;
 impl App {
     pub fn handle_normal_file_view_event(&mut self, event: Event) -> AppResult<()> {
         if let Event::Key(key_event) = event {
+            if self.handle_jump_key(&key_event)? || self.handle_mark_key(key_event.code)? {
+                return Ok(());
+            }
             let file_text_info = self
                 .message_holder
                 .file_text_info
                 .as_ref()
                 .ok_or(AppError::Parse("Unexpected, file should be opened".into()))?;
+            let (n_rows, max_line_length) = (file_text_info.n_rows, file_text_info.max_line_length);
+            let message_holder = &mut self.message_holder;
+            let scroll = (
+                &mut message_holder.vertical_scroll,
+                &mut message_holder.horizontal_scroll,
+            );
+            if Self::handle_scroll_key(key_event.code, scroll, n_rows, max_line_length) {
+                message_holder.pause_follow();
+                message_holder.vertical_scroll_state = message_holder
+                    .vertical_scroll_state
+                    .position(message_holder.vertical_scroll);
+                message_holder.horizontal_scroll_state = message_holder
+                    .horizontal_scroll_state
+                    .position(message_holder.horizontal_scroll);
+                return Ok(());
+            }
             match key_event.code {
-                KeyCode::Char('q') => {
-                    self.message_holder.reset_file_view();
-                    self.state_holder.borrow_mut().restore_previous_state();
-                }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.message_holder.vertical_scroll = self
-                        .message_holder
-                        .vertical_scroll
-                        .saturating_add(1)
-                        .min(file_text_info.n_rows);
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
-                }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.message_holder.vertical_scroll =
-                        self.message_holder.vertical_scroll.saturating_sub(1);
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
-                }
-                KeyCode::Char('h') | KeyCode::Left => {
-                    self.message_holder.horizontal_scroll =
-                        self.message_holder.horizontal_scroll.saturating_sub(1);
-                    self.message_holder.horizontal_scroll_state = self
-                        .message_holder
-                        .horizontal_scroll_state
-                        .position(self.message_holder.horizontal_scroll);
-                }
-                KeyCode::Char('l') | KeyCode::Right => {
-                    self.message_holder.horizontal_scroll = self
-                        .message_holder
-                        .horizontal_scroll
-                        .saturating_add(1)
-                        .min(file_text_info.max_line_length);
-                    self.message_holder.horizontal_scroll_state = self
-                        .message_holder
-                        .horizontal_scroll_state
-                        .position(self.message_holder.horizontal_scroll);
-                }
-                KeyCode::Home => {
-                    self.message_holder.horizontal_scroll = 0;
-                    self.message_holder.horizontal_scroll_state = self
-                        .message_holder
-                        .horizontal_scroll_state
-                        .position(self.message_holder.horizontal_scroll);
-                    self.message_holder.vertical_scroll = 0;
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
+                KeyCode::Char('q') => self.message_holder.close_file_view()?,
+                KeyCode::Char('F') => self.message_holder.toggle_follow()?,
+                KeyCode::Char('L') => self.message_holder.toggle_log_view()?,
+                KeyCode::Char('v') => self.message_holder.cycle_log_level()?,
+                KeyCode::Char('t') => {
+                    let log_holder =
+                        self.message_holder
+                            .log_holder
+                            .as_ref()
+                            .ok_or(AppError::State(
+                                "Log view is off, toggle it with <Shift+L>".into(),
+                            ))?;
+                    let time_range = log_holder.time_range;
+                    if !time_range.is_empty() {
+                        self.input = Input::new(time_range.to_string());
+                    }
+                    self.state_holder.borrow_mut().to_log_filter()?;
                 }
-                KeyCode::End => {
-                    self.message_holder.vertical_scroll = file_text_info.n_rows.saturating_sub(30);
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
+                KeyCode::Char('b') => self.message_holder.toggle_blame()?,
+                KeyCode::Char('e') => {
+                    self.pending_editor = Some(self.message_holder.open_in_editor()?);
                 }
-                KeyCode::PageDown => {
-                    self.message_holder.vertical_scroll = self
-                        .message_holder
-                        .vertical_scroll
-                        .saturating_add(30)
-                        .min(file_text_info.n_rows);
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
+                KeyCode::Char('g') => self.message_holder.open_file_history()?,
+                KeyCode::Char('/') => {
+                    self.restore_filter_input();
+                    self.state_holder.borrow_mut().to_search_edit()?;
                 }
-                KeyCode::PageUp => {
-                    self.message_holder.vertical_scroll =
-                        self.message_holder.vertical_scroll.saturating_sub(30);
-                    self.message_holder.vertical_scroll_state = self
-                        .message_holder
-                        .vertical_scroll_state
-                        .position(self.message_holder.vertical_scroll);
+                KeyCode::Char('?') | KeyCode::F(1) => self.state_holder.borrow_mut().to_help()?,
+                KeyCode::Char('!') => {
+                    self.message_holder.start_command()?;
+                    self.input.reset();
                 }
                 _ => (),
             }
@@ -109,12 +84,35 @@ impl App {
         Ok(())
     }
     pub fn draw_help_normal_file_view(&mut self, help_area: Rect, frame: &mut Frame) {
-        let instructions = Text::from(Line::from(vec![
+        let instructions = Text::from(Self::help_line_normal_file_view());
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+
+    /// Returns the key bindings of the mode, also listed by the help view
+    pub fn help_line_normal_file_view() -> Line<'static> {
+        Line::from(vec![
             "FileView ".bold(),
             " Quit ".into(),
             "<Q>".light_blue().bold(),
-        ]));
-        let help_message = Paragraph::new(instructions);
-        frame.render_widget(help_message, help_area);
+            " Search ".into(),
+            "</>".light_blue().bold(),
+            " Help ".into(),
+            "<?>".light_blue().bold(),
+            " Edit/Command ".into(),
+            "<E/!>".light_blue().bold(),
+            " Blame ".into(),
+            "<B>".light_blue().bold(),
+            " Git History ".into(),
+            "<G>".light_blue().bold(),
+            " Follow ".into(),
+            "<Shift+F>".light_blue().bold(),
+            " Log/Level/Time ".into(),
+            "<Shift+L/V/T>".light_blue().bold(),
+            " Mark/Jump/Marks ".into(),
+            "<M/'/Shift+M>".light_blue().bold(),
+            " Back/Forward ".into(),
+            "<CTRL+O/CTRL+I or CTRL+N>".light_blue().bold(),
+        ])
     }
 }
@@ -0,0 +1,64 @@
+//! Event handling and rendering for Normal+HelpView mode
+//!
+//! This mode lists every key binding of the mode it was opened from, as the
+//! help line is cut at the width of the terminal.
+
+use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::app::App;
+use crate::state_holder::{InputMode, ViewMode};
+
+impl App {
+    pub fn handle_normal_help_view_event(&mut self, event: Event) -> AppResult<()> {
+        if let Event::Key(key_event) = event {
+            if let KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::Esc | KeyCode::F(1) =
+                key_event.code
+            {
+                self.state_holder.borrow_mut().pop_mode()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the key bindings of the mode the help was opened from
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// help was not opened from a mode that has one
+    pub fn draw_normal_help_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        use InputMode::*;
+        use ViewMode::*;
+        let (title, keys) = match self.state_holder.borrow().previous_mode() {
+            Some((Normal, Search)) => ("Normal", Self::help_line_normal_search()),
+            Some((Edit, Search)) => ("FileSearch", Self::help_line_edit_search()),
+            Some((Normal, FileView)) => ("FileView", Self::help_line_normal_file_view()),
+            mode => {
+                return Err(AppError::State(format!("No help for mode {:?}", mode)));
+            }
+        };
+        let help = Paragraph::new(keys)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().title(format!("Keys of {}", title)));
+        frame.render_widget(help, area);
+        Ok(())
+    }
+
+    pub fn draw_help_normal_help_view(&mut self, help_area: Rect, frame: &mut Frame) {
+        let instructions = Text::from(Line::from(vec![
+            "Help ".bold(),
+            " Back ".into(),
+            "<Q/Esc>".light_blue().bold(),
+        ]));
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+}
@@ -10,22 +10,74 @@ use ratatui::{
     widgets::Paragraph,
     Frame,
 };
+use tui_input::Input;
 
 use crate::app::app_error::AppResult;
 use crate::app::App;
+use crate::message_holder::transfer_holder::TransferMode;
 
 impl App {
     pub fn handle_normal_search_event(&mut self, event: Event) -> AppResult<()> {
         if let Event::Key(key_event) = event {
+            if self.handle_depth_key(key_event.code)?
+                || self.handle_jump_key(&key_event)?
+                || self.handle_mark_key(key_event.code)?
+            {
+                return Ok(());
+            }
             match key_event.code {
                 KeyCode::Char('u') => self.message_holder.refresh_current_folder_cache()?,
-                KeyCode::Char('h') => {
-                    self.state_holder.borrow_mut().to_history_search();
-                    self.message_holder.reset()?;
-                }
+                KeyCode::Char('H') => self.message_holder.open_history_search()?,
+                KeyCode::Char('l') | KeyCode::Right => self.message_holder.expand_selected()?,
+                KeyCode::Char('h') | KeyCode::Left => self.message_holder.collapse_selected()?,
                 KeyCode::Char('e') => self.message_holder.expand()?,
+                KeyCode::Char('E') => self.pending_key = Some('E'),
                 KeyCode::Char('c') => self.message_holder.collapse()?,
-                KeyCode::Tab => self.state_holder.borrow_mut().to_search_edit(),
+                KeyCode::Char('C') => self.message_holder.toggle_columns(),
+                KeyCode::Char('s') => self.message_holder.cycle_sort_key()?,
+                KeyCode::Char('S') => self.message_holder.toggle_sort_reverse()?,
+                KeyCode::Char('D') => self.message_holder.toggle_directories_first()?,
+                KeyCode::Char('U') => self.log_message = self.message_holder.undo()?,
+                KeyCode::Char('r') if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(name) = self.message_holder.start_rename()? {
+                        self.input = Input::new(name);
+                    }
+                }
+                KeyCode::Char('R') => {
+                    self.pending_editor = Some(self.message_holder.start_bulk_rename()?);
+                }
+                KeyCode::Char('o') => {
+                    self.pending_editor = Some(self.message_holder.open_in_editor()?);
+                }
+                KeyCode::Char('!') => {
+                    self.message_holder.start_command()?;
+                    self.input.reset();
+                }
+                KeyCode::Char('a') => {
+                    self.message_holder.start_create(false)?;
+                    self.input.reset();
+                }
+                KeyCode::Char('A') => {
+                    self.message_holder.start_create(true)?;
+                    self.input.reset();
+                }
+                KeyCode::Char('y') => {
+                    self.log_message = self.message_holder.yank(TransferMode::Copy)?;
+                }
+                KeyCode::Char('x') => {
+                    self.log_message = self.message_holder.yank(TransferMode::Move)?;
+                }
+                KeyCode::Char('Y') => self.log_message = self.message_holder.yank_paths()?,
+                KeyCode::Char('p') => self.log_message = self.message_holder.paste()?,
+                KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.log_message = self.message_holder.redo()?;
+                }
+                KeyCode::Tab => self.state_holder.borrow_mut().to_search_edit()?,
+                KeyCode::Esc => self.cancel_or_clear_selection()?,
+                KeyCode::Char('?') | KeyCode::F(1) => self.state_holder.borrow_mut().to_help()?,
+                KeyCode::Char(' ') => self.message_holder.toggle_selected()?,
+                KeyCode::Char('V') => self.message_holder.select_all(),
+                KeyCode::Char('v') => self.message_holder.invert_selection(),
                 KeyCode::Char('k') | KeyCode::Up => {
                     if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                         self.message_holder.to_parent()?;
@@ -35,15 +87,17 @@ impl App {
                 }
                 KeyCode::Char('j') | KeyCode::Down => self.message_holder.move_down(),
                 KeyCode::Enter => {
+                    // directories inside the tree toggle, the others are entered
+                    if self.message_holder.toggle_selected_node()? {
+                        return Ok(());
+                    }
                     self.message_holder.submit()?;
                     if !self.state_holder.borrow().is_file_view() {
                         self.input.reset();
                     }
                 }
-                KeyCode::Char('d') => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.message_holder.delete()?;
-                    }
+                KeyCode::Char('d') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.message_holder.delete()?;
                 }
 
                 _ => {}
@@ -53,27 +107,89 @@ impl App {
         Ok(())
     }
 
+    /// Cancels the background work, or clears the selection if there is none
+    ///
+    /// With neither, goes back to the file the search was opened from.
+    fn cancel_or_clear_selection(&mut self) -> AppResult<()> {
+        if self.cancel_loading() {
+            return Ok(());
+        }
+        if self.message_holder.clear_selection() {
+            self.log_message = "Selection cleared".into();
+        } else if self.message_holder.close_nested_search()? {
+            self.input.reset();
+        }
+        Ok(())
+    }
+
+    /// Handles the digit following `E`, expanding the listing to that depth
+    fn handle_depth_key(&mut self, key_code: KeyCode) -> AppResult<bool> {
+        if self.pending_key != Some('E') {
+            return Ok(false);
+        }
+        self.pending_key = None;
+        if let KeyCode::Char(digit) = key_code {
+            if let Some(depth) = digit.to_digit(10) {
+                self.message_holder.expand_to_depth(depth as usize)?;
+            }
+        }
+        Ok(true)
+    }
+
     pub fn draw_help_normal_search(&mut self, help_area: Rect, frame: &mut Frame) {
-        let instructions = Text::from(Line::from(vec![
+        let instructions = Text::from(Self::help_line_normal_search());
+        let help_message = Paragraph::new(instructions);
+        frame.render_widget(help_message, help_area);
+    }
+
+    /// Returns the key bindings of the mode, also listed by the help view
+    pub fn help_line_normal_search() -> Line<'static> {
+        Line::from(vec![
             "Normal ".bold(),
             "Switch to".into(),
             " FileSearch ".bold(),
             "<Tab>".light_blue().bold(),
             " Update ".into(),
             "<U>".light_blue().bold(),
-            " Expand ".into(),
-            "<E>".light_blue().bold(),
+            " Tree Open/Close ".into(),
+            "<L/H or Enter in Tree>".light_blue().bold(),
+            " Enter Dir ".into(),
+            "<Enter>".light_blue().bold(),
+            " Expand/To Depth ".into(),
+            "<E/Shift+E 0-9>".light_blue().bold(),
             " Collapse ".into(),
             "<C>".light_blue().bold(),
+            " Columns ".into(),
+            "<Shift+C>".light_blue().bold(),
+            " Cancel Load/Selection ".into(),
+            "<Esc>".light_blue().bold(),
+            " Help ".into(),
+            "<?>".light_blue().bold(),
+            " Sort ".into(),
+            "<S/Shift+S/Shift+D>".light_blue().bold(),
+            " Mark/Jump/Marks ".into(),
+            "<M/'/Shift+M>".light_blue().bold(),
+            " Select/All/Invert ".into(),
+            "<Space/Shift+V/V>".light_blue().bold(),
             " Delete ".into(),
             "<CTRL+D>".light_blue().bold(),
+            " Undo/Redo ".into(),
+            "<Shift+U/CTRL+R>".light_blue().bold(),
+            " Rename/Bulk/New File/Dir ".into(),
+            "<R/Shift+R/A/Shift+A>".light_blue().bold(),
+            " Copy/Cut/Paste ".into(),
+            "<Y/X/P>".light_blue().bold(),
+            " Copy Paths ".into(),
+            "<Shift+Y>".light_blue().bold(),
+            " Open in Editor/Command ".into(),
+            "<O/!>".light_blue().bold(),
             " To Parent ".into(),
             "<CTRL+K>".light_blue().bold(),
+            " Back/Forward ".into(),
+            "<CTRL+O/CTRL+I or CTRL+N>".light_blue().bold(),
             " Switch to ".into(),
             "FileSearchHistory ".bold(),
-            "<H>".light_blue().bold(),
-        ]));
-        let help_message = Paragraph::new(instructions);
-        frame.render_widget(help_message, help_area);
+            "<Shift+H>".light_blue().bold(),
+        ])
     }
 }
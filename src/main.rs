@@ -1,13 +1,87 @@
 use athena_viewer::app;
+use athena_viewer::message_holder::bookmark_holder::BOOKMARK_FILE_NAME;
+use athena_viewer::message_holder::history_store::{self, HistoryStore};
+use ratatui::crossterm::event::{
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use ratatui::crossterm::{execute, terminal};
 use std::env;
+use std::io::stdout;
+use std::process::ExitCode;
 
-fn main() -> app::app_error::AppResult<()> {
-    let mut terminal = ratatui::init();
+/// Command line flag printing the best history match for a query
+const JUMP_FLAG: &str = "--jump";
+
+fn main() -> app::app_error::AppResult<ExitCode> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(position) = args.iter().position(|arg| arg == JUMP_FLAG) {
+        let query = args
+            .get(position + 1)
+            .ok_or(app::app_error::AppError::Parse(format!(
+                "{} expects a query",
+                JUMP_FLAG
+            )))?;
+        return jump(query);
+    }
 
     let current_directory = env::current_dir().map_err(|_| {
         app::app_error::AppError::Path("Unable to get current working directory".into())
     })?;
-    let app_result = app::App::new(current_directory)?.run(&mut terminal);
+    let mut app = app::App::new(current_directory)?;
+    // a broken state file should not keep the viewer from starting
+    let history_result = HistoryStore::from_env().and_then(|store| app.load_history(store));
+    if let Err(error) = history_result {
+        warn(&mut app, format!("History not loaded: {}", error));
+    }
+    let bookmark_result = history_store::data_dir()
+        .and_then(|data_dir| app.load_bookmarks(data_dir.join(BOOKMARK_FILE_NAME)));
+    if let Err(error) = bookmark_result {
+        warn(&mut app, format!("Marks not loaded: {}", error));
+    }
+    if let Err(error) = app.watch_files() {
+        warn(
+            &mut app,
+            format!("File watching disabled, <U> to refresh: {}", error),
+        );
+    }
+
+    let mut terminal = ratatui::init();
+    // lets terminals that support it report Ctrl+I apart from Tab
+    let keyboard_enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false)
+        && execute!(
+            stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )
+        .is_ok();
+    app.keyboard_enhanced = keyboard_enhanced;
+    let app_result = app.run(&mut terminal);
+    if keyboard_enhanced {
+        let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+    }
     ratatui::restore();
-    app_result
+    app_result.map(|_| ExitCode::SUCCESS)
+}
+
+/// Prints the directory from history ranking best for `query`
+///
+/// Meant for shell integration, e.g. `cd "$(athena_viewer --jump proj)"`.
+/// Exits with failure when nothing matches.
+fn jump(query: &str) -> app::app_error::AppResult<ExitCode> {
+    let entries = HistoryStore::from_env()?.load()?;
+    match history_store::best_match(&entries, query) {
+        Some(entry) => {
+            println!("{}", entry.path.display());
+            Ok(ExitCode::SUCCESS)
+        }
+        None => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// Shows a startup problem in the log area, after the previous ones
+fn warn(app: &mut app::App, message: String) {
+    if app.log_message.is_empty() {
+        app.log_message = message;
+    } else {
+        app.log_message = format!("{}, {}", app.log_message, message);
+    }
 }
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::filter_helper::matches_filter;
+use crate::message_holder::history_store::write_atomically;
+
+/// Name of the bookmark state file
+pub const BOOKMARK_FILE_NAME: &str = "bookmarks";
+
+/// A named mark on a directory, or on a file with its scroll position
+///
+/// # Fields
+///
+/// - `path`: Marked directory or file
+/// - `vertical_scroll`: Vertical scroll position (files only)
+/// - `horizontal_scroll`: Horizontal scroll position (files only)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub path: PathBuf,
+    pub vertical_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+/// Holds vim-style marks and the bookmark picker selection
+///
+/// # Fields
+///
+/// - `marks`: Bookmarks by letter
+/// - `store_path`: State file marks are persisted to (if any)
+/// - `scope`: Directory marks must be inside to be picked or jumped to
+/// - `input`: Current picker filter string
+/// - `selected_mark_holder`: Filtered list of mark letters shown in the picker
+/// - `raw_highlight_index`: Current picker selection (before wrapping)
+#[derive(Debug, Default)]
+pub struct BookmarkHolder {
+    marks: BTreeMap<char, Bookmark>,
+    store_path: Option<PathBuf>,
+    scope: PathBuf,
+    pub input: String,
+    pub selected_mark_holder: Vec<char>,
+    pub raw_highlight_index: i32,
+}
+
+impl BookmarkHolder {
+    /// Loads marks from a state file, saving back to it on every change
+    ///
+    /// A missing state file yields no marks. Malformed lines are skipped.
+    /// Marks outside `scope` are kept for saving, but hidden from the picker.
+    ///
+    /// # Arguments
+    ///
+    /// * `store_path` - Location of the state file
+    /// * `scope` - Directory marks must be inside to be used
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Io` if the state
+    /// file exists but cannot be read
+    pub fn load(store_path: PathBuf, scope: &Path) -> AppResult<Self> {
+        let mut marks = BTreeMap::new();
+        if store_path.exists() {
+            let content = fs::read_to_string(&store_path)?;
+            marks.extend(content.lines().filter_map(Self::parse_line));
+        }
+        let mut holder = Self {
+            marks,
+            store_path: Some(store_path),
+            scope: scope.to_path_buf(),
+            ..Default::default()
+        };
+        holder.update(None);
+        Ok(holder)
+    }
+
+    /// Sets a mark, replacing any previous mark with the same letter
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Mark letter (ASCII alphabetic)
+    /// * `bookmark` - Marked location
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If `name` is not a letter
+    /// - `AppError::Path`: If the location is outside the scope
+    /// - `AppError::Io`: If the state file cannot be written
+    pub fn set(&mut self, name: char, bookmark: Bookmark) -> AppResult<()> {
+        if !name.is_ascii_alphabetic() {
+            return Err(AppError::Parse(format!("Invalid mark name {:?}", name)));
+        }
+        if !bookmark.path.starts_with(&self.scope) {
+            return Err(AppError::Path(format!(
+                "Cannot mark {:?} as it is not child of {:?}",
+                bookmark.path, self.scope
+            )));
+        }
+        self.marks.insert(name, bookmark);
+        self.update(None);
+        self.save()
+    }
+
+    /// Gets the mark with a given letter
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<&Bookmark>` which may contain:
+    /// - `AppError::State`: If the mark is not set
+    /// - `AppError::Path`: If the mark is outside the scope
+    pub fn get(&self, name: char) -> AppResult<&Bookmark> {
+        let bookmark = self
+            .marks
+            .get(&name)
+            .ok_or(AppError::State(format!("Mark {:?} is not set", name)))?;
+        if !bookmark.path.starts_with(&self.scope) {
+            return Err(AppError::Path(format!(
+                "Mark {:?} points to {:?}, outside of {:?}",
+                name, bookmark.path, self.scope
+            )));
+        }
+        Ok(bookmark)
+    }
+
+    /// Updates the picker selection based on the filter
+    ///
+    /// The selection is reset when the filter changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Optional filter string, matched against letter and path
+    pub fn update(&mut self, input: Option<String>) {
+        if let Some(value) = input {
+            self.input = value;
+            self.raw_highlight_index = 0;
+        }
+        self.selected_mark_holder = self
+            .marks
+            .iter()
+            .filter(|(name, bookmark)| {
+                bookmark.path.starts_with(&self.scope)
+                    && matches_filter(
+                        &format!("{} {}", name, bookmark.path.display()),
+                        &self.input,
+                    )
+            })
+            .map(|(name, _)| *name)
+            .collect();
+    }
+
+    /// Returns the picker selection index wrapped within bounds
+    pub fn highlight_index(&self) -> usize {
+        self.raw_highlight_index
+            .rem_euclid(self.selected_mark_holder.len().max(1) as i32) as usize
+    }
+
+    /// Returns the letter of the selected mark in the picker
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<char>` which may contain `AppError::State` if no
+    /// mark matches the filter
+    pub fn selected(&self) -> AppResult<char> {
+        self.selected_mark_holder
+            .get(self.highlight_index())
+            .copied()
+            .ok_or(AppError::State("No mark selected".into()))
+    }
+
+    /// Formats a mark for display in the picker
+    pub fn describe(&self, name: char) -> AppResult<String> {
+        let bookmark = self.get(name)?;
+        if bookmark.path.is_file() {
+            Ok(format!(
+                "{}  {}:{}",
+                name,
+                bookmark.path.display(),
+                bookmark.vertical_scroll + 1
+            ))
+        } else {
+            Ok(format!("{}  {}", name, bookmark.path.display()))
+        }
+    }
+
+    fn save(&self) -> AppResult<()> {
+        let Some(store_path) = &self.store_path else {
+            return Ok(());
+        };
+        let content: String = self
+            .marks
+            .iter()
+            .filter_map(|(name, bookmark)| Self::format_line(*name, bookmark))
+            .collect();
+        write_atomically(store_path, &content)
+    }
+
+    fn parse_line(line: &str) -> Option<(char, Bookmark)> {
+        let mut fields = line.split('\t');
+        let mut name_chars = fields.next()?.chars();
+        let name = name_chars.next().filter(|c| c.is_ascii_alphabetic())?;
+        if name_chars.next().is_some() {
+            return None;
+        }
+        let path = PathBuf::from(fields.next().filter(|path| !path.is_empty())?);
+        let vertical_scroll = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        let horizontal_scroll = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        Some((
+            name,
+            Bookmark {
+                path,
+                vertical_scroll,
+                horizontal_scroll,
+            },
+        ))
+    }
+
+    fn format_line(name: char, bookmark: &Bookmark) -> Option<String> {
+        let path = bookmark.path.to_str()?;
+        if path.contains(['\t', '\n']) {
+            return None;
+        }
+        Some(format!(
+            "{}\t{}\t{}\t{}\n",
+            name, path, bookmark.vertical_scroll, bookmark.horizontal_scroll
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn bookmark(path: &str) -> Bookmark {
+        Bookmark {
+            path: PathBuf::from(path),
+            vertical_scroll: 12,
+            horizontal_scroll: 3,
+        }
+    }
+
+    #[test]
+    fn test_set_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(BOOKMARK_FILE_NAME);
+
+        let mut holder = BookmarkHolder::load(store_path.clone(), Path::new("/tmp")).unwrap();
+        holder.set('b', bookmark("/tmp/b")).unwrap();
+        holder.set('a', bookmark("/tmp/a")).unwrap();
+        assert!(holder.set('1', bookmark("/tmp/1")).is_err());
+
+        let holder = BookmarkHolder::load(store_path, Path::new("/tmp")).unwrap();
+        assert_eq!(holder.selected_mark_holder, vec!['a', 'b']);
+        assert_eq!(holder.get('a').unwrap(), &bookmark("/tmp/a"));
+        assert!(holder.get('c').is_err());
+    }
+
+    #[test]
+    fn test_marks_outside_scope_kept_but_hidden() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join(BOOKMARK_FILE_NAME);
+        fs::write(&store_path, "a\t/tmp/a\t0\t0\nb\t/srv/b\t0\t0\n").unwrap();
+
+        let mut holder = BookmarkHolder::load(store_path.clone(), Path::new("/tmp")).unwrap();
+        assert_eq!(holder.selected_mark_holder, vec!['a']);
+        assert!(matches!(holder.get('b'), Err(AppError::Path(_))));
+        assert!(holder.set('c', bookmark("/srv/c")).is_err());
+
+        holder.set('a', bookmark("/tmp/a")).unwrap();
+        let holder = BookmarkHolder::load(store_path, Path::new("/srv")).unwrap();
+        assert_eq!(holder.selected_mark_holder, vec!['b']);
+    }
+
+    #[test]
+    fn test_update_filters() {
+        let mut holder = BookmarkHolder::default();
+        holder.set('a', bookmark("/tmp/project")).unwrap();
+        holder.set('b', bookmark("/tmp/logs")).unwrap();
+        holder.update(Some("log".into()));
+        assert_eq!(holder.selected_mark_holder, vec!['b']);
+        holder.update(Some("".into()));
+        assert_eq!(holder.selected_mark_holder, vec!['a', 'b']);
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::Write;
+
+use crate::app::app_error::{AppError, AppResult};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the OSC 52 escape sequence setting the terminal clipboard
+///
+/// Terminals supporting OSC 52 copy the text to the system clipboard,
+/// including over ssh. The others ignore the sequence.
+///
+/// # Arguments
+///
+/// * `text` - Text to copy
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Copies text to the terminal clipboard
+///
+/// # Arguments
+///
+/// * `writer` - Output of the terminal
+/// * `text` - Text to copy
+///
+/// # Returns
+///
+/// Returns `AppResult<()>` which may contain `AppError::Io` if the terminal
+/// cannot be written
+pub fn copy_to_clipboard(writer: &mut impl Write, text: &str) -> AppResult<()> {
+    writer
+        .write_all(osc52_sequence(text).as_bytes())
+        .and_then(|_| writer.flush())
+        .map_err(AppError::Io)
+}
+
+/// Encodes bytes as padded standard base64
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | (byte as u32) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"/tmp/a\n/tmp/b"), "L3RtcC9hCi90bXAvYg==");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("foo"), "\x1b]52;c;Zm9v\x07");
+    }
+}
@@ -0,0 +1,447 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::app::app_error::{AppError, AppResult};
+
+/// Shell running the commands
+pub const SHELL: &str = "sh";
+
+/// Prefix of a command run with the terminal, instead of capturing its output
+pub const INTERACTIVE_PREFIX: char = '!';
+
+/// Maximum number of output lines kept, the first ones are dropped
+pub const MAX_OUTPUT_LINES: usize = 10_000;
+
+/// Time the output is still waited for once the shell exited, a process
+/// left in the background may hold the pipes open
+pub const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+/// A shell command with its placeholders expanded
+///
+/// # Fields
+///
+/// - `command`: Command line given to the shell
+/// - `directory`: Directory the command runs in
+/// - `affected`: Paths given to the command and the current directory,
+///   refreshed once it exits
+/// - `interactive`: Whether the command runs with the terminal
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellCommand {
+    pub command: String,
+    pub directory: PathBuf,
+    pub affected: Vec<PathBuf>,
+    pub interactive: bool,
+}
+
+impl ShellCommand {
+    /// Expands the placeholders of a typed command
+    ///
+    /// `%s` is replaced by the selected paths, `%d` by the current
+    /// directory, `%l` by the current line of the opened file and `%%` by
+    /// `%`. Paths are quoted for the shell. A command starting with `!`
+    /// runs with the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed` - Command typed in the prompt
+    /// * `paths` - Selected paths
+    /// * `directory` - Current directory
+    /// * `line` - Current line of the opened file (if any)
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::State` if the
+    /// command is empty or uses a placeholder that has no value
+    pub fn new(
+        typed: &str,
+        paths: &[PathBuf],
+        directory: &Path,
+        line: Option<usize>,
+    ) -> AppResult<Self> {
+        let (interactive, typed) = match typed.trim().strip_prefix(INTERACTIVE_PREFIX) {
+            Some(rest) => (true, rest.trim()),
+            None => (false, typed.trim()),
+        };
+        if typed.is_empty() {
+            return Err(AppError::State("No command to run".into()));
+        }
+        let mut command = String::new();
+        let mut affected = Vec::new();
+        let mut chars = typed.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                command.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('s') if !paths.is_empty() => {
+                    let quoted: Vec<String> = paths.iter().map(|path| quote(path)).collect();
+                    command.push_str(&quoted.join(" "));
+                    affected.extend(paths.iter().cloned());
+                }
+                Some('s') => return Err(AppError::State("%s needs a selected entry".into())),
+                Some('d') => command.push_str(&quote(directory)),
+                Some('l') => {
+                    let line = line.ok_or(AppError::State("%l needs an opened file".into()))?;
+                    command.push_str(&line.to_string());
+                }
+                Some('%') => command.push('%'),
+                Some(other) => {
+                    command.push('%');
+                    command.push(other);
+                }
+                None => command.push('%'),
+            }
+        }
+        affected.push(directory.to_path_buf());
+        Ok(Self {
+            command,
+            directory: directory.to_path_buf(),
+            affected,
+            interactive,
+        })
+    }
+
+    /// Runs the command with the terminal, then waits for Enter
+    ///
+    /// The terminal must be restored before, the output stays visible
+    /// until Enter is pressed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<ExitStatus>` which may contain `AppError::Io` if
+    /// the shell cannot be run
+    pub fn run_interactive(&self) -> AppResult<ExitStatus> {
+        let status = Command::new(SHELL)
+            .arg("-c")
+            .arg(&self.command)
+            .current_dir(&self.directory)
+            .status()?;
+        print!("\n[{}] Press Enter to continue", describe_status(status));
+        io::stdout().flush()?;
+        io::stdin().lock().read_line(&mut String::new())?;
+        Ok(status)
+    }
+}
+
+/// Quotes a path for the shell
+///
+/// # Arguments
+///
+/// * `path` - Path to quote
+pub fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+/// Describes how a command ended, its exit code or the signal that stopped it
+///
+/// # Arguments
+///
+/// * `status` - Exit status of the command
+pub fn describe_status(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("exit code {}", code),
+        None => status.to_string(),
+    }
+}
+
+/// Runs a shell command in the background, capturing its output
+///
+/// Standard output and error are read on their own threads and shown in
+/// the order they arrive. The shell runs in its own process group, so
+/// stopping it stops every process of a pipeline. Dropping the holder
+/// kills the command.
+///
+/// # Fields
+///
+/// - `command`: The command running
+/// - `child`: Shell process
+/// - `receiver`: Output lines sent by the reader threads
+/// - `lines`: Output read so far
+/// - `max_line_length`: Length of the longest output line
+/// - `status`: Exit status, once exited
+/// - `exit_time`: When the shell exited (if it did)
+/// - `output_closed`: Whether the output pipes were closed, or no longer waited for
+/// - `vertical_scroll`: Vertical scroll position within the output
+/// - `horizontal_scroll`: Horizontal scroll position within the output
+#[derive(Debug)]
+pub struct CommandHolder {
+    pub command: ShellCommand,
+    child: Child,
+    receiver: Receiver<String>,
+    pub lines: Vec<String>,
+    pub max_line_length: usize,
+    pub status: Option<ExitStatus>,
+    exit_time: Option<Instant>,
+    output_closed: bool,
+    pub vertical_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl CommandHolder {
+    /// Starts running a command
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - Command to run
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Io` if the
+    /// shell cannot be run
+    pub fn start(command: ShellCommand) -> AppResult<Self> {
+        let mut shell = Command::new(SHELL);
+        shell
+            .arg("-c")
+            .arg(&command.command)
+            .current_dir(&command.directory)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+        let mut child = shell.spawn()?;
+        let (sender, receiver) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            Self::forward(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            Self::forward(stderr, sender);
+        }
+        Ok(Self {
+            command,
+            child,
+            receiver,
+            lines: Vec::new(),
+            max_line_length: 0,
+            status: None,
+            exit_time: None,
+            output_closed: false,
+            vertical_scroll: 0,
+            horizontal_scroll: 0,
+        })
+    }
+
+    /// Sends the lines of a pipe until it is closed
+    fn forward(pipe: impl Read + Send + 'static, sender: Sender<String>) {
+        thread::spawn(move || {
+            let mut reader = BufReader::new(pipe);
+            let mut buffer = Vec::new();
+            while let Ok(n_bytes) = reader.read_until(b'\n', &mut buffer) {
+                if n_bytes == 0 {
+                    return;
+                }
+                let line = String::from_utf8_lossy(&buffer);
+                let line = line.trim_end_matches(['\n', '\r']).replace('\t', "    ");
+                if sender.send(line).is_err() {
+                    return;
+                }
+                buffer.clear();
+            }
+        });
+    }
+
+    /// Reads the output sent since the last call
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<ExitStatus>>`, the exit status once when the
+    /// command exits, which may contain `AppError::Io` if the process cannot
+    /// be waited for
+    pub fn poll(&mut self) -> AppResult<Option<ExitStatus>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(line) => {
+                    self.max_line_length = self.max_line_length.max(line.chars().count());
+                    self.lines.push(line);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.output_closed = true;
+                    break;
+                }
+            }
+        }
+        if self.lines.len() > MAX_OUTPUT_LINES {
+            let n_dropped = self.lines.len() - MAX_OUTPUT_LINES;
+            self.lines.drain(..n_dropped);
+            // the lines shown stay in place
+            self.vertical_scroll = self.vertical_scroll.saturating_sub(n_dropped);
+        }
+        if self
+            .exit_time
+            .is_some_and(|exit_time| exit_time.elapsed() >= OUTPUT_GRACE)
+        {
+            self.output_closed = true;
+        }
+        if self.status.is_some() {
+            return Ok(None);
+        }
+        self.status = self.child.try_wait()?;
+        if self.status.is_some() {
+            self.exit_time = Some(Instant::now());
+        }
+        Ok(self.status)
+    }
+
+    /// Checks if the command exited and all of its output was read, or
+    /// `OUTPUT_GRACE` passed since it exited
+    pub fn is_done(&self) -> bool {
+        self.status.is_some() && self.output_closed
+    }
+
+    /// Checks if the command is still running
+    pub fn is_running(&self) -> bool {
+        self.status.is_none()
+    }
+
+    /// Kills the command and the processes it started, unless it is done
+    ///
+    /// # Returns
+    ///
+    /// Returns whether the command was running
+    pub fn cancel(&mut self) -> bool {
+        !self.is_done() && self.kill().is_ok()
+    }
+
+    #[cfg(unix)]
+    fn kill(&mut self) -> io::Result<()> {
+        let Ok(pid) = libc::pid_t::try_from(self.child.id()) else {
+            return self.child.kill();
+        };
+        // the shell leads its group, a negative pid signals the whole group
+        // SAFETY: kill only sends a signal, it does not touch memory
+        if unsafe { libc::kill(-pid, libc::SIGKILL) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Describes the command and its state, for the view title
+    pub fn title(&self) -> String {
+        match self.status {
+            None => format!("$ {} (running, <Esc> to stop)", self.command.command),
+            Some(status) => format!("$ {} ({})", self.command.command, describe_status(status)),
+        }
+    }
+}
+
+impl Drop for CommandHolder {
+    fn drop(&mut self) {
+        if !self.is_done() {
+            let _ = self.kill();
+        }
+        if self.is_running() {
+            let _ = self.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_placeholders() {
+        let paths = vec![PathBuf::from("/tmp/a b.txt"), PathBuf::from("/tmp/it's")];
+        let command =
+            ShellCommand::new("wc -l %s %d +%l 100%%", &paths, Path::new("/tmp"), Some(3)).unwrap();
+        assert_eq!(
+            command.command,
+            r"wc -l '/tmp/a b.txt' '/tmp/it'\''s' '/tmp' +3 100%"
+        );
+        assert!(!command.interactive);
+        assert_eq!(command.affected.len(), 3);
+
+        let command = ShellCommand::new("! less %d", &[], Path::new("/tmp"), None).unwrap();
+        assert!(command.interactive);
+        assert_eq!(command.command, "less '/tmp'");
+        assert!(ShellCommand::new("cat %s", &[], Path::new("/tmp"), None).is_err());
+        assert!(ShellCommand::new("vi +%l", &paths, Path::new("/tmp"), None).is_err());
+        assert!(ShellCommand::new(" ", &paths, Path::new("/tmp"), None).is_err());
+    }
+
+    #[test]
+    fn test_capture_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let command =
+            ShellCommand::new("echo out; echo err >&2; exit 3", &[], temp_dir.path(), None)
+                .unwrap();
+        let mut command_holder = CommandHolder::start(command).unwrap();
+        while !command_holder.is_done() {
+            command_holder.poll().unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        let mut lines = command_holder.lines.clone();
+        lines.sort();
+        assert_eq!(lines, vec!["err", "out"]);
+        assert_eq!(
+            describe_status(command_holder.status.unwrap()),
+            "exit code 3"
+        );
+        assert!(!command_holder.cancel());
+    }
+
+    #[test]
+    fn test_background_process_not_waited_for() {
+        let temp_dir = TempDir::new().unwrap();
+        let command =
+            ShellCommand::new("echo start; sleep 3 &", &[], temp_dir.path(), None).unwrap();
+        let mut command_holder = CommandHolder::start(command).unwrap();
+        let start = Instant::now();
+        while !command_holder.is_done() {
+            command_holder.poll().unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(command_holder.lines, vec!["start"]);
+    }
+
+    #[test]
+    fn test_scroll_follows_dropped_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let command = ShellCommand::new(
+            &format!("seq {}", MAX_OUTPUT_LINES + 5),
+            &[],
+            temp_dir.path(),
+            None,
+        )
+        .unwrap();
+        let mut command_holder = CommandHolder::start(command).unwrap();
+        command_holder.vertical_scroll = 20;
+        while !command_holder.is_done() {
+            command_holder.poll().unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(command_holder.lines.len(), MAX_OUTPUT_LINES);
+        assert_eq!(command_holder.vertical_scroll, 15);
+        assert_eq!(command_holder.lines[command_holder.vertical_scroll], "21");
+    }
+
+    #[test]
+    fn test_cancel_stops_pipeline() {
+        let temp_dir = TempDir::new().unwrap();
+        let command = ShellCommand::new("sleep 4 | cat", &[], temp_dir.path(), None).unwrap();
+        let mut command_holder = CommandHolder::start(command).unwrap();
+        command_holder.poll().unwrap();
+        assert!(command_holder.cancel());
+        // cat only exits once sleep is killed too and the pipe is closed
+        let start = Instant::now();
+        while !command_holder.is_done() {
+            command_holder.poll().unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use crate::message_holder::trash_holder::{remove_path, TrashEntry, TrashHolder, TreeSize};
+
+/// Measures the items of a delete on a background thread
+///
+/// Dropping the holder stops the measure.
+///
+/// # Fields
+///
+/// - `receiver`: Size sent by the worker thread once measured
+/// - `cancel`: Flag asking the worker thread to stop
+#[derive(Debug)]
+pub struct SizeHolder {
+    receiver: Receiver<TreeSize>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SizeHolder {
+    /// Starts measuring items
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Files or directories to measure
+    pub fn start(paths: Vec<PathBuf>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let tree_size =
+                TreeSize::of_all_until(paths.iter().map(PathBuf::as_path), &worker_cancel);
+            if let Some(tree_size) = tree_size {
+                let _ = sender.send(tree_size);
+            }
+        });
+        Self { receiver, cancel }
+    }
+
+    /// Takes the size once measured
+    ///
+    /// # Returns
+    ///
+    /// Returns `Option<TreeSize>`, `None` while the items are measured
+    pub fn poll(&self) -> Option<TreeSize> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for SizeHolder {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Message sent by the worker thread
+#[derive(Debug)]
+enum DeleteMessage {
+    Removed(PathBuf),
+    Trashed(TrashEntry),
+    Failed(String),
+}
+
+/// Deletes or trashes items on a background thread
+///
+/// Items are handled one after the other and the delete stops at the
+/// first item that cannot be deleted, the ones before it stay deleted.
+/// Dropping the holder cancels the delete after the item being deleted.
+///
+/// # Fields
+///
+/// - `permanent`: Whether the items are removed rather than trashed
+/// - `paths`: Items to delete
+/// - `receiver`: Progress sent by the worker thread
+/// - `cancel`: Flag asking the worker thread to stop
+/// - `removed`: Items removed permanently
+/// - `trashed`: Items moved to the trash
+/// - `error`: Why the delete stopped (if it failed)
+#[derive(Debug)]
+pub struct DeleteHolder {
+    pub permanent: bool,
+    pub paths: Vec<PathBuf>,
+    receiver: Receiver<DeleteMessage>,
+    cancel: Arc<AtomicBool>,
+    pub removed: Vec<PathBuf>,
+    pub trashed: Vec<TrashEntry>,
+    pub error: Option<String>,
+}
+
+impl DeleteHolder {
+    /// Starts deleting items
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Items to delete
+    /// * `permanent` - If true the items are removed, otherwise they are moved to the trash
+    /// * `trash_holder` - Trash the items are moved to
+    pub fn start(paths: Vec<PathBuf>, permanent: bool, trash_holder: TrashHolder) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+        let worker_paths = paths.clone();
+        thread::spawn(move || {
+            Self::delete(
+                worker_paths,
+                permanent,
+                &trash_holder,
+                &sender,
+                &worker_cancel,
+            )
+        });
+        Self {
+            permanent,
+            paths,
+            receiver,
+            cancel,
+            removed: Vec::new(),
+            trashed: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Applies the progress sent since the last call
+    ///
+    /// # Returns
+    ///
+    /// Returns true once every item was deleted, or the delete stopped
+    pub fn poll(&mut self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(DeleteMessage::Removed(path)) => self.removed.push(path),
+                Ok(DeleteMessage::Trashed(entry)) => self.trashed.push(entry),
+                Ok(DeleteMessage::Failed(error)) => self.error = Some(error),
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => return true,
+            }
+        }
+    }
+
+    /// Asks the worker thread to stop after the item being deleted
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Number of items deleted so far
+    pub fn n_done(&self) -> usize {
+        self.removed.len() + self.trashed.len()
+    }
+
+    /// Describes the progress, for the view title
+    pub fn status(&self) -> String {
+        let action = if self.permanent {
+            "deleting"
+        } else {
+            "trashing"
+        };
+        format!(
+            "{} {} of {} items, <Esc> to cancel",
+            action,
+            self.n_done(),
+            self.paths.len()
+        )
+    }
+
+    fn delete(
+        paths: Vec<PathBuf>,
+        permanent: bool,
+        trash_holder: &TrashHolder,
+        sender: &Sender<DeleteMessage>,
+        cancel: &AtomicBool,
+    ) {
+        for path in paths {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let message = if permanent {
+                match remove_path(&path) {
+                    Ok(()) => DeleteMessage::Removed(path),
+                    Err(error) => DeleteMessage::Failed(error.to_string()),
+                }
+            } else {
+                match trash_holder.trash(&path) {
+                    Ok(entry) => DeleteMessage::Trashed(entry),
+                    Err(error) => DeleteMessage::Failed(error.to_string()),
+                }
+            };
+            let failed = matches!(message, DeleteMessage::Failed(_));
+            if sender.send(message).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for DeleteHolder {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_measure_and_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("dir/sub")).unwrap();
+        fs::write(temp_dir.path().join("dir/sub/a.txt"), "aaa").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "b").unwrap();
+        let paths = vec![
+            temp_dir.path().join("dir"),
+            temp_dir.path().join("b.txt"),
+            temp_dir.path().join("missing.txt"),
+        ];
+
+        let size_holder = SizeHolder::start(paths.clone());
+        let tree_size = loop {
+            if let Some(tree_size) = size_holder.poll() {
+                break tree_size;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(
+            tree_size,
+            TreeSize {
+                n_files: 2,
+                size: 4
+            }
+        );
+
+        let trash_holder = TrashHolder {
+            trash_dir: temp_dir.path().join("Trash"),
+        };
+        let mut delete_holder = DeleteHolder::start(paths, false, trash_holder);
+        while !delete_holder.poll() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        // the delete stops at the missing item
+        assert_eq!(delete_holder.trashed.len(), 2);
+        assert!(delete_holder.error.is_some());
+        assert!(!temp_dir.path().join("dir").exists());
+        assert!(temp_dir.path().join("Trash/files/dir/sub/a.txt").exists());
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::rename_holder::RenamePlan;
+use crate::message_holder::transfer_holder::{TransferJob, TransferMode};
+use crate::message_holder::trash_holder::TreeSize;
+
+/// Number of items listed in the confirmation popup
+pub const MAX_LISTED_ITEMS: usize = 5;
+
+/// A delete waiting for confirmation
+///
+/// # Fields
+///
+/// - `paths`: Items to delete
+/// - `n_dirs`: Number of directories among the items
+/// - `tree_size`: Number of files and total size that would be deleted, once measured
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingDelete {
+    pub paths: Vec<PathBuf>,
+    pub n_dirs: usize,
+    pub tree_size: Option<TreeSize>,
+}
+
+/// A paste waiting for the conflicts to be resolved
+///
+/// # Fields
+///
+/// - `mode`: Whether the items are copied or moved
+/// - `jobs`: Items whose destination is free
+/// - `conflicts`: Items whose destination exists already
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingPaste {
+    pub mode: TransferMode,
+    pub jobs: Vec<TransferJob>,
+    pub conflicts: Vec<TransferJob>,
+}
+
+/// An operation waiting for an answer in the confirmation popup
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    /// Deleting items
+    Delete(PendingDelete),
+    /// Pasting over existing items
+    Conflict(PendingPaste),
+    /// Renaming the items edited in the bulk rename buffer
+    Rename(RenamePlan),
+}
+
+/// How pasted items replace the existing ones
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictChoice {
+    /// The existing items are moved to the trash
+    Overwrite,
+    /// The conflicting items are not pasted
+    Skip,
+    /// The conflicting items are pasted under a new name
+    KeepBoth,
+}
+
+/// A name or command typed in the input, and what it is for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prompt {
+    /// New name of an item
+    Rename(PathBuf),
+    /// Name of a file to create in the current directory
+    NewFile,
+    /// Name of a directory to create in the current directory
+    NewDirectory,
+    /// Shell command to run on the selected entries
+    Command,
+}
+
+impl fmt::Display for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Prompt::Rename(path) => write!(
+                f,
+                "Rename {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            Prompt::NewFile => write!(f, "New file"),
+            Prompt::NewDirectory => write!(f, "New directory"),
+            Prompt::Command => write!(
+                f,
+                "Command (%s selection, %d directory, %l line, ! prefix to run in the terminal)"
+            ),
+        }
+    }
+}
+
+/// Checks that a typed name is a single path component
+///
+/// # Arguments
+///
+/// * `name` - Typed name
+///
+/// # Returns
+///
+/// Returns `AppResult<&str>` which may contain `AppError::Path` if the name
+/// is empty, `.`, `..` or contains a path separator
+pub fn check_name(name: &str) -> AppResult<&str> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(std::path::is_separator) {
+        return Err(AppError::Path(format!("Invalid name \"{}\"", name)));
+    }
+    Ok(name)
+}
+
+/// Returns a free path next to an existing one, like `notes (2).txt`
+///
+/// # Arguments
+///
+/// * `path` - Path that exists already
+pub fn unique_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|index| path.with_file_name(format!("{} ({}){}", stem, index, extension)))
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_name() {
+        assert!(check_name("notes.txt").is_ok());
+        assert!(check_name("").is_err());
+        assert!(check_name("..").is_err());
+        assert!(check_name("a/b").is_err());
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        fs::write(&path, "").unwrap();
+        fs::write(temp_dir.path().join("notes (2).txt"), "").unwrap();
+        assert_eq!(unique_path(&path), temp_dir.path().join("notes (3).txt"));
+        assert_eq!(
+            unique_path(&temp_dir.path().join("docs")),
+            temp_dir.path().join("docs (2)")
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::app::app_error::{AppError, AppResult};
+
+/// Environment variables naming the external editor, by preference
+pub const EDITOR_ENV: [&str; 2] = ["VISUAL", "EDITOR"];
+
+/// Editor used when no editor is configured
+pub const DEFAULT_EDITOR: &str = "vi";
+
+/// Number of buffers created by the process, keeping their names apart
+static BUFFER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Editors opening a file at line `N` when given `+N`
+pub const LINE_ARGUMENT_EDITORS: [&str; 12] = [
+    "vi",
+    "vim",
+    "nvim",
+    "gvim",
+    "view",
+    "nano",
+    "emacs",
+    "emacsclient",
+    "micro",
+    "kak",
+    "joe",
+    "mg",
+];
+
+/// What the edited files are for, handled once the editor exits
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorTask {
+    /// Buffer listing the names of the original items, renamed once saved
+    BulkRename {
+        buffer: PathBuf,
+        originals: Vec<PathBuf>,
+    },
+    /// Files opened by the user, read again once the editor exits, with
+    /// the number of selected directories left out
+    Open { n_skipped: usize },
+}
+
+/// Files to edit in the external editor, with the terminal suspended
+///
+/// # Fields
+///
+/// - `paths`: Files to edit
+/// - `line`: Line to open a single file at (if any)
+/// - `task`: What to do once the editor exits
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorRequest {
+    pub paths: Vec<PathBuf>,
+    pub line: Option<usize>,
+    pub task: EditorTask,
+}
+
+impl EditorRequest {
+    /// Runs the editor on the files and waits for it to exit
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// editor cannot be run or exits with a failure
+    pub fn run(&self) -> AppResult<()> {
+        let editor = editor_command();
+        let status = self
+            .command(&editor)
+            .status()
+            .map_err(|error| AppError::State(format!("Unable to run {}: {}", editor, error)))?;
+        if !status.success() {
+            return Err(AppError::State(format!(
+                "{} exited with {}",
+                editor, status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Builds the command running an editor on the files
+    ///
+    /// The editor command may have arguments, like `code --wait`. The line
+    /// is passed as `+N` to the editors known to support it.
+    ///
+    /// # Arguments
+    ///
+    /// * `editor` - Editor command
+    pub fn command(&self, editor: &str) -> Command {
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or(DEFAULT_EDITOR);
+        let mut command = Command::new(program);
+        command.args(words);
+        let name = Path::new(program)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        if let (Some(line), [_]) = (self.line, self.paths.as_slice()) {
+            if LINE_ARGUMENT_EDITORS.contains(&name.as_ref()) {
+                command.arg(format!("+{}", line));
+            }
+        }
+        command.args(&self.paths);
+        command
+    }
+}
+
+/// Returns the configured editor command, `$VISUAL` then `$EDITOR`
+pub fn editor_command() -> String {
+    EDITOR_ENV
+        .iter()
+        .filter_map(env::var_os)
+        .map(|editor| editor.to_string_lossy().trim().to_string())
+        .find(|editor| !editor.is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.into())
+}
+
+/// Creates a buffer to edit in the temporary directory
+///
+/// The file is created new and only the user can read it, so an existing
+/// file or link at a guessed path is never written through.
+///
+/// # Arguments
+///
+/// * `purpose` - Word naming what the buffer is for, like `rename`
+/// * `text` - Initial text of the buffer
+///
+/// # Returns
+///
+/// Returns `AppResult<PathBuf>` with the path of the buffer, which may
+/// contain `AppError::Io` if it cannot be created or written
+pub fn create_buffer(purpose: &str, text: &str) -> AppResult<PathBuf> {
+    loop {
+        let count = BUFFER_COUNT.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!(
+            "athena_viewer-{}-{}-{}.txt",
+            purpose,
+            process::id(),
+            count
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            }
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => (),
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_with_line() {
+        let request = EditorRequest {
+            paths: vec![PathBuf::from("src/main.rs")],
+            line: Some(42),
+            task: EditorTask::Open { n_skipped: 0 },
+        };
+        let args = |command: Command| {
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            args(request.command("/usr/bin/nvim")),
+            vec!["+42", "src/main.rs"]
+        );
+        // other editors only get the file
+        assert_eq!(
+            args(request.command("code --wait")),
+            vec!["--wait", "src/main.rs"]
+        );
+        let request = EditorRequest {
+            paths: vec![PathBuf::from("a"), PathBuf::from("b")],
+            ..request
+        };
+        assert_eq!(args(request.command("vim")), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_buffer_never_written_through() {
+        let count = BUFFER_COUNT.load(Ordering::Relaxed);
+        let taken = env::temp_dir().join(format!(
+            "athena_viewer-test-{}-{}.txt",
+            process::id(),
+            count
+        ));
+        std::fs::write(&taken, "kept").unwrap();
+
+        let buffer = create_buffer("test", "a.txt\n").unwrap();
+        assert_ne!(buffer, taken);
+        assert_eq!(std::fs::read_to_string(&taken).unwrap(), "kept");
+        assert_eq!(std::fs::read_to_string(&buffer).unwrap(), "a.txt\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&buffer).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(taken).unwrap();
+        std::fs::remove_file(buffer).unwrap();
+    }
+}
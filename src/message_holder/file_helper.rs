@@ -3,9 +3,11 @@ use std::fs::{self};
 use chrono::{DateTime, Local};
 use ratatui::text::Line;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::app::app_error::{AppError, AppResult};
 use crate::message_holder::code_highlighter::CodeHighlighter;
+use crate::message_holder::file_sorter::SortMode;
 
 /// Maximum file size allowed for viewing (10MB)
 ///
@@ -34,11 +36,17 @@ pub struct FileTextInfo {
 /// - `parent`: Parent directory path
 /// - `file_name`: Name of the file/directory
 /// - `is_file`: True if this is a file, false if directory
+/// - `is_symlink`: True if the entry itself is a symbolic link
+/// - `size`: Size in bytes (0 if unavailable)
+/// - `modified`: Last modification time (if available)
 #[derive(Debug, Clone)]
 pub struct FileHolder {
     pub parent: PathBuf,
     pub file_name: String,
     pub is_file: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
 }
 
 /// Holds a group of files/directories with metadata
@@ -47,10 +55,16 @@ pub struct FileHolder {
 ///
 /// - `child`: List of file/directory entries
 /// - `update_time`: When this group was last updated
+/// - `sort_mode`: Sort order applied to `child`, remembered per directory
+/// - `is_loaded`: False for placeholders restored from history, not read yet
+/// - `selected`: Last selected entry, restored when returning to the directory
 #[derive(Debug)]
 pub struct FileGroupHolder {
     pub child: Vec<FileHolder>,
     pub update_time: DateTime<Local>,
+    pub sort_mode: SortMode,
+    pub is_loaded: bool,
+    pub selected: Option<PathBuf>,
 }
 
 impl FileTextInfo {
@@ -68,24 +82,95 @@ impl FileTextInfo {
     /// - `AppError::Path`: If file is too large (> 10MB)
     /// - `AppError::Parse`: If syntax highlighting fails
     pub fn new(value: &Path, code_highlighter: &CodeHighlighter) -> AppResult<Self> {
+        let content = Self::read_content(value)?;
+        Self::from_content(&content, value, code_highlighter)
+    }
+
+    /// Reads a file for display
+    ///
+    /// Content that is not valid UTF-8 is replaced by a placeholder.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Path to the file to read
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` which may contain:
+    /// - `AppError::Io`: If file metadata cannot be read
+    /// - `AppError::Path`: If file is too large (> 10MB)
+    pub fn read_content(value: &Path) -> AppResult<String> {
         let meta_data = fs::metadata(value).map_err(AppError::Io)?;
         if meta_data.len() > MAX_FILE_SIZE {
             return Err(AppError::Path("File too large".into()));
         }
-        let content = match fs::read_to_string(value) {
+        Ok(match fs::read_to_string(value) {
             Ok(text) => text,
             Err(_) => "Unable to read...".to_string(),
-        };
+        })
+    }
 
-        let (n_rows, max_line_length) = Self::get_string_dimensions(&content);
+    /// Creates a new FileTextInfo from text already in memory
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Text to format
+    /// * `value` - Path used to pick the syntax
+    /// * `code_highlighter` - Syntax highlighter for formatting
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Parse` if
+    /// syntax highlighting fails
+    pub fn from_content(
+        content: &str,
+        value: &Path,
+        code_highlighter: &CodeHighlighter,
+    ) -> AppResult<Self> {
+        let (n_rows, max_line_length) = Self::get_string_dimensions(content);
 
         Ok(Self {
             n_rows,
             max_line_length,
-            formatted_text: code_highlighter.highlight(&content, value)?,
+            formatted_text: code_highlighter.highlight(content, value)?,
         })
     }
 
+    /// Appends text written to the end of the file
+    ///
+    /// Only the appended text is highlighted, the lines already shown are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Appended text
+    /// * `replaces_last_line` - Whether `text` starts with the unterminated last line
+    /// * `value` - Path used to pick the syntax
+    /// * `code_highlighter` - Syntax highlighter for formatting
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Parse` if
+    /// syntax highlighting fails
+    pub fn append(
+        &mut self,
+        text: &str,
+        replaces_last_line: bool,
+        value: &Path,
+        code_highlighter: &CodeHighlighter,
+    ) -> AppResult<()> {
+        let formatted_text = code_highlighter.highlight(text, value)?;
+        if replaces_last_line {
+            self.formatted_text.pop();
+        }
+        self.formatted_text.extend(formatted_text);
+
+        // The last counted row is either the one replaced or the empty one after a newline
+        let (n_rows, max_line_length) = Self::get_string_dimensions(text);
+        self.n_rows = self.n_rows.saturating_sub(1) + n_rows;
+        self.max_line_length = self.max_line_length.max(max_line_length);
+        Ok(())
+    }
+
     fn get_string_dimensions(text: &str) -> (usize, usize) {
         let mut num_rows = 0;
         let mut max_line_length = 0;
@@ -133,7 +218,10 @@ impl TryFrom<PathBuf> for FileHolder {
                 path
             )))?;
 
-        let is_file = path.is_file();
+        let metadata = fs::metadata(&path).ok();
+        let is_symlink = fs::symlink_metadata(&path)
+            .map(|m| m.file_type().is_symlink())
+            .unwrap_or(false);
         Ok(FileHolder {
             parent: path
                 .parent()
@@ -143,12 +231,32 @@ impl TryFrom<PathBuf> for FileHolder {
                 )))?
                 .to_path_buf(),
             file_name,
-            is_file,
+            is_file: metadata.as_ref().is_some_and(|m| m.is_file()),
+            is_symlink,
+            size: metadata.as_ref().map_or(0, |m| m.len()),
+            modified: metadata.and_then(|m| m.modified().ok()),
         })
     }
 }
 
 impl FileHolder {
+    /// Returns the ".." entry listed first in a directory
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` at the root directory
+    pub fn parent_shortcut(path: &Path) -> Option<Self> {
+        path.parent()?;
+        Some(FileHolder {
+            parent: path.to_path_buf(),
+            file_name: "..".to_string(),
+            is_file: false,
+            is_symlink: false,
+            size: 0,
+            modified: None,
+        })
+    }
+
     /// Returns the canonicalized absolute path of this file/directory
     ///
     /// # Returns
@@ -201,24 +309,22 @@ impl FileGroupHolder {
     ///
     /// * `path` - The directory path to read
     /// * `adding_parent_shortcut` - If true, adds ".." entry for parent navigation
+    /// * `sort_mode` - Order to apply to the entries
     ///
     /// # Returns
     ///
     /// Returns `AppResult<Self>` which may contain:
     /// - `AppError::Parse`: If the directory cannot be read
     /// - `AppError::Path`: If individual entries cannot be processed
-    pub fn new(path: PathBuf, adding_parent_shortcut: bool) -> AppResult<Self> {
+    pub fn new(
+        path: PathBuf,
+        adding_parent_shortcut: bool,
+        sort_mode: SortMode,
+    ) -> AppResult<Self> {
         let mut entries = Vec::new();
 
-        // add if not at root
         if adding_parent_shortcut {
-            if let Some(_parent) = path.parent() {
-                entries.push(FileHolder {
-                    parent: path.clone(),
-                    file_name: "..".to_string(),
-                    is_file: false,
-                })
-            }
+            entries.extend(FileHolder::parent_shortcut(&path));
         }
 
         let read_dir_result = fs::read_dir(&path)
@@ -229,12 +335,33 @@ impl FileGroupHolder {
             entries.push(file_holder);
         }
 
-        entries.sort_by_key(|f| f.file_name.clone());
+        sort_mode.sort(&mut entries);
         Ok(Self {
             child: entries,
             update_time: Local::now(),
+            sort_mode,
+            is_loaded: true,
+            selected: None,
         })
     }
+
+    /// Creates a placeholder for a directory that has not been read yet
+    ///
+    /// Used for directories restored from a previous session, the listing
+    /// is read when the directory is visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `sort_mode` - Order to apply once the entries are read
+    pub fn unloaded(sort_mode: SortMode) -> Self {
+        Self {
+            child: Vec::new(),
+            update_time: Local::now(),
+            sort_mode,
+            is_loaded: false,
+            selected: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -255,11 +382,34 @@ mod tests {
         let temp_file = get_temp_file();
         let path = temp_file.path();
         let code_highlighter = CodeHighlighter::default();
-        let file_text_info = FileTextInfo::new(&path, &code_highlighter).unwrap();
+        let file_text_info = FileTextInfo::new(path, &code_highlighter).unwrap();
         assert_eq!(file_text_info.n_rows, 1);
         assert_eq!(file_text_info.max_line_length, 13);
     }
 
+    #[test]
+    fn test_file_text_info_append() {
+        let path = Path::new("service.log");
+        let code_highlighter = CodeHighlighter::default();
+        let mut file_text_info =
+            FileTextInfo::from_content("first\nsec", path, &code_highlighter).unwrap();
+        file_text_info
+            .append("second line\nthird\n", true, path, &code_highlighter)
+            .unwrap();
+        let expected =
+            FileTextInfo::from_content("first\nsecond line\nthird\n", path, &code_highlighter)
+                .unwrap();
+        assert_eq!(file_text_info.n_rows, expected.n_rows);
+        assert_eq!(file_text_info.max_line_length, expected.max_line_length);
+        assert_eq!(file_text_info.formatted_text, expected.formatted_text);
+
+        file_text_info
+            .append("fourth", false, path, &code_highlighter)
+            .unwrap();
+        assert_eq!(file_text_info.n_rows, 4);
+        assert_eq!(file_text_info.formatted_text.len(), 4);
+    }
+
     fn get_temp_file() -> NamedTempFile {
         let temp_file = NamedTempFile::new().unwrap();
 
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::code_highlighter::CodeHighlighter;
+use crate::message_holder::file_helper::FileTextInfo;
+use crate::message_holder::git_helper::{find_repository_root, CommitInfo};
+
+/// Manages the git history browser of an opened file
+///
+/// # Fields
+///
+/// - `file_path`: File whose history is browsed
+/// - `root`: Top level directory of the repository
+/// - `commits`: Commits that touched the file, newest first
+/// - `raw_highlight_index`: Current commit selection (before wrapping)
+/// - `revision`: Content or diff of the selected commit, when shown
+/// - `show_diff`: Shows the diff against the parent instead of the content
+/// - `vertical_scroll`: Vertical scroll position within `revision`
+/// - `horizontal_scroll`: Horizontal scroll position within `revision`
+#[derive(Debug)]
+pub struct FileHistoryHolder {
+    pub file_path: PathBuf,
+    root: PathBuf,
+    pub commits: Vec<CommitInfo>,
+    pub raw_highlight_index: i32,
+    pub revision: Option<FileTextInfo>,
+    pub show_diff: bool,
+    pub vertical_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl FileHistoryHolder {
+    /// Loads the history of a file from its repository
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The opened file
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain:
+    /// - `AppError::State`: If the file is not inside a git repository or has no history
+    /// - `AppError::Parse`: If git log fails
+    pub fn new(file_path: &Path) -> AppResult<Self> {
+        let directory = file_path.parent().ok_or(AppError::Path(format!(
+            "Unable to get parent folder for {:?}",
+            file_path
+        )))?;
+        let root = find_repository_root(directory).ok_or(AppError::State(
+            "File is not inside a git repository".into(),
+        ))?;
+        let commits = CommitInfo::load_file_log(file_path)?;
+        if commits.is_empty() {
+            return Err(AppError::State("File has no git history".into()));
+        }
+
+        Ok(Self {
+            file_path: file_path.to_path_buf(),
+            root,
+            commits,
+            raw_highlight_index: 0,
+            revision: None,
+            show_diff: false,
+            vertical_scroll: 0,
+            horizontal_scroll: 0,
+        })
+    }
+
+    /// Returns the selected commit index wrapped within bounds
+    pub fn highlight_index(&self) -> usize {
+        self.raw_highlight_index
+            .rem_euclid(self.commits.len().max(1) as i32) as usize
+    }
+
+    /// Returns the selected commit
+    pub fn selected(&self) -> AppResult<&CommitInfo> {
+        self.commits
+            .get(self.highlight_index())
+            .ok_or(AppError::State("No commit selected".into()))
+    }
+
+    /// Shows the selected commit, either its file content or its diff
+    ///
+    /// # Arguments
+    ///
+    /// * `code_highlighter` - Syntax highlighter for formatting
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If git show or highlighting fails
+    pub fn open_revision(&mut self, code_highlighter: &CodeHighlighter) -> AppResult<()> {
+        let commit = self.selected()?;
+        let revision = if self.show_diff {
+            let diff = commit.show_diff(&self.root)?;
+            FileTextInfo::from_content(&diff, Path::new("commit.diff"), code_highlighter)?
+        } else {
+            let content = commit.show_revision(&self.root)?;
+            FileTextInfo::from_content(&content, Path::new(&commit.path), code_highlighter)?
+        };
+        self.revision = Some(revision);
+        self.vertical_scroll = 0;
+        self.horizontal_scroll = 0;
+        Ok(())
+    }
+
+    /// Switches between content and diff, reloading the shown revision
+    pub fn toggle_diff(&mut self, code_highlighter: &CodeHighlighter) -> AppResult<()> {
+        self.show_diff = !self.show_diff;
+        if self.revision.is_some() {
+            self.open_revision(code_highlighter)?;
+        }
+        Ok(())
+    }
+
+    /// Goes back from a shown revision to the commit list
+    pub fn close_revision(&mut self) {
+        self.revision = None;
+        self.vertical_scroll = 0;
+        self.horizontal_scroll = 0;
+    }
+}
@@ -0,0 +1,436 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::app::app_error::AppError;
+
+use crate::message_holder::file_helper::FileHolder;
+
+/// Attribute used to order entries of a directory listing
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum SortKey {
+    /// Natural (numeric-aware) file name order
+    #[default]
+    Name,
+    /// File size in bytes
+    Size,
+    /// Last modification time
+    Modified,
+    /// File extension, then name
+    Extension,
+    /// Entry type (directory, symlink, file), then name
+    Type,
+}
+
+/// Full sort configuration for a directory listing
+///
+/// # Fields
+///
+/// - `key`: Attribute to sort by
+/// - `reverse`: Reverses the order of `key`
+/// - `directories_first`: Lists directories before files regardless of `key`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct SortMode {
+    pub key: SortKey,
+    pub reverse: bool,
+    pub directories_first: bool,
+}
+
+impl SortKey {
+    /// Returns the next sort key in the cycle
+    pub fn next(self) -> Self {
+        use SortKey::*;
+        match self {
+            Name => Size,
+            Size => Modified,
+            Modified => Extension,
+            Extension => Type,
+            Type => Name,
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SortKey::*;
+        let name = match self {
+            Name => "name",
+            Size => "size",
+            Modified => "mtime",
+            Extension => "ext",
+            Type => "type",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = AppError;
+
+    /// Parses the name produced by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use SortKey::*;
+        match s {
+            "name" => Ok(Name),
+            "size" => Ok(Size),
+            "mtime" => Ok(Modified),
+            "ext" => Ok(Extension),
+            "type" => Ok(Type),
+            _ => Err(AppError::Parse(format!("Unknown sort key {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key)?;
+        if self.reverse {
+            write!(f, " desc")?;
+        }
+        if self.directories_first {
+            write!(f, " dirs-first")?;
+        }
+        Ok(())
+    }
+}
+
+impl SortMode {
+    /// Sorts the entries of a directory in place
+    ///
+    /// The ".." shortcut is always kept at the front.
+    pub fn sort(&self, entries: &mut [FileHolder]) {
+        entries.sort_by(|a, b| self.compare(a, b));
+    }
+
+    /// Sorts an expanded listing, each directory followed by its content
+    ///
+    /// Siblings are ordered by this mode. An expanded directory that is not
+    /// listed itself is sorted among its siblings and replaced by its
+    /// content, so the default mode keeps the path order of the expansion.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Directory the listing is relative to
+    /// * `entries` - Entries of several directories
+    ///
+    /// # Returns
+    ///
+    /// Returns the entries in display order
+    pub fn sort_tree(&self, root: &Path, entries: Vec<FileHolder>) -> Vec<FileHolder> {
+        let mut groups: BTreeMap<PathBuf, Vec<FileHolder>> = BTreeMap::new();
+        for entry in entries {
+            groups.entry(entry.parent.clone()).or_default().push(entry);
+        }
+        let listed: HashSet<PathBuf> = groups
+            .values()
+            .flatten()
+            .filter(|entry| entry.file_name != "..")
+            .map(FileHolder::to_path)
+            .collect();
+        let parents: Vec<PathBuf> = groups.keys().cloned().collect();
+        let mut hidden = HashSet::new();
+        for parent in parents {
+            let mut directory = parent;
+            while directory != root
+                && directory.starts_with(root)
+                && !listed.contains(&directory)
+                && !hidden.contains(&directory)
+            {
+                let Ok(holder) = FileHolder::try_from(directory.clone()) else {
+                    break;
+                };
+                hidden.insert(directory.clone());
+                directory = holder.parent.clone();
+                groups.entry(directory.clone()).or_default().push(holder);
+            }
+        }
+        for group in groups.values_mut() {
+            self.sort(group);
+        }
+        let roots: Vec<PathBuf> = groups
+            .keys()
+            .filter(|parent| !listed.contains(*parent) && !hidden.contains(*parent))
+            .cloned()
+            .collect();
+        let mut sorted = Vec::new();
+        for root in roots {
+            Self::push_tree(&root, &mut groups, &hidden, &mut sorted);
+        }
+        sorted
+    }
+
+    /// Appends the sorted content of a directory, recursing into listed directories
+    ///
+    /// Directories in `hidden` are replaced by their content.
+    fn push_tree(
+        directory: &Path,
+        groups: &mut BTreeMap<PathBuf, Vec<FileHolder>>,
+        hidden: &HashSet<PathBuf>,
+        sorted: &mut Vec<FileHolder>,
+    ) {
+        let Some(group) = groups.remove(directory) else {
+            return;
+        };
+        for entry in group {
+            let path = entry.to_path();
+            if entry.file_name == ".." {
+                sorted.push(entry);
+                continue;
+            }
+            if !hidden.contains(&path) {
+                sorted.push(entry);
+            }
+            Self::push_tree(&path, groups, hidden, sorted);
+        }
+    }
+
+    /// Compares two entries according to this sort mode
+    pub fn compare(&self, a: &FileHolder, b: &FileHolder) -> Ordering {
+        let a_is_shortcut = a.file_name == "..";
+        let b_is_shortcut = b.file_name == "..";
+        if a_is_shortcut || b_is_shortcut {
+            return b_is_shortcut.cmp(&a_is_shortcut);
+        }
+
+        if self.directories_first && a.is_file != b.is_file {
+            return a.is_file.cmp(&b.is_file);
+        }
+
+        let order = self.compare_key(a, b);
+        if self.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+
+    fn compare_key(&self, a: &FileHolder, b: &FileHolder) -> Ordering {
+        use SortKey::*;
+        let by_name = || natural_cmp(&a.file_name, &b.file_name);
+        match self.key {
+            Name => by_name(),
+            Size => a.size.cmp(&b.size).then_with(by_name),
+            Modified => a.modified.cmp(&b.modified).then_with(by_name),
+            Extension => {
+                natural_cmp(extension(&a.file_name), extension(&b.file_name)).then_with(by_name)
+            }
+            Type => type_rank(a).cmp(&type_rank(b)).then_with(by_name),
+        }
+    }
+}
+
+fn extension(file_name: &str) -> &str {
+    match file_name.rfind('.') {
+        Some(0) | None => "",
+        Some(index) => &file_name[index + 1..],
+    }
+}
+
+fn type_rank(entry: &FileHolder) -> u8 {
+    if entry.is_symlink {
+        1
+    } else if entry.is_file {
+        2
+    } else {
+        0
+    }
+}
+
+/// Compares two strings treating runs of ASCII digits as numbers
+///
+/// `file2` sorts before `file10`. Non-digit characters are compared as-is,
+/// so the ordering is otherwise identical to plain string comparison.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_iter = a.chars().peekable();
+    let mut b_iter = b.chars().peekable();
+
+    loop {
+        match (a_iter.peek().copied(), b_iter.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let a_number = take_digits(&mut a_iter);
+                let b_number = take_digits(&mut b_iter);
+                let a_trimmed = a_number.trim_start_matches('0');
+                let b_trimmed = b_number.trim_start_matches('0');
+                let order = a_trimmed
+                    .len()
+                    .cmp(&b_trimmed.len())
+                    .then_with(|| a_trimmed.cmp(b_trimmed))
+                    .then_with(|| a_number.len().cmp(&b_number.len()));
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(a_char), Some(b_char)) => {
+                if a_char != b_char {
+                    return a_char.cmp(&b_char);
+                }
+                a_iter.next();
+                b_iter.next();
+            }
+        }
+    }
+}
+
+fn take_digits(iter: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = iter.peek().copied() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(c);
+        iter.next();
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, is_file: bool, size: u64) -> FileHolder {
+        FileHolder {
+            parent: PathBuf::from("/tmp"),
+            file_name: name.into(),
+            is_file,
+            is_symlink: false,
+            size,
+            modified: None,
+        }
+    }
+
+    fn entry_in(parent: &str, name: &str, is_file: bool) -> FileHolder {
+        FileHolder {
+            parent: PathBuf::from(parent),
+            file_name: name.into(),
+            is_file,
+            is_symlink: false,
+            size: 0,
+            modified: None,
+        }
+    }
+
+    fn names(entries: &[FileHolder]) -> Vec<&str> {
+        entries.iter().map(|e| e.file_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10", "file10"), Ordering::Equal);
+        assert_eq!(natural_cmp("a", "b"), Ordering::Less);
+        assert_eq!(natural_cmp("file02", "file2"), Ordering::Greater);
+        assert_eq!(natural_cmp("README.md", "main.rs"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort_keeps_shortcut_first() {
+        let mut entries = vec![
+            entry("b", true, 1),
+            entry("..", false, 0),
+            entry("a", true, 2),
+        ];
+        let mode = SortMode {
+            reverse: true,
+            ..Default::default()
+        };
+        mode.sort(&mut entries);
+        assert_eq!(names(&entries), vec!["..", "b", "a"]);
+    }
+
+    #[test]
+    fn test_sort_by_size_directories_first() {
+        let mut entries = vec![
+            entry("big", true, 100),
+            entry("dir", false, 0),
+            entry("small", true, 1),
+        ];
+        let mode = SortMode {
+            key: SortKey::Size,
+            reverse: false,
+            directories_first: true,
+        };
+        mode.sort(&mut entries);
+        assert_eq!(names(&entries), vec!["dir", "small", "big"]);
+    }
+
+    #[test]
+    fn test_sort_by_extension() {
+        let mut entries = vec![
+            entry("b.rs", true, 0),
+            entry("a.toml", true, 0),
+            entry("c.md", true, 0),
+        ];
+        let mode = SortMode {
+            key: SortKey::Extension,
+            ..Default::default()
+        };
+        mode.sort(&mut entries);
+        assert_eq!(names(&entries), vec!["c.md", "b.rs", "a.toml"]);
+    }
+
+    #[test]
+    fn test_sort_tree_keeps_content_under_directory() {
+        let entries = vec![
+            entry_in("/tmp/b", "y.txt", true),
+            entry_in("/tmp", "c.txt", true),
+            entry_in("/tmp", "b", false),
+            entry_in("/tmp/b", "x.txt", true),
+            entry_in("/tmp", "..", false),
+            entry_in("/tmp", "a.txt", true),
+        ];
+        let sorted = SortMode::default().sort_tree(Path::new("/tmp"), entries);
+        assert_eq!(
+            names(&sorted),
+            vec!["..", "a.txt", "b", "x.txt", "y.txt", "c.txt"]
+        );
+
+        let mode = SortMode {
+            reverse: true,
+            ..Default::default()
+        };
+        let sorted = mode.sort_tree(Path::new("/tmp"), sorted);
+        assert_eq!(
+            names(&sorted),
+            vec!["..", "c.txt", "b", "y.txt", "x.txt", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn test_sort_tree_replaces_unlisted_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        std::fs::create_dir(root.join("b")).unwrap();
+        let root_str = root.to_str().unwrap();
+        let b_str = root.join("b");
+        let b_str = b_str.to_str().unwrap();
+        let entries = vec![
+            entry_in(b_str, "y.txt", true),
+            entry_in(root_str, "c.txt", true),
+            entry_in(b_str, "x.txt", true),
+            entry_in(root_str, "..", false),
+            entry_in(root_str, "a.txt", true),
+        ];
+        let sorted = SortMode::default().sort_tree(&root, entries);
+        assert_eq!(
+            names(&sorted),
+            vec!["..", "a.txt", "x.txt", "y.txt", "c.txt"]
+        );
+
+        let mode = SortMode {
+            reverse: true,
+            ..Default::default()
+        };
+        let sorted = mode.sort_tree(&root, sorted);
+        assert_eq!(
+            names(&sorted),
+            vec!["..", "c.txt", "y.txt", "x.txt", "a.txt"]
+        );
+    }
+}
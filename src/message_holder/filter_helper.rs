@@ -0,0 +1,43 @@
+/// Checks if an item matches a filter (case-insensitive)
+///
+/// Checks if all characters in `input` appear in order in `name`. Shared
+/// by the listings, the bookmark picker and the history ranking.
+///
+/// # Arguments
+///
+/// * `name` - Text of the item
+/// * `input` - Filter typed by the user
+pub fn matches_filter(name: &str, input: &str) -> bool {
+    if input.is_empty() {
+        return true;
+    }
+
+    // check if all characters in input appear in order (case-insensitive) in name
+    let mut input_iter = input.chars();
+    let mut next_to_match = input_iter.next();
+
+    for name_char in name.chars() {
+        match next_to_match {
+            Some(input_char) if name_char.eq_ignore_ascii_case(&input_char) => {
+                next_to_match = input_iter.next();
+            }
+            None => return true,
+            _ => (),
+        }
+    }
+
+    next_to_match.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_filter() {
+        assert!(matches_filter("abc", "c"));
+        assert!(matches_filter("abc", ""));
+        assert!(!matches_filter("abc", "d"));
+        assert!(!matches_filter("abc", "abcd"));
+    }
+}
@@ -1,15 +1,23 @@
+use chrono::{DateTime, Local};
 use lru::LruCache;
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::sync::mpsc;
-use std::sync::Arc;
-use std::thread;
+use std::time::Duration;
 
 use crate::app::app_error::{AppError, AppResult};
 use crate::message_holder::file_helper::{FileGroupHolder, FileHolder};
+use crate::message_holder::file_sorter::SortMode;
+use crate::message_holder::filter_helper::matches_filter;
+use crate::message_holder::git_helper::{GitStatus, GitStatusHolder, GitStatusLoader};
+use crate::message_holder::history_store::{
+    merge_by_recency, HistoryEntry, HistoryStore, VisitInfo,
+};
+use crate::message_holder::load_holder::{open_directory, LoadHolder, LoadStatus};
 use crate::state_holder::StateHolder;
 
 /// Default LRU cache size for directory listings
@@ -21,6 +29,53 @@ pub const EXPAND_THREAD_COUNT: usize = 4;
 /// Minimum number of folders to trigger multi-threaded expansion
 pub const EXPAND_MULTI_THREAD_THRESHOLD: usize = EXPAND_THREAD_COUNT + 2;
 
+/// Time a directory read may block the UI before it continues in the background
+pub const LOAD_BUDGET: Duration = Duration::from_millis(50);
+
+/// Default maximum number of entries listed by an expansion
+pub const EXPAND_ENTRY_BUDGET: usize = 10_000;
+
+/// Environment variable overriding the expansion entry budget
+pub const EXPAND_BUDGET_ENV: &str = "ATHENA_VIEWER_EXPAND_BUDGET";
+
+/// Number of skipped paths named in the log line
+const MAX_REPORTED_PATHS: usize = 5;
+
+/// What a background directory read is for
+#[derive(Debug)]
+enum LoadTarget {
+    /// Listing of the current directory, expanded `expand_level` times once read
+    Directory { expand_level: usize },
+    /// One more expansion of the listing `base`, expanded `remaining` more times once read
+    Expand {
+        base: Vec<FileHolder>,
+        remaining: usize,
+    },
+    /// Children of the tree nodes `paths`, then the listing is expanded `expand_level` times
+    Tree {
+        paths: Vec<PathBuf>,
+        expand_level: usize,
+    },
+}
+
+/// Entry of the tree view, with the descendants kept by the filter
+#[derive(Debug)]
+struct TreeNode {
+    entry: FileHolder,
+    children: Vec<TreeNode>,
+}
+
+/// Outcome of checking the background directory read
+#[derive(Debug, PartialEq)]
+pub enum LoadUpdate {
+    /// Nothing changed
+    Unchanged,
+    /// More entries were read, or another read started
+    Progress,
+    /// The listing is complete
+    Finished,
+}
+
 /// Manages directory navigation, search filtering, and caching
 ///
 /// # Fields
@@ -29,19 +84,49 @@ pub const EXPAND_MULTI_THREAD_THRESHOLD: usize = EXPAND_THREAD_COUNT + 2;
 /// - `cache_holder`: LRU cache of directory listings
 /// - `input`: Current search filter string
 /// - `selected_path_holder`: Filtered list of matching items
+/// - `tree_guides`: Tree-drawing prefix of each item of `selected_path_holder`
 /// - `current_directory`: Currently displayed directory
 /// - `current_holder`: All items in current directory
 /// - `expand_level`: Current expansion depth (for recursive expand)
+/// - `tree_holder`: Children of the expanded tree nodes, by node path
+/// - `git_status`: Git status of the repository containing `current_directory`
+/// - `git_loading`: Git status being read in the background (if any)
+/// - `canonical_paths`: Canonical path of the listed entries, for git status lookups
+/// - `history_store`: State file the directory history is persisted to (if any)
+/// - `out_of_scope_history`: Saved directories outside `initial_directory`, kept for saving
+/// - `visit_holder`: Visit statistics per directory, used to rank history by frecency
+/// - `loading`: Background directory read in progress (if any)
+/// - `load_budget`: Time a directory read may block before continuing in the background
+/// - `expand_budget`: Maximum number of entries listed by an expansion
+/// - `skipped_paths`: Unreadable directories and entries left out of the listing
+/// - `n_reported_skipped`: Number of `skipped_paths` already reported
+/// - `is_truncated`: Whether the last expansion stopped at `expand_budget`
+/// - `multi_selection`: Entries selected for batch operations, kept across filter changes
 #[derive(Debug)]
 pub struct FolderHolder {
     state_holder: Rc<RefCell<StateHolder>>,
     cache_holder: LruCache<PathBuf, FileGroupHolder>,
     pub input: String,
     pub selected_path_holder: Vec<FileHolder>,
+    pub tree_guides: Vec<String>,
     pub current_directory: PathBuf,
     initial_directory: PathBuf,
     current_holder: Vec<FileHolder>,
     expand_level: usize,
+    tree_holder: HashMap<PathBuf, Vec<FileHolder>>,
+    pub git_status: Option<GitStatusHolder>,
+    git_loading: Option<GitStatusLoader>,
+    canonical_paths: HashMap<PathBuf, Option<PathBuf>>,
+    history_store: Option<HistoryStore>,
+    out_of_scope_history: Vec<HistoryEntry>,
+    visit_holder: HashMap<PathBuf, VisitInfo>,
+    loading: Option<(LoadHolder, LoadTarget)>,
+    pub load_budget: Duration,
+    pub expand_budget: usize,
+    skipped_paths: Vec<PathBuf>,
+    n_reported_skipped: usize,
+    is_truncated: bool,
+    pub multi_selection: BTreeSet<PathBuf>,
 }
 
 impl FolderHolder {
@@ -61,124 +146,282 @@ impl FolderHolder {
         current_directory: PathBuf,
         state_holder: Rc<RefCell<StateHolder>>,
     ) -> AppResult<Self> {
-        let holder = FileGroupHolder::new(current_directory.clone(), true)?;
+        let holder = FileGroupHolder::new(current_directory.clone(), true, SortMode::default())?;
         let current_holder: Vec<FileHolder> = holder.child.clone();
         let mut cache_holder = LruCache::new(
             NonZeroUsize::new(DEFAULT_CACHE_SIZE)
                 .ok_or(AppError::Terminal("Unable to setup the cache!".into()))?,
         );
         cache_holder.put(current_directory.clone(), holder);
+        let mut visit_info = VisitInfo::default();
+        visit_info.visit();
 
         Ok(FolderHolder {
             state_holder,
+            git_status: None,
+            git_loading: Some(GitStatusLoader::spawn(&current_directory)),
+            canonical_paths: HashMap::new(),
             cache_holder,
             initial_directory: current_directory.clone(),
-            current_directory,
+            current_directory: current_directory.clone(),
             input: Default::default(),
+            tree_guides: vec![String::new(); current_holder.len()],
             selected_path_holder: current_holder.clone(),
             current_holder,
             expand_level: 0,
+            tree_holder: HashMap::new(),
+            history_store: None,
+            out_of_scope_history: Vec::new(),
+            visit_holder: HashMap::from([(current_directory, visit_info)]),
+            loading: None,
+            load_budget: LOAD_BUDGET,
+            expand_budget: env::var(EXPAND_BUDGET_ENV)
+                .ok()
+                .and_then(|budget| budget.parse().ok())
+                .unwrap_or(EXPAND_ENTRY_BUDGET),
+            skipped_paths: Vec::new(),
+            n_reported_skipped: 0,
+            is_truncated: false,
+            multi_selection: BTreeSet::new(),
         })
     }
 
-    /// Expands all directories recursively
+    /// Restores the directory history saved by a previous session
     ///
-    /// Reads all subdirectories and adds their contents to the current view.
-    /// Increments the expand level counter.
+    /// Directories are added to the cache as placeholders and read lazily
+    /// when visited, directories that no longer exist are dropped from
+    /// history. Directories outside the initial directory are not shown
+    /// but kept when saving.
+    ///
+    /// # Arguments
+    ///
+    /// * `history_store` - State file to load from and save to
     ///
     /// # Returns
     ///
     /// Returns `AppResult<()>` which may contain:
-    /// - `AppError::Path`: If path resolution fails
-    /// - `AppError::Parse`: If directory reading fails
-    pub fn expand(&mut self) -> AppResult<()> {
-        let paths_to_expand: Vec<PathBuf> = self
-            .current_holder
+    /// - `AppError::Io`: If the state file cannot be read
+    /// - `AppError::Cache`: If the current directory is not cached
+    pub fn load_history(&mut self, history_store: HistoryStore) -> AppResult<()> {
+        let entries = history_store.load()?;
+        if let Some(capacity) = NonZeroUsize::new(history_store.max_size) {
+            self.cache_holder.resize(capacity);
+        }
+
+        let mut out_of_scope_history = Vec::new();
+        for entry in entries.into_iter().rev() {
+            if entry.path == self.current_directory {
+                self.change_sort_mode(|sort_mode| *sort_mode = entry.sort_mode)?;
+                let visit_info = self.visit_holder.entry(entry.path).or_default();
+                visit_info.count = visit_info.count.saturating_add(entry.visit_info.count);
+            } else if Self::is_child_path(&self.initial_directory, &entry.path)? {
+                self.visit_holder
+                    .insert(entry.path.clone(), entry.visit_info);
+                self.cache_holder
+                    .put(entry.path, FileGroupHolder::unloaded(entry.sort_mode));
+            } else {
+                out_of_scope_history.push(entry);
+            }
+        }
+        out_of_scope_history.reverse();
+        out_of_scope_history.retain(|entry| entry.path.is_dir());
+        self.cache_holder.promote(&self.current_directory);
+
+        self.out_of_scope_history = out_of_scope_history;
+        self.history_store = Some(history_store);
+        self.drop_invalid_folders()?;
+        Ok(())
+    }
+
+    /// Drops the history directories that no longer exist
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` if a
+    /// folder is not found in cache
+    fn drop_invalid_folders(&mut self) -> AppResult<()> {
+        let invalid_paths: Vec<PathBuf> = self
+            .cache_holder
             .iter()
-            .skip(1) // ignore ".." case
-            .filter_map(|p| p.to_path_canonicalize().ok())
+            .map(|(path, _)| path)
+            .filter(|path| **path != self.current_directory && !path.is_dir())
+            .cloned()
             .collect();
-        if paths_to_expand.is_empty() {
-            return Ok(());
+        for path in invalid_paths {
+            self.forget_folder(&path)?;
         }
+        self.update(None)
+    }
 
-        let folder_count = paths_to_expand.iter().filter(|&x| x.is_dir()).count();
+    /// Saves the directory history, most recently used first
+    ///
+    /// Directories outside the initial directory are merged back by last
+    /// visit time. Does nothing if no history store was loaded.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be written
+    pub fn save_history(&self) -> AppResult<()> {
+        let Some(history_store) = &self.history_store else {
+            return Ok(());
+        };
+        let entries: Vec<HistoryEntry> = self
+            .cache_holder
+            .iter()
+            .filter(|(path, _)| self.visit_holder.contains_key(*path))
+            .map(|(path, holder)| HistoryEntry {
+                path: path.clone(),
+                sort_mode: holder.sort_mode,
+                visit_info: self.visit_holder.get(path).copied().unwrap_or_default(),
+            })
+            .collect();
+        history_store.save(&merge_by_recency(
+            entries,
+            self.out_of_scope_history.clone(),
+        ))
+    }
 
-        let mut result = Vec::new();
-        let first_item = self.current_holder[0].clone();
-        result.push(first_item);
+    /// Expands all directories recursively
+    ///
+    /// Reads all subdirectories and adds their contents to the current view.
+    /// Increments the expand level counter once read. Unreadable directories
+    /// are skipped and the listing stops at `expand_budget` entries, see
+    /// [`FolderHolder::load_notice`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::State`: If another read is in progress
+    pub fn expand(&mut self) -> AppResult<()> {
+        self.check_not_loading()?;
+        self.clear_load_notice();
+        self.expand_by(1)
+    }
 
-        if folder_count < EXPAND_MULTI_THREAD_THRESHOLD {
-            Self::expand_single(&mut result, paths_to_expand)?;
-        } else {
-            Self::expand_multi_threaded(&mut result, paths_to_expand)?;
+    /// Expands or collapses the listing to a given depth
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Expansion level to reach, 0 being the plain listing
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::State`: If another read is in progress
+    pub fn expand_to_depth(&mut self, depth: usize) -> AppResult<()> {
+        self.check_not_loading()?;
+        self.clear_load_notice();
+        while self.expand_level > depth {
+            self.collapse()?;
         }
+        self.expand_by(depth - self.expand_level)
+    }
 
-        result.sort_by_key(|p| p.to_path());
-        self.current_holder = result;
-        self.update(None)?;
-        self.expand_level = self.expand_level.saturating_add(1);
-
-        Ok(())
+    /// Describes what the listing left out, for the view title
+    ///
+    /// # Returns
+    ///
+    /// Returns the notice, or `None` if every entry is listed
+    pub fn load_notice(&self) -> Option<String> {
+        let mut notices = Vec::new();
+        if self.is_truncated {
+            notices.push(format!("truncated at {} entries", self.expand_budget));
+        }
+        if !self.skipped_paths.is_empty() {
+            notices.push(format!("{} unreadable skipped", self.skipped_paths.len()));
+        }
+        (!notices.is_empty()).then(|| notices.join(", "))
     }
 
-    fn expand_single(holder: &mut Vec<FileHolder>, paths_to_expand: Vec<PathBuf>) -> AppResult<()> {
-        for p in &paths_to_expand {
-            if p.is_dir() {
-                let group = FileGroupHolder::new(p.clone(), false)?;
-                holder.extend(group.child);
-            } else {
-                let file_holder = FileHolder::try_from(p.clone())?;
-                holder.push(file_holder);
-            }
+    /// Lists the unreadable paths skipped since the last call, for the log line
+    ///
+    /// # Returns
+    ///
+    /// Returns the report, or `None` if no new path was skipped
+    pub fn take_skipped_report(&mut self) -> Option<String> {
+        let skipped = &self.skipped_paths[self.n_reported_skipped..];
+        if skipped.is_empty() {
+            return None;
+        }
+        let mut names: Vec<String> = skipped
+            .iter()
+            .take(MAX_REPORTED_PATHS)
+            .map(|path| {
+                path.strip_prefix(&self.current_directory)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        if skipped.len() > MAX_REPORTED_PATHS {
+            names.push(format!("and {} more", skipped.len() - MAX_REPORTED_PATHS));
         }
+        self.n_reported_skipped = self.skipped_paths.len();
+        Some(format!("Skipped unreadable: {}", names.join(", ")))
+    }
 
-        Ok(())
+    fn clear_load_notice(&mut self) {
+        self.skipped_paths.clear();
+        self.n_reported_skipped = 0;
+        self.is_truncated = false;
     }
 
-    fn expand_multi_threaded(
-        holder: &mut Vec<FileHolder>,
-        paths_to_expand: Vec<PathBuf>,
-    ) -> AppResult<()> {
-        let num_threads = std::cmp::min(paths_to_expand.len(), EXPAND_THREAD_COUNT);
-        let chunk_size = paths_to_expand.len().div_ceil(num_threads);
-
-        let (tx, rx) = mpsc::channel();
-        let tx = Arc::new(tx);
-
-        let mut handle_group = Vec::new();
-        for chunk in paths_to_expand.chunks(chunk_size) {
-            let tx = Arc::clone(&tx);
-            let chunk = chunk.to_vec();
-
-            let handle = thread::spawn(move || {
-                for p in chunk {
-                    let result = if p.is_dir() {
-                        FileGroupHolder::new(p.clone(), false).unwrap().child
-                    } else {
-                        vec![FileHolder::try_from(p.clone()).unwrap()]
-                    };
-
-                    if tx.send(result).is_err() {
-                        break; // receiver dropped, stop processing
-                    }
-                }
-            });
+    fn check_not_loading(&self) -> AppResult<()> {
+        if self.loading.is_some() {
+            return Err(AppError::State(
+                "Still loading, press <Esc> to cancel".into(),
+            ));
+        }
+        Ok(())
+    }
 
-            handle_group.push(handle);
+    /// Expands the listing several levels, reading in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - Number of levels to expand
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::State`: If another read is in progress
+    fn expand_by(&mut self, levels: usize) -> AppResult<()> {
+        self.check_not_loading()?;
+        let paths_to_expand: Vec<PathBuf> = self
+            .current_holder
+            .iter()
+            .skip(1) // ignore ".." case
+            .filter_map(|p| p.to_path_canonicalize().ok())
+            .collect();
+        if paths_to_expand.is_empty() || levels == 0 {
+            return Ok(());
         }
 
-        drop(tx);
-        for mut received in rx {
-            holder.append(&mut received);
+        let mut base = vec![self.current_holder[0].clone()];
+        let mut directories = Vec::new();
+        for path in paths_to_expand {
+            if path.is_dir() {
+                directories.push(path);
+            } else {
+                base.push(FileHolder::try_from(path)?);
+            }
         }
-        for handle in handle_group {
-            handle
-                .join()
-                .map_err(|_| AppError::Path("Unable to expand".into()))?;
+        if base.len() >= self.expand_budget {
+            self.is_truncated = true;
+            return Ok(());
         }
-
-        Ok(())
+        let max_entries = self.expand_budget - base.len();
+        self.start_loading(
+            LoadHolder::read_directories(directories, max_entries),
+            LoadTarget::Expand {
+                base,
+                remaining: levels - 1,
+            },
+        )
     }
 
     /// Collapses expanded directories
@@ -195,6 +438,7 @@ impl FolderHolder {
         if self.expand_level == 0 {
             return Ok(());
         }
+        self.is_truncated = false;
         self.expand_level = self.expand_level.saturating_sub(1);
 
         let first_item = self.current_holder[0].clone();
@@ -231,24 +475,143 @@ impl FolderHolder {
         Ok(())
     }
 
-    /// Adds a directory to the cache
+    /// Expands a directory of the listing in place, as a tree node
     ///
     /// # Arguments
     ///
-    /// * `path` - Directory path to cache
+    /// * `index` - Index of the directory in `selected_path_holder`
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain `AppError::Parse` if directory cannot be read
-    pub fn put(&mut self, path: &Path) -> AppResult<()> {
-        let holder = FileGroupHolder::new(path.to_path_buf(), true)?;
-        self.cache_holder.put(path.to_path_buf(), holder);
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::State`: If another read is in progress
+    pub fn expand_node(&mut self, index: usize) -> AppResult<()> {
+        let Some(entry) = self.selected_path_holder.get(index) else {
+            return Ok(());
+        };
+        let path = entry.to_path();
+        if entry.is_file || entry.file_name == ".." || self.tree_holder.contains_key(&path) {
+            return Ok(());
+        }
+        self.check_not_loading()?;
+        let read_dir = open_directory(&path)?;
+        self.start_loading(
+            LoadHolder::read_directory(read_dir),
+            LoadTarget::Tree {
+                paths: vec![path],
+                expand_level: 0,
+            },
+        )
+    }
 
-        Ok(())
+    /// Returns whether a directory listed inside an expanded one is expanded too
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the entry in `selected_path_holder`
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(true)` for an expanded directory, `Some(false)` for a
+    /// collapsed one, `None` for files and entries of the current directory
+    pub fn tree_node_state(&self, index: usize) -> Option<bool> {
+        let entry = self.selected_path_holder.get(index)?;
+        if entry.is_file || !self.tree_holder.contains_key(&entry.parent) {
+            return None;
+        }
+        Some(self.tree_holder.contains_key(&entry.to_path()))
+    }
+
+    /// Collapses the tree node of an entry
+    ///
+    /// The entry itself is collapsed if it is an expanded directory, its
+    /// parent otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the entry in `selected_path_holder`
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<usize>>` with the index of the collapsed
+    /// node, `None` if there was nothing to collapse, which may contain
+    /// `AppError::Path` if path resolution fails
+    pub fn collapse_node(&mut self, index: usize) -> AppResult<Option<usize>> {
+        let Some(entry) = self.selected_path_holder.get(index) else {
+            return Ok(None);
+        };
+        let path = entry.to_path();
+        let node = if self.tree_holder.contains_key(&path) {
+            path
+        } else {
+            entry.parent.clone()
+        };
+        if !self.tree_holder.contains_key(&node) {
+            return Ok(None);
+        }
+        self.tree_holder.retain(|path, _| !path.starts_with(&node));
+        self.update(None)?;
+        Ok(self
+            .selected_path_holder
+            .iter()
+            .position(|entry| entry.to_path() == node))
+    }
+
+    /// Keeps the entries matching the filter, and the ancestors of matching entries
+    fn filter_tree(&self, entries: &[FileHolder]) -> AppResult<Vec<TreeNode>> {
+        let mut nodes = Vec::new();
+        for entry in entries {
+            let children = match self.tree_holder.get(&entry.to_path()) {
+                Some(children) if entry.file_name != ".." => self.filter_tree(children)?,
+                _ => Vec::new(),
+            };
+            if !children.is_empty()
+                || self.should_select(&entry.relative_to(&self.current_directory)?)
+            {
+                nodes.push(TreeNode {
+                    entry: entry.clone(),
+                    children,
+                });
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Lists tree nodes depth first, with their tree-drawing prefix
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Sibling nodes to list
+    /// * `guide` - Prefix drawn for the ancestors of `nodes`, `None` at the top level
+    /// * `entries` - Listed entries
+    /// * `guides` - Prefix of each listed entry
+    fn flatten_tree(
+        nodes: Vec<TreeNode>,
+        guide: Option<&str>,
+        entries: &mut Vec<FileHolder>,
+        guides: &mut Vec<String>,
+    ) {
+        let n_nodes = nodes.len();
+        for (index, node) in nodes.into_iter().enumerate() {
+            let (branch, continuation) = match guide {
+                None => (String::new(), String::new()),
+                Some(guide) if index + 1 == n_nodes => {
+                    (format!("{}└── ", guide), format!("{}    ", guide))
+                }
+                Some(guide) => (format!("{}├── ", guide), format!("{}│   ", guide)),
+            };
+            entries.push(node.entry);
+            guides.push(branch);
+            Self::flatten_tree(node.children, Some(&continuation), entries, guides);
+        }
     }
 
     /// Updates the filtered selection based on search input
     ///
+    /// In history mode, matching cached directories are ranked by frecency.
+    /// Otherwise expanded tree nodes list their children below them.
+    ///
     /// # Arguments
     ///
     /// * `input` - Optional search filter string
@@ -262,23 +625,33 @@ impl FolderHolder {
         }
 
         let mut selected_path_holder = Vec::new();
+        let mut tree_guides = Vec::new();
         if self.state_holder.borrow().is_history_search() {
+            let now = Local::now().timestamp();
+            let mut scored_paths = Vec::new();
+            // previewed directories are cached too but were never visited
             for (path, _) in &self.cache_holder {
+                if !self.visit_holder.contains_key(path) {
+                    continue;
+                }
                 if let Some(path_str) = path.to_str() {
                     if self.should_select(path_str) {
-                        let file_holder = FileHolder::try_from(path.clone())?;
-                        selected_path_holder.push(file_holder);
+                        let visit_info = self.visit_holder.get(path).copied().unwrap_or_default();
+                        scored_paths.push((visit_info.frecency(now), path));
                     }
                 }
             }
-        } else {
-            for file_holder in &self.current_holder {
-                if self.should_select(&file_holder.relative_to(&self.current_directory)?) {
-                    selected_path_holder.push(file_holder.clone());
-                }
+            // stable sort keeps the LRU order between equally ranked folders
+            scored_paths.sort_by(|a, b| b.0.total_cmp(&a.0));
+            for (_, path) in scored_paths {
+                selected_path_holder.push(FileHolder::try_from(path.clone())?);
             }
+        } else {
+            let nodes = self.filter_tree(&self.current_holder)?;
+            Self::flatten_tree(nodes, None, &mut selected_path_holder, &mut tree_guides);
         }
         self.selected_path_holder = selected_path_holder;
+        self.tree_guides = tree_guides;
 
         Ok(())
     }
@@ -295,18 +668,21 @@ impl FolderHolder {
     /// - `AppError::Cache`: If cache lookup fails
     /// - `AppError::Path`: If path resolution fails
     pub fn submit_new_working_directory(&mut self, path: PathBuf) -> AppResult<()> {
-        if self.cache_holder.get(&path).is_none() {
-            self.put(&path)?
-        }
-        let is_valid_child = Self::is_child_path(self.initial_directory.as_path(), path.as_path())?;
-        if !is_valid_child {
-            return Err(AppError::Path(format!(
-                "Cannot goto {} as it is not child of {}",
-                path.display(),
-                self.initial_directory.display()
-            )));
+        self.check_in_scope(&path)?;
+        let read_dir = match self.cache_holder.get(&path) {
+            Some(holder) if holder.is_loaded => None,
+            _ => Some(open_directory(&path)?),
+        };
+        self.cancel_loading();
+        self.clear_load_notice();
+        self.tree_holder.clear();
+        self.multi_selection.clear();
+        if self.cache_holder.peek(&path).is_none() {
+            self.cache_holder
+                .put(path.clone(), FileGroupHolder::unloaded(SortMode::default()));
         }
 
+        self.visit_holder.entry(path.clone()).or_default().visit();
         self.current_directory = path;
         let cache_result =
             self.cache_holder
@@ -321,7 +697,61 @@ impl FolderHolder {
         self.input.clear();
         self.update(None)?;
         self.expand_level = 0;
+        if !self
+            .git_status
+            .as_ref()
+            .is_some_and(|status| status.contains(&self.current_directory))
+        {
+            self.git_status = None;
+            self.load_git_status();
+        }
 
+        if let Some(read_dir) = read_dir {
+            self.show_directory_entries(Vec::new(), false)?;
+            self.start_loading(
+                LoadHolder::read_directory(read_dir),
+                LoadTarget::Directory { expand_level: 0 },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the directory the viewer was started in
+    pub fn initial_directory(&self) -> &Path {
+        &self.initial_directory
+    }
+
+    /// Checks that a directory can be changed to, without changing to it
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory path
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If the directory is outside the initial directory
+    /// - `AppError::Parse`: If the directory cannot be read
+    pub fn check_directory(&self, path: &Path) -> AppResult<()> {
+        self.check_in_scope(path)?;
+        open_directory(path)?;
+        Ok(())
+    }
+
+    /// Checks that a path is inside the initial directory
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Path` if the path
+    /// is outside the initial directory
+    pub fn check_in_scope(&self, path: &Path) -> AppResult<()> {
+        if !Self::is_child_path(self.initial_directory.as_path(), path)? {
+            return Err(AppError::Path(format!(
+                "Cannot goto {} as it is not child of {}",
+                path.display(),
+                self.initial_directory.display()
+            )));
+        }
         Ok(())
     }
 
@@ -340,7 +770,9 @@ impl FolderHolder {
 
     /// Refreshes the current directory cache
     ///
-    /// Re-reads the current directory and updates the cache
+    /// Re-reads the current directory and its git status, and updates the
+    /// cache once read, keeping the expansion level. The previous listing is
+    /// shown meanwhile.
     ///
     /// # Returns
     ///
@@ -348,48 +780,474 @@ impl FolderHolder {
     /// - `AppError::Parse`: If directory cannot be read
     /// - `AppError::Cache`: If cache update fails
     pub fn refresh(&mut self) -> AppResult<()> {
-        let holder = FileGroupHolder::new(self.current_directory.clone(), true)?;
+        let read_dir = open_directory(&self.current_directory)?;
+        self.load_git_status();
+        self.clear_load_notice();
+        self.start_loading(
+            LoadHolder::read_directory(read_dir),
+            LoadTarget::Directory {
+                expand_level: self.expand_level,
+            },
+        )
+    }
+
+    /// Checks if a directory read is in progress
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    /// Describes the directory read in progress, for the view title
+    pub fn loading_status(&self) -> Option<String> {
+        let (load_holder, target) = self.loading.as_ref()?;
+        let action = match target {
+            LoadTarget::Directory { .. } => "loading",
+            LoadTarget::Expand { .. } | LoadTarget::Tree { .. } => "expanding",
+        };
+        Some(format!(
+            "{} {} entries, <Esc> to cancel",
+            action,
+            load_holder.entries.len()
+        ))
+    }
+
+    /// Stops the directory read in progress
+    ///
+    /// Entries of a directory read so far stay listed, the directory is read
+    /// again when visited. A cancelled expansion leaves the listing unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns whether a read was in progress
+    pub fn cancel_loading(&mut self) -> bool {
+        self.loading.take().is_some()
+    }
+
+    /// Applies the entries read in the background since the last check
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<LoadUpdate>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::Cache`: If the current directory is not cached
+    pub fn poll_loading(&mut self) -> AppResult<LoadUpdate> {
+        let Some((load_holder, _)) = &mut self.loading else {
+            return Ok(LoadUpdate::Unchanged);
+        };
+        let status = load_holder.poll();
+        self.apply_load_status(status)
+    }
+
+    /// Starts a background read, waiting up to `load_budget` for it to finish
+    fn start_loading(&mut self, load_holder: LoadHolder, target: LoadTarget) -> AppResult<()> {
+        self.loading = Some((load_holder, target));
+        if self.load_budget.is_zero() {
+            return Ok(());
+        }
+        let budget = self.load_budget;
+        let Some((load_holder, _)) = &mut self.loading else {
+            return Ok(());
+        };
+        let status = load_holder.wait(budget);
+        self.apply_load_status(status)?;
+        Ok(())
+    }
+
+    fn apply_load_status(&mut self, status: LoadStatus) -> AppResult<LoadUpdate> {
+        match status {
+            LoadStatus::Pending => Ok(LoadUpdate::Unchanged),
+            LoadStatus::Progress => {
+                // a listing being read for the first time is shown as it comes
+                let is_loaded = self.peek()?.is_loaded;
+                if let Some((load_holder, LoadTarget::Directory { .. })) = &mut self.loading {
+                    if !is_loaded {
+                        let replaces = !load_holder.has_shown_entries();
+                        let entries = load_holder.new_entries();
+                        self.append_directory_entries(entries, replaces)?;
+                    }
+                }
+                Ok(LoadUpdate::Progress)
+            }
+            LoadStatus::Done(entries) => {
+                let Some((load_holder, target)) = self.loading.take() else {
+                    return Ok(LoadUpdate::Unchanged);
+                };
+                // paths removed meanwhile are not unreadable
+                self.skipped_paths.extend(
+                    load_holder
+                        .skipped
+                        .iter()
+                        .filter(|path| path.symlink_metadata().is_ok())
+                        .cloned(),
+                );
+                self.is_truncated |= load_holder.truncated;
+                match target {
+                    LoadTarget::Directory { expand_level } => {
+                        self.show_directory_entries(entries, true)?;
+                        self.expand_level = 0;
+                        // expanded tree nodes are read again too
+                        let paths: Vec<PathBuf> = self.tree_holder.keys().cloned().collect();
+                        if paths.is_empty() {
+                            self.expand_by(expand_level)?;
+                        } else {
+                            self.start_loading(
+                                LoadHolder::read_directories(paths.clone(), usize::MAX),
+                                LoadTarget::Tree {
+                                    paths,
+                                    expand_level,
+                                },
+                            )?;
+                        }
+                    }
+                    LoadTarget::Tree {
+                        paths,
+                        expand_level,
+                    } => {
+                        let sort_mode = self.sort_mode();
+                        let mut children: HashMap<PathBuf, Vec<FileHolder>> = paths
+                            .into_iter()
+                            .filter(|path| !load_holder.skipped.contains(path))
+                            .map(|path| (path, Vec::new()))
+                            .collect();
+                        for entry in entries {
+                            if let Some(siblings) = children.get_mut(&entry.parent) {
+                                siblings.push(entry);
+                            }
+                        }
+                        for siblings in children.values_mut() {
+                            sort_mode.sort(siblings);
+                        }
+                        self.tree_holder
+                            .retain(|path, _| !load_holder.skipped.contains(path));
+                        self.tree_holder.extend(children);
+                        self.update(None)?;
+                        self.expand_by(expand_level)?;
+                    }
+                    LoadTarget::Expand {
+                        mut base,
+                        remaining,
+                    } => {
+                        base.extend(entries);
+                        // directories left unread stay listed, unexpanded
+                        for path in &load_holder.cut_off {
+                            base.push(FileHolder::try_from(path.clone())?);
+                        }
+                        self.current_holder =
+                            self.sort_mode().sort_tree(&self.current_directory, base);
+                        self.update(None)?;
+                        self.expand_level = self.expand_level.saturating_add(1);
+                        if !self.is_truncated {
+                            self.expand_by(remaining)?;
+                        }
+                    }
+                }
+                if self.loading.is_some() {
+                    Ok(LoadUpdate::Progress)
+                } else {
+                    Ok(LoadUpdate::Finished)
+                }
+            }
+        }
+    }
+
+    /// Shows entries read for the current directory and stores them in its cache
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Entries of the current directory, without ".."
+    /// * `is_loaded` - Whether every entry was read
+    fn show_directory_entries(
+        &mut self,
+        entries: Vec<FileHolder>,
+        is_loaded: bool,
+    ) -> AppResult<()> {
+        let holder = self
+            .cache_holder
+            .get_mut(&self.current_directory)
+            .ok_or(AppError::Cache(format!(
+                "Unable to insert folder cache for {:?}",
+                self.current_directory
+            )))?;
+        let mut child: Vec<FileHolder> = FileHolder::parent_shortcut(&self.current_directory)
+            .into_iter()
+            .chain(entries)
+            .collect();
+        holder.sort_mode.sort(&mut child);
+        holder.child = child;
+        holder.is_loaded = is_loaded;
+        holder.update_time = Local::now();
         self.current_holder = holder.child.clone();
-        self.update(None)?;
+        self.update(None)
+    }
 
-        self.cache_holder
-            .put(self.current_directory.clone(), holder)
+    /// Appends entries read so far for the current directory, unsorted
+    ///
+    /// The listing is sorted once read, by [`FolderHolder::show_directory_entries`].
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Entries read since the last call, without ".."
+    /// * `replaces` - Whether the entries replace the stale listing
+    fn append_directory_entries(
+        &mut self,
+        entries: Vec<FileHolder>,
+        replaces: bool,
+    ) -> AppResult<()> {
+        let holder = self
+            .cache_holder
+            .get_mut(&self.current_directory)
             .ok_or(AppError::Cache(format!(
                 "Unable to insert folder cache for {:?}",
                 self.current_directory
             )))?;
+        if replaces {
+            holder.child = FileHolder::parent_shortcut(&self.current_directory)
+                .into_iter()
+                .collect();
+            self.current_holder = holder.child.clone();
+        }
+        holder.child.extend(entries.iter().cloned());
+        holder.update_time = Local::now();
 
+        let is_history_search = self.state_holder.borrow().is_history_search();
+        let has_subtree = entries
+            .iter()
+            .any(|entry| self.tree_holder.contains_key(&entry.to_path()));
+        self.current_holder.extend(entries.iter().cloned());
+        if replaces || is_history_search || has_subtree {
+            return self.update(None);
+        }
+        // only the new entries need filtering
+        for entry in entries {
+            if self.should_select(&entry.relative_to(&self.current_directory)?) {
+                self.selected_path_holder.push(entry);
+                self.tree_guides.push(String::new());
+            }
+        }
         Ok(())
     }
 
-    /// Checks if an item matches the current filter
-    fn should_select(&self, name: &str) -> bool {
-        Self::should_select_helper(name, &self.input)
+    /// Returns the directories whose entries are listed
+    ///
+    /// This is the current directory and, when expanded, the
+    /// subdirectories whose content is shown.
+    pub fn listed_directories(&self) -> HashSet<PathBuf> {
+        let mut directories: HashSet<PathBuf> = self
+            .current_holder
+            .iter()
+            .filter(|entry| entry.file_name != "..")
+            .map(|entry| entry.parent.clone())
+            .collect();
+        directories.extend(self.tree_holder.keys().cloned());
+        directories.insert(self.current_directory.clone());
+        directories
     }
 
-    /// Helper function for substring matching (case-insensitive)
+    /// Marks cached listings as stale so they are read again when visited
     ///
-    /// Checks if all characters in `input` appear in order in `name`
-    fn should_select_helper(name: &str, input: &str) -> bool {
-        if input.is_empty() {
-            return true;
+    /// # Arguments
+    ///
+    /// * `directories` - Directories whose content changed
+    pub fn invalidate(&mut self, directories: &HashSet<PathBuf>) {
+        for directory in directories {
+            if let Some(holder) = self.cache_holder.peek_mut(directory) {
+                holder.is_loaded = false;
+            }
         }
+    }
 
-        // check if all characters in self.input appear in order (case-insensitive) in name
-        let mut input_iter = input.chars();
-        let mut next_to_match = input_iter.next();
+    /// Starts reading the git status of the current directory in the background
+    ///
+    /// The previous status is shown meanwhile.
+    fn load_git_status(&mut self) {
+        self.canonical_paths.clear();
+        self.git_loading = Some(GitStatusLoader::spawn(&self.current_directory));
+    }
 
-        for name_char in name.chars() {
-            match next_to_match {
-                Some(input_char) if name_char.eq_ignore_ascii_case(&input_char) => {
-                    next_to_match = input_iter.next();
+    /// Applies the git status once read in the background
+    ///
+    /// # Returns
+    ///
+    /// Returns whether the status changed
+    pub fn poll_git_status(&mut self) -> bool {
+        let Some(git_loading) = &self.git_loading else {
+            return false;
+        };
+        let Some(git_status) = git_loading.poll() else {
+            return false;
+        };
+        self.git_status = git_status;
+        self.git_loading = None;
+        true
+    }
+
+    /// Checks if the git status is being read
+    pub fn is_git_status_loading(&self) -> bool {
+        self.git_loading.is_some()
+    }
+
+    /// Returns the git status of each entry of `selected_path_holder`
+    ///
+    /// Entries are looked up by their canonical path when the listed path
+    /// is outside of the repository, like through a symbolic link. The
+    /// canonical paths are kept until the directory is read again.
+    pub fn git_statuses(&mut self) -> Vec<Option<GitStatus>> {
+        let Some(git_status) = &self.git_status else {
+            return vec![None; self.selected_path_holder.len()];
+        };
+        let canonical_paths = &mut self.canonical_paths;
+        self.selected_path_holder
+            .iter()
+            .map(|entry| {
+                if entry.file_name == ".." {
+                    return None;
+                }
+                let path = entry.to_path();
+                if git_status.contains(&path) {
+                    return git_status.status_of(&path);
                 }
-                None => return true,
-                _ => (),
+                canonical_paths
+                    .entry(path)
+                    .or_insert_with(|| entry.to_path_canonicalize().ok())
+                    .as_ref()
+                    .and_then(|path| git_status.status_of(path))
+            })
+            .collect()
+    }
+
+    /// Returns the sort mode remembered for the current directory
+    pub fn sort_mode(&self) -> SortMode {
+        self.peek()
+            .map(|holder| holder.sort_mode)
+            .unwrap_or_default()
+    }
+
+    /// Returns the cached entries of a directory, if still up to date
+    ///
+    /// Used for the directory previews, the listing is None if the directory
+    /// was not read, was invalidated or was modified since.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory path
+    pub fn listing(&self, path: &Path) -> Option<Vec<&FileHolder>> {
+        let holder = self
+            .cache_holder
+            .peek(path)
+            .filter(|holder| holder.is_loaded)?;
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        if DateTime::<Local>::from(modified) > holder.update_time {
+            return None;
+        }
+        Some(
+            holder
+                .child
+                .iter()
+                .filter(|entry| entry.file_name != "..")
+                .collect(),
+        )
+    }
+
+    /// Stores the entries of a directory read for its preview
+    ///
+    /// The directory is cached as if read, so visiting it later shows the
+    /// entries right away, but it stays out of the history until visited.
+    /// When the cache is full, previewed directories are dropped before
+    /// visited ones. The current directory is left to its own loading.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory path
+    /// * `entries` - Entries of the directory, without ".."
+    pub fn store_listing(&mut self, path: PathBuf, entries: Vec<FileHolder>) {
+        if path == self.current_directory {
+            return;
+        }
+        let mut child: Vec<FileHolder> = FileHolder::parent_shortcut(&path)
+            .into_iter()
+            .chain(entries)
+            .collect();
+        if let Some(holder) = self.cache_holder.peek_mut(&path) {
+            holder.sort_mode.sort(&mut child);
+            holder.child = child;
+            holder.is_loaded = true;
+            holder.update_time = Local::now();
+            return;
+        }
+
+        if self.cache_holder.len() == self.cache_holder.cap().get() {
+            let previewed = self
+                .cache_holder
+                .iter()
+                .rev()
+                .map(|(cached, _)| cached)
+                .find(|cached| !self.visit_holder.contains_key(*cached))
+                .cloned();
+            if let Some(previewed) = previewed {
+                self.cache_holder.pop(&previewed);
             }
         }
+        let mut holder = FileGroupHolder::unloaded(SortMode::default());
+        holder.sort_mode.sort(&mut child);
+        holder.child = child;
+        holder.is_loaded = true;
+        self.cache_holder.put(path, holder);
+        self.cache_holder.promote(&self.current_directory);
+    }
+
+    /// Returns the sort mode remembered for a directory, the default if not visited
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory path
+    pub fn sort_mode_of(&self, path: &Path) -> SortMode {
+        self.cache_holder
+            .peek(path)
+            .map(|holder| holder.sort_mode)
+            .unwrap_or_default()
+    }
+
+    /// Changes the sort mode of the current directory
+    ///
+    /// The mode is stored in the directory cache entry so it is restored
+    /// when returning to this directory. The current listing, including
+    /// any expanded entries, is re-sorted in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - Function modifying the current sort mode
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` if the
+    /// current directory is not in cache
+    pub fn change_sort_mode(&mut self, change: impl FnOnce(&mut SortMode)) -> AppResult<()> {
+        let holder = self
+            .cache_holder
+            .get_mut(&self.current_directory)
+            .ok_or(AppError::Cache(format!(
+                "Unable to get cache for {:?}",
+                self.current_directory
+            )))?;
+        change(&mut holder.sort_mode);
+        holder.sort_mode.sort(&mut holder.child);
+        let sort_mode = holder.sort_mode;
+
+        self.current_holder = sort_mode.sort_tree(
+            &self.current_directory,
+            std::mem::take(&mut self.current_holder),
+        );
+        for children in self.tree_holder.values_mut() {
+            sort_mode.sort(children);
+        }
+        self.update(None)?;
+        Ok(())
+    }
 
-        next_to_match.is_none()
+    /// Checks if an item matches the current filter
+    fn should_select(&self, name: &str) -> bool {
+        matches_filter(name, &self.input)
     }
 
     /// Submits a selection and returns the canonicalized path
@@ -406,6 +1264,143 @@ impl FolderHolder {
         self.selected_path_holder[index].to_path_canonicalize()
     }
 
+    /// Returns the position of a filtered entry in the full directory listing
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index in the selected_path_holder
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the entry is not part of the current directory listing
+    pub fn unfiltered_index(&self, index: usize) -> Option<usize> {
+        let path = self.selected_path_holder.get(index)?.to_path();
+        self.current_holder
+            .iter()
+            .position(|entry| entry.to_path() == path)
+    }
+
+    /// Remembers the selected entry of the current directory
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index in the selected_path_holder
+    pub fn remember_selection(&mut self, index: usize) {
+        let Some(entry) = self.selected_path_holder.get(index) else {
+            return;
+        };
+        let path = entry.to_path();
+        self.remember_path(path);
+    }
+
+    /// Remembers an entry of the current directory as the selected one
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the entry, which may not be listed yet
+    pub fn remember_path(&mut self, path: PathBuf) {
+        if let Some(holder) = self.cache_holder.peek_mut(&self.current_directory) {
+            holder.selected = Some(path);
+        }
+    }
+
+    /// Adds an entry to the multi-selection, or removes it if selected
+    ///
+    /// The ".." shortcut cannot be selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index in the selected_path_holder
+    pub fn toggle_selection(&mut self, index: usize) {
+        let Some(entry) = self.selected_path_holder.get(index) else {
+            return;
+        };
+        if entry.file_name == ".." {
+            return;
+        }
+        let path = entry.to_path();
+        if !self.multi_selection.remove(&path) {
+            self.multi_selection.insert(path);
+        }
+    }
+
+    /// Adds every entry kept by the filter to the multi-selection
+    pub fn select_all(&mut self) {
+        let paths = self.filtered_paths();
+        self.multi_selection.extend(paths);
+    }
+
+    /// Toggles the selection of every entry kept by the filter
+    ///
+    /// Selected entries hidden by the filter stay selected.
+    pub fn invert_selection(&mut self) {
+        for path in self.filtered_paths() {
+            if !self.multi_selection.remove(&path) {
+                self.multi_selection.insert(path);
+            }
+        }
+    }
+
+    /// Empties the multi-selection
+    ///
+    /// # Returns
+    ///
+    /// Returns true if some entries were selected
+    pub fn clear_selection(&mut self) -> bool {
+        let was_selected = !self.multi_selection.is_empty();
+        self.multi_selection.clear();
+        was_selected
+    }
+
+    /// Drops the selected entries that no longer exist
+    pub fn prune_selection(&mut self) {
+        self.multi_selection
+            .retain(|path| path.symlink_metadata().is_ok());
+    }
+
+    /// Returns whether an entry is part of the multi-selection
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index in the selected_path_holder
+    pub fn is_selected(&self, index: usize) -> bool {
+        !self.multi_selection.is_empty()
+            && self
+                .selected_path_holder
+                .get(index)
+                .is_some_and(|entry| self.multi_selection.contains(&entry.to_path()))
+    }
+
+    fn filtered_paths(&self) -> Vec<PathBuf> {
+        self.selected_path_holder
+            .iter()
+            .filter(|entry| entry.file_name != "..")
+            .map(FileHolder::to_path)
+            .collect()
+    }
+
+    /// Returns the index of the entry to select after entering the current directory
+    ///
+    /// The entry remembered for the directory is preferred, then the
+    /// directory we came from (when going up), then the first entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_directory` - Directory shown before entering this one
+    pub fn remembered_index(&self, previous_directory: &Path) -> usize {
+        let remembered = self.peek().ok().and_then(|holder| holder.selected.as_ref());
+        remembered
+            .into_iter()
+            .map(PathBuf::as_path)
+            .chain([previous_directory])
+            .find_map(|path| {
+                self.selected_path_holder
+                    .iter()
+                    .position(|entry| entry.to_path() == path)
+            })
+            .unwrap_or(0)
+    }
+
     /// Removes an invalid folder from the history and cache
     ///
     /// Only works in history search mode
@@ -424,11 +1419,15 @@ impl FolderHolder {
             return Err(AppError::State("Must be in history mode".into()));
         }
         let removed = self.selected_path_holder.remove(index);
-        self.cache_holder
-            .pop(&removed.to_path())
-            .ok_or(AppError::Cache(
-                "Must contain the invalid path in cache".into(),
-            ))?;
+        self.forget_folder(&removed.to_path())
+    }
+
+    /// Removes a folder from the history and cache
+    fn forget_folder(&mut self, path: &Path) -> AppResult<()> {
+        self.visit_holder.remove(path);
+        self.cache_holder.pop(path).ok_or(AppError::Cache(
+            "Must contain the invalid path in cache".into(),
+        ))?;
         Ok(())
     }
 
@@ -451,12 +1450,102 @@ impl FolderHolder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_flatten_tree() {
+        let node = |name: &str, children: Vec<TreeNode>| TreeNode {
+            entry: FileHolder {
+                parent: PathBuf::from("/root"),
+                file_name: name.into(),
+                is_file: children.is_empty(),
+                is_symlink: false,
+                size: 0,
+                modified: None,
+            },
+            children,
+        };
+        let nodes = vec![
+            node(
+                "a",
+                vec![node("b", vec![node("c", vec![])]), node("d", vec![])],
+            ),
+            node("e", vec![]),
+        ];
+        let mut entries = Vec::new();
+        let mut guides = Vec::new();
+        FolderHolder::flatten_tree(nodes, None, &mut entries, &mut guides);
+        assert_eq!(guides, vec!["", "├── ", "│   └── ", "└── ", ""]);
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.file_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn test_take_skipped_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let directory = temp_dir.path().to_path_buf();
+        let state_holder = Rc::new(RefCell::new(StateHolder::default()));
+        let mut folder_holder = FolderHolder::new(directory.clone(), state_holder).unwrap();
+        assert_eq!(folder_holder.take_skipped_report(), None);
+
+        // skipped paths are named once, relative to the current directory
+        folder_holder.skipped_paths = (0..MAX_REPORTED_PATHS + 2)
+            .map(|index| directory.join(format!("d{}", index)))
+            .collect();
+        assert_eq!(
+            folder_holder.take_skipped_report().unwrap(),
+            "Skipped unreadable: d0, d1, d2, d3, d4, and 2 more"
+        );
+        assert_eq!(folder_holder.take_skipped_report(), None);
+        folder_holder
+            .skipped_paths
+            .push(PathBuf::from("/elsewhere"));
+        assert_eq!(
+            folder_holder.take_skipped_report().unwrap(),
+            "Skipped unreadable: /elsewhere"
+        );
+    }
 
     #[test]
-    fn test_should_select() {
-        assert!(FolderHolder::should_select_helper("abc", "c"));
-        assert!(FolderHolder::should_select_helper("abc", ""));
-        assert!(!FolderHolder::should_select_helper("abc", "d"));
-        assert!(!FolderHolder::should_select_helper("abc", "abcd"));
+    fn test_store_listing() {
+        let temp_dir = TempDir::new().unwrap();
+        let directory = temp_dir.path().to_path_buf();
+        let state_holder = Rc::new(RefCell::new(StateHolder::default()));
+        let mut folder_holder = FolderHolder::new(directory.clone(), state_holder).unwrap();
+        let entry = |name: &str| FileHolder {
+            parent: PathBuf::from("/elsewhere"),
+            file_name: name.into(),
+            is_file: true,
+            is_symlink: false,
+            size: 0,
+            modified: None,
+        };
+
+        // the listing is kept without the parent shortcut
+        let first = temp_dir.path().join("first");
+        fs::create_dir(&first).unwrap();
+        assert!(folder_holder.listing(&first).is_none());
+        folder_holder.store_listing(first.clone(), vec![entry("b"), entry("a")]);
+        let names: Vec<&str> = folder_holder
+            .listing(&first)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.file_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+
+        // previews are dropped before visited directories
+        folder_holder
+            .cache_holder
+            .resize(NonZeroUsize::new(2).unwrap());
+        let second = temp_dir.path().join("second");
+        fs::create_dir(&second).unwrap();
+        folder_holder.store_listing(second.clone(), Vec::new());
+        assert!(folder_holder.listing(&first).is_none());
+        assert!(folder_holder.listing(&second).is_some());
+        assert!(folder_holder.cache_holder.contains(&directory));
     }
 }
@@ -0,0 +1,199 @@
+use std::fs::{self, File, Metadata};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::file_helper::MAX_FILE_SIZE;
+
+/// Result of checking a followed file for new content
+#[derive(Debug, PartialEq)]
+pub enum FollowUpdate {
+    /// Nothing was written since the last check
+    Unchanged,
+    /// Lines were appended
+    ///
+    /// `text` replaces the last displayed line when `replaces_partial_line`
+    /// is set, as that line was not terminated yet.
+    Appended {
+        text: String,
+        replaces_partial_line: bool,
+    },
+    /// The file was truncated or replaced (e.g. rotated) and was read again
+    Reopened(String),
+}
+
+/// Follows a growing file, like `tail -f`
+///
+/// # Fields
+///
+/// - `file_path`: Followed file
+/// - `offset`: Number of bytes read so far
+/// - `file_id`: Inode of the followed file, used to detect rotation
+/// - `partial_line`: Bytes after the last newline, displayed but not terminated yet
+/// - `is_paused`: Whether the view stopped sticking to the end after a manual scroll
+#[derive(Debug)]
+pub struct FollowHolder {
+    pub file_path: PathBuf,
+    offset: u64,
+    file_id: Option<u64>,
+    partial_line: Vec<u8>,
+    pub is_paused: bool,
+}
+
+impl FollowHolder {
+    /// Starts following a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - File to follow
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<(Self, String)>` with the current content of the file,
+    /// which may contain:
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Path`: If the file is too large
+    pub fn new(file_path: &Path) -> AppResult<(Self, String)> {
+        let mut follow_holder = Self {
+            file_path: file_path.to_path_buf(),
+            offset: 0,
+            file_id: None,
+            partial_line: Vec::new(),
+            is_paused: false,
+        };
+        let content = follow_holder.read_all()?;
+        Ok((follow_holder, content))
+    }
+
+    /// Reads what was written since the last check
+    ///
+    /// A missing file is reported as unchanged, as it may be recreated after
+    /// a rotation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<FollowUpdate>` which may contain:
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Path`: If a reopened file is too large
+    pub fn read_new(&mut self) -> AppResult<FollowUpdate> {
+        let meta_data = match fs::metadata(&self.file_path) {
+            Ok(meta_data) => meta_data,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                return Ok(FollowUpdate::Unchanged)
+            }
+            Err(error) => return Err(error.into()),
+        };
+        if Self::file_id(&meta_data) != self.file_id || meta_data.len() < self.offset {
+            return Ok(FollowUpdate::Reopened(self.read_all()?));
+        }
+        if meta_data.len() == self.offset {
+            return Ok(FollowUpdate::Unchanged);
+        }
+
+        let mut file = File::open(&self.file_path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        self.offset += appended.len() as u64;
+
+        let replaces_partial_line = !self.partial_line.is_empty();
+        let mut bytes = std::mem::take(&mut self.partial_line);
+        bytes.extend(appended);
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        self.partial_line = Self::split_partial_line(&bytes).to_vec();
+        Ok(FollowUpdate::Appended {
+            text,
+            replaces_partial_line,
+        })
+    }
+
+    fn read_all(&mut self) -> AppResult<String> {
+        let meta_data = fs::metadata(&self.file_path)?;
+        if meta_data.len() > MAX_FILE_SIZE {
+            return Err(AppError::Path("File too large".into()));
+        }
+        let bytes = fs::read(&self.file_path)?;
+        self.offset = bytes.len() as u64;
+        self.file_id = Self::file_id(&meta_data);
+        self.partial_line = Self::split_partial_line(&bytes).to_vec();
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Returns the bytes after the last newline
+    fn split_partial_line(bytes: &[u8]) -> &[u8] {
+        match bytes.iter().rposition(|byte| *byte == b'\n') {
+            Some(index) => &bytes[index + 1..],
+            None => bytes,
+        }
+    }
+
+    #[cfg(unix)]
+    fn file_id(meta_data: &Metadata) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt;
+        Some(meta_data.ino())
+    }
+
+    #[cfg(not(unix))]
+    fn file_id(_meta_data: &Metadata) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_new() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("service.log");
+        fs::write(&path, "first\nsecond").unwrap();
+
+        let (mut follow_holder, content) = FollowHolder::new(&path).unwrap();
+        assert_eq!(content, "first\nsecond");
+        assert_eq!(follow_holder.read_new().unwrap(), FollowUpdate::Unchanged);
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b" line\nthird\n").unwrap();
+        assert_eq!(
+            follow_holder.read_new().unwrap(),
+            FollowUpdate::Appended {
+                text: "second line\nthird\n".into(),
+                replaces_partial_line: true,
+            }
+        );
+
+        file.write_all(b"fourth\n").unwrap();
+        assert_eq!(
+            follow_holder.read_new().unwrap(),
+            FollowUpdate::Appended {
+                text: "fourth\n".into(),
+                replaces_partial_line: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_truncate_and_rotate() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("service.log");
+        fs::write(&path, "old content\n").unwrap();
+        let (mut follow_holder, _) = FollowHolder::new(&path).unwrap();
+
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(
+            follow_holder.read_new().unwrap(),
+            FollowUpdate::Reopened("new\n".into())
+        );
+
+        fs::rename(&path, temp_dir.path().join("service.log.1")).unwrap();
+        assert_eq!(follow_holder.read_new().unwrap(), FollowUpdate::Unchanged);
+        fs::write(&path, "rotated\n").unwrap();
+        assert_eq!(
+            follow_holder.read_new().unwrap(),
+            FollowUpdate::Reopened("rotated\n".into())
+        );
+    }
+}
@@ -0,0 +1,737 @@
+use chrono::DateTime;
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::app::app_error::{AppError, AppResult};
+
+/// Git status of a single working tree entry
+///
+/// Variants are ordered by priority: when a directory aggregates the status
+/// of its children, the highest one wins.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum GitStatus {
+    /// Ignored by `.gitignore`
+    Ignored,
+    /// Not tracked by git
+    Untracked,
+    /// Changes staged in the index
+    Staged,
+    /// Changes in the working tree not yet staged
+    Modified,
+    /// Unmerged paths
+    Conflicted,
+}
+
+/// Change of a single line against `HEAD`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LineChange {
+    /// Line does not exist in `HEAD`
+    Added,
+    /// Line differs from `HEAD`
+    Modified,
+    /// Lines were removed right after this line
+    Deleted,
+}
+
+/// Blame information for a single line
+///
+/// # Fields
+///
+/// - `commit`: Abbreviated commit hash (zeros when not committed yet)
+/// - `author`: Author name
+/// - `date`: Author date formatted as `YYYY-MM-DD`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BlameLine {
+    pub commit: String,
+    pub author: String,
+    pub date: String,
+}
+
+/// Per-line git information for an opened file
+///
+/// Both vectors are indexed like `FileTextInfo::formatted_text`.
+///
+/// # Fields
+///
+/// - `line_changes`: Change marker of each line against `HEAD`
+/// - `blame`: Blame of each line, loaded on demand
+#[derive(Debug, Default)]
+pub struct FileGitInfo {
+    pub line_changes: Vec<Option<LineChange>>,
+    pub blame: Option<Vec<BlameLine>>,
+}
+
+/// A commit that touched a file
+///
+/// # Fields
+///
+/// - `hash`: Abbreviated commit hash
+/// - `author`: Author name
+/// - `date`: Author date formatted as `YYYY-MM-DD`
+/// - `subject`: First line of the commit message
+/// - `path`: Path of the file in this commit, relative to the repository root
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    pub path: String,
+}
+
+/// Git status snapshot of a repository
+///
+/// # Fields
+///
+/// - `root`: Top level directory of the working tree
+/// - `branch`: Current branch name (`HEAD` when detached)
+/// - `statuses`: Status per absolute path, directories included
+#[derive(Debug, Default)]
+pub struct GitStatusHolder {
+    pub root: PathBuf,
+    pub branch: Option<String>,
+    statuses: HashMap<PathBuf, GitStatus>,
+}
+
+impl GitStatus {
+    /// Returns the single character marker shown next to an entry
+    pub fn marker(&self) -> char {
+        use GitStatus::*;
+        match self {
+            Ignored => '!',
+            Untracked => '?',
+            Staged => '+',
+            Modified => 'M',
+            Conflicted => 'C',
+        }
+    }
+
+    /// Returns the color used to render the marker
+    pub fn color(&self) -> Color {
+        use GitStatus::*;
+        match self {
+            Ignored => Color::DarkGray,
+            Untracked => Color::LightRed,
+            Staged => Color::LightGreen,
+            Modified => Color::Yellow,
+            Conflicted => Color::Red,
+        }
+    }
+
+    /// Parses the two letter `XY` code of `git status --porcelain`
+    fn from_porcelain(code: &str) -> Option<Self> {
+        let mut chars = code.chars();
+        let index = chars.next()?;
+        let work_tree = chars.next()?;
+        match (index, work_tree) {
+            ('?', '?') => Some(GitStatus::Untracked),
+            ('!', '!') => Some(GitStatus::Ignored),
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => Some(GitStatus::Conflicted),
+            (_, ' ') if index != ' ' => Some(GitStatus::Staged),
+            (_, ' ') => None,
+            _ => Some(GitStatus::Modified),
+        }
+    }
+}
+
+/// Runs a git command in `directory` and returns its stdout
+///
+/// # Returns
+///
+/// Returns `AppResult<String>` which may contain:
+/// - `AppError::Io`: If git cannot be launched
+/// - `AppError::Parse`: If git exits with an error (e.g. not a repository)
+pub fn run_git(directory: &Path, args: &[&str]) -> AppResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(AppError::Parse(format!(
+            "git {} failed in {}",
+            args.join(" "),
+            directory.display()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the top level directory of the repository containing `directory`
+///
+/// # Returns
+///
+/// Returns `None` if `directory` is not inside a git working tree
+pub fn find_repository_root(directory: &Path) -> Option<PathBuf> {
+    run_git(directory, &["rev-parse", "--show-toplevel"])
+        .ok()
+        .map(|out| PathBuf::from(out.trim_end()))
+}
+
+impl LineChange {
+    /// Returns the gutter marker and its color
+    pub fn marker(&self) -> (char, Color) {
+        use LineChange::*;
+        match self {
+            Added => ('+', Color::LightGreen),
+            Modified => ('~', Color::Yellow),
+            Deleted => ('-', Color::LightRed),
+        }
+    }
+}
+
+/// Splits a file path into the directory to run git in and the file name
+fn split_file_path(file_path: &Path) -> AppResult<(&Path, &str)> {
+    let parent = file_path.parent().ok_or(AppError::Path(format!(
+        "Unable to get parent folder for {:?}",
+        file_path
+    )))?;
+    let file_name = file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(AppError::Path(format!(
+            "Unable to get file name for {:?}",
+            file_path
+        )))?;
+    Ok((parent, file_name))
+}
+
+impl FileGitInfo {
+    /// Loads the line change markers of a file against `HEAD`
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path of the opened file
+    /// * `n_rows` - Number of lines of the opened file
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the file is not inside a git working tree or is ignored.
+    /// Untracked files are reported as entirely added.
+    pub fn load(file_path: &Path, n_rows: usize) -> Option<Self> {
+        let (directory, file_name) = split_file_path(file_path).ok()?;
+        find_repository_root(directory)?;
+
+        let is_tracked =
+            run_git(directory, &["ls-files", "--error-unmatch", "--", file_name]).is_ok();
+        let line_changes = if is_tracked {
+            let diff = run_git(
+                directory,
+                &[
+                    "diff",
+                    "--no-color",
+                    "--no-ext-diff",
+                    "-U0",
+                    "HEAD",
+                    "--",
+                    file_name,
+                ],
+            )
+            .ok()?;
+            Self::parse_diff(&diff, n_rows)
+        } else {
+            let is_ignored = run_git(directory, &["check-ignore", "-q", "--", file_name]).is_ok();
+            if is_ignored {
+                return None;
+            }
+            vec![Some(LineChange::Added); n_rows]
+        };
+
+        Some(Self {
+            line_changes,
+            blame: None,
+        })
+    }
+
+    /// Loads the blame of the file if not already loaded
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If the file path is invalid
+    /// - `AppError::Parse`: If git blame fails (e.g. untracked file)
+    pub fn load_blame(&mut self, file_path: &Path) -> AppResult<()> {
+        if self.blame.is_some() {
+            return Ok(());
+        }
+        let (directory, file_name) = split_file_path(file_path)?;
+        let output = run_git(directory, &["blame", "--line-porcelain", "--", file_name])?;
+        self.blame = Some(Self::parse_blame(&output));
+        Ok(())
+    }
+
+    /// Parses `git diff -U0` output into per-line change markers
+    fn parse_diff(diff: &str, n_rows: usize) -> Vec<Option<LineChange>> {
+        let mut line_changes = vec![None; n_rows];
+        let mut mark = |line: usize, change: LineChange| {
+            if let Some(slot) = line.checked_sub(1).and_then(|i| line_changes.get_mut(i)) {
+                *slot = Some(change);
+            }
+        };
+
+        for hunk in diff.lines().filter_map(|line| line.strip_prefix("@@ -")) {
+            let mut ranges = hunk.split(' ');
+            let (Some(old), Some(new)) = (ranges.next(), ranges.next()) else {
+                continue;
+            };
+            let (_, old_count) = Self::parse_range(old);
+            let (new_start, new_count) = Self::parse_range(new.trim_start_matches('+'));
+
+            if new_count == 0 {
+                // deletion is reported after `new_start`, show it on that line
+                mark(new_start.max(1), LineChange::Deleted);
+                continue;
+            }
+            for offset in 0..new_count {
+                let change = if offset < old_count {
+                    LineChange::Modified
+                } else {
+                    LineChange::Added
+                };
+                mark(new_start + offset, change);
+            }
+        }
+        line_changes
+    }
+
+    /// Parses a `start,count` hunk range, count defaults to 1
+    fn parse_range(range: &str) -> (usize, usize) {
+        let mut parts = range.split(',');
+        let start = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+        let count = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+        (start, count)
+    }
+
+    /// Parses `git blame --line-porcelain` output
+    fn parse_blame(output: &str) -> Vec<BlameLine> {
+        let mut blame = Vec::new();
+        let mut current = BlameLine {
+            commit: String::new(),
+            author: String::new(),
+            date: String::new(),
+        };
+        let mut expect_header = true;
+
+        for line in output.lines() {
+            if expect_header {
+                current.commit = line.chars().take(7).collect();
+                expect_header = false;
+            } else if line.starts_with('\t') {
+                blame.push(current.clone());
+                expect_header = true;
+            } else if let Some(author) = line.strip_prefix("author ") {
+                current.author = author.to_string();
+            } else if let Some(time) = line.strip_prefix("author-time ") {
+                current.date = time
+                    .parse()
+                    .ok()
+                    .and_then(|t| DateTime::from_timestamp(t, 0))
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+            }
+        }
+        blame
+    }
+}
+
+impl CommitInfo {
+    /// Lists the commits that touched a file, newest first
+    ///
+    /// Renames are followed, each commit records the path it used.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Vec<Self>>` which may contain:
+    /// - `AppError::Path`: If the file path is invalid
+    /// - `AppError::Parse`: If git log fails (e.g. not a repository)
+    pub fn load_file_log(file_path: &Path) -> AppResult<Vec<Self>> {
+        let (directory, file_name) = split_file_path(file_path)?;
+        let output = run_git(
+            directory,
+            &[
+                "log",
+                "--follow",
+                "--name-only",
+                "--date=short",
+                "--format=%x1e%h%x1f%an%x1f%ad%x1f%s",
+                "--",
+                file_name,
+            ],
+        )?;
+        Ok(Self::parse_log(&output))
+    }
+
+    /// Parses the output of `git log --name-only` with the format used by
+    /// [`CommitInfo::load_file_log`]
+    fn parse_log(output: &str) -> Vec<Self> {
+        output
+            .split('\x1e')
+            .filter_map(|record| {
+                let mut lines = record.lines();
+                let mut fields = lines.next()?.split('\x1f');
+                let hash = fields.next()?.to_string();
+                let author = fields.next()?.to_string();
+                let date = fields.next()?.to_string();
+                let subject = fields.next().unwrap_or_default().to_string();
+                let path = lines.find(|line| !line.is_empty())?.to_string();
+                Some(Self {
+                    hash,
+                    author,
+                    date,
+                    subject,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads the content of the file at this commit
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Top level directory of the repository
+    pub fn show_revision(&self, root: &Path) -> AppResult<String> {
+        run_git(root, &["show", &format!("{}:{}", self.hash, self.path)])
+    }
+
+    /// Reads the diff of the file introduced by this commit
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Top level directory of the repository
+    pub fn show_diff(&self, root: &Path) -> AppResult<String> {
+        run_git(
+            root,
+            &[
+                "show",
+                "--no-color",
+                "--no-ext-diff",
+                "--format=",
+                &self.hash,
+                "--",
+                &self.path,
+            ],
+        )
+    }
+}
+
+/// Git status read on a background thread
+///
+/// # Fields
+///
+/// - `receiver`: Status sent by the worker thread once read
+#[derive(Debug)]
+pub struct GitStatusLoader {
+    receiver: Receiver<Option<GitStatusHolder>>,
+}
+
+impl GitStatusLoader {
+    /// Starts reading the git status of the repository containing `directory`
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - Directory whose repository status is read
+    pub fn spawn(directory: &Path) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let directory = directory.to_path_buf();
+        thread::spawn(move || {
+            let _ = sender.send(GitStatusHolder::load(&directory));
+        });
+        Self { receiver }
+    }
+
+    /// Returns the status once read
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` while reading, then the status, itself `None` outside
+    /// of a git working tree
+    pub fn poll(&self) -> Option<Option<GitStatusHolder>> {
+        match self.receiver.try_recv() {
+            Ok(git_status) => Some(git_status),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+/// Line changes, and blame if asked for, of a file read on a background thread
+///
+/// # Fields
+///
+/// - `receiver`: Git information sent by the worker thread once read
+#[derive(Debug)]
+pub struct FileGitLoader {
+    receiver: Receiver<Option<FileGitInfo>>,
+}
+
+impl FileGitLoader {
+    /// Starts reading the git information of a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path of the opened file
+    /// * `n_rows` - Number of lines of the opened file
+    /// * `with_blame` - Whether the blame is read too
+    pub fn spawn(file_path: &Path, n_rows: usize, with_blame: bool) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let file_path = file_path.to_path_buf();
+        thread::spawn(move || {
+            let mut file_git_info = FileGitInfo::load(&file_path, n_rows);
+            if let Some(file_git_info) = file_git_info.as_mut().filter(|_| with_blame) {
+                let _ = file_git_info.load_blame(&file_path);
+            }
+            let _ = sender.send(file_git_info);
+        });
+        Self { receiver }
+    }
+
+    /// Returns the git information once read
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` while reading, then the information, itself `None` if
+    /// the file is not tracked by git
+    pub fn poll(&self) -> Option<Option<FileGitInfo>> {
+        match self.receiver.try_recv() {
+            Ok(file_git_info) => Some(file_git_info),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+impl GitStatusHolder {
+    /// Loads the git status of the repository containing `directory`
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if `directory` is not inside a git working tree or the
+    /// status cannot be read
+    pub fn load(directory: &Path) -> Option<Self> {
+        let root = find_repository_root(directory)?;
+        let output = run_git(
+            &root,
+            &["status", "--porcelain=v1", "-z", "--branch", "--ignored"],
+        )
+        .ok()?;
+        Some(Self::parse(root, &output))
+    }
+
+    /// Parses the output of `git status --porcelain=v1 -z --branch`
+    fn parse(root: PathBuf, output: &str) -> Self {
+        let mut holder = GitStatusHolder {
+            root,
+            ..Default::default()
+        };
+
+        let mut records = output.split('\0');
+        while let Some(record) = records.next() {
+            if let Some(header) = record.strip_prefix("## ") {
+                holder.branch = Some(Self::parse_branch(header));
+                continue;
+            }
+            if record.len() < 4 {
+                continue;
+            }
+            let (code, path) = record.split_at(3);
+            if code.starts_with('R') || code.starts_with('C') {
+                // renames and copies are followed by the original path
+                records.next();
+            }
+            if let Some(status) = GitStatus::from_porcelain(code) {
+                holder.insert(path.trim_end_matches('/'), status);
+            }
+        }
+        holder
+    }
+
+    fn parse_branch(header: &str) -> String {
+        if let Some(branch) = header.strip_prefix("No commits yet on ") {
+            return branch.to_string();
+        }
+        if header.starts_with("HEAD (no branch)") {
+            return "HEAD".into();
+        }
+        header
+            .split("...")
+            .next()
+            .unwrap_or(header)
+            .split(' ')
+            .next()
+            .unwrap_or(header)
+            .to_string()
+    }
+
+    /// Records the status of `relative_path` and aggregates it into the
+    /// parent directories
+    ///
+    /// Ignored entries are not propagated, a directory is not ignored just
+    /// because it contains an ignored file.
+    fn insert(&mut self, relative_path: &str, status: GitStatus) {
+        let path = self.root.join(relative_path);
+        self.statuses.insert(path.clone(), status);
+        if status == GitStatus::Ignored {
+            return;
+        }
+
+        let mut parent = path.parent();
+        while let Some(dir) = parent {
+            if !dir.starts_with(&self.root) || dir == self.root {
+                break;
+            }
+            let entry = self.statuses.entry(dir.to_path_buf()).or_insert(status);
+            if *entry < status {
+                *entry = status;
+            }
+            parent = dir.parent();
+        }
+    }
+
+    /// Returns the status of `path`
+    ///
+    /// Entries inside an untracked or ignored directory inherit its status.
+    pub fn status_of(&self, path: &Path) -> Option<GitStatus> {
+        if let Some(status) = self.statuses.get(path) {
+            return Some(*status);
+        }
+        let mut parent = path.parent();
+        while let Some(dir) = parent {
+            if !dir.starts_with(&self.root) || dir == self.root {
+                break;
+            }
+            match self.statuses.get(dir) {
+                Some(status @ (GitStatus::Untracked | GitStatus::Ignored)) => return Some(*status),
+                Some(_) => return None,
+                None => parent = dir.parent(),
+            }
+        }
+        None
+    }
+
+    /// Checks if `path` is inside this repository's working tree
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = "## main...origin/main [ahead 1]\0 M src/lib.rs\0A  new.rs\0?? tmp/\0!! target/\0UU conflict.rs\0R  renamed.rs\0old.rs\0";
+        let holder = GitStatusHolder::parse(PathBuf::from("/repo"), output);
+
+        assert_eq!(holder.branch.as_deref(), Some("main"));
+        let status = |p: &str| holder.status_of(Path::new(p));
+        assert_eq!(status("/repo/src/lib.rs"), Some(GitStatus::Modified));
+        assert_eq!(status("/repo/src"), Some(GitStatus::Modified));
+        assert_eq!(status("/repo/new.rs"), Some(GitStatus::Staged));
+        assert_eq!(status("/repo/tmp/a/b.txt"), Some(GitStatus::Untracked));
+        assert_eq!(status("/repo/target"), Some(GitStatus::Ignored));
+        assert_eq!(status("/repo/conflict.rs"), Some(GitStatus::Conflicted));
+        assert_eq!(status("/repo/renamed.rs"), Some(GitStatus::Staged));
+        assert_eq!(status("/repo/old.rs"), None);
+        assert_eq!(status("/repo/src/clean.rs"), None);
+    }
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -2 +2 @@ a\n-b\n+B\n@@ -4,0 +5 @@ d\n+e\n@@ -7,2 +6,0 @@\n-x\n-y\n";
+        let changes = FileGitInfo::parse_diff(diff, 6);
+        assert_eq!(
+            changes,
+            vec![
+                None,
+                Some(LineChange::Modified),
+                None,
+                None,
+                Some(LineChange::Added),
+                Some(LineChange::Deleted),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_blame() {
+        let output = "2293c083d261edbb84d999f6cfef8ff7bda31a5f 1 1 1\nauthor X\nauthor-time 0\nsummary init\n\ta\n0000000000000000000000000000000000000000 2 2 1\nauthor Not Committed Yet\nauthor-time 86400\n\tB\n";
+        let blame = FileGitInfo::parse_blame(output);
+        assert_eq!(
+            blame,
+            vec![
+                BlameLine {
+                    commit: "2293c08".into(),
+                    author: "X".into(),
+                    date: "1970-01-01".into(),
+                },
+                BlameLine {
+                    commit: "0000000".into(),
+                    author: "Not Committed Yet".into(),
+                    date: "1970-01-02".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let output = "\x1eabc1234\x1fX\x1f2024-01-02\x1fsecond: commit\n\nsrc/new.rs\n\x1edef5678\x1fY\x1f2024-01-01\x1finit\n\nold.rs\n";
+        let commits = CommitInfo::parse_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc1234");
+        assert_eq!(commits[0].subject, "second: commit");
+        assert_eq!(commits[0].path, "src/new.rs");
+        assert_eq!(commits[1].author, "Y");
+        assert_eq!(commits[1].path, "old.rs");
+    }
+
+    #[test]
+    fn test_parse_branch() {
+        assert_eq!(GitStatusHolder::parse_branch("main"), "main");
+        assert_eq!(
+            GitStatusHolder::parse_branch("No commits yet on master"),
+            "master"
+        );
+        assert_eq!(GitStatusHolder::parse_branch("HEAD (no branch)"), "HEAD");
+        assert_eq!(
+            GitStatusHolder::parse_branch("feature...origin/feature"),
+            "feature"
+        );
+    }
+
+    #[test]
+    fn test_status_loader_outside_repository() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let loader = GitStatusLoader::spawn(temp_dir.path());
+        let git_status = loop {
+            if let Some(git_status) = loader.poll() {
+                break git_status;
+            }
+            thread::sleep(std::time::Duration::from_millis(1));
+        };
+        assert!(git_status.is_none());
+    }
+
+    #[test]
+    fn test_file_loader_outside_repository() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("notes.txt");
+        std::fs::write(&file_path, "notes\n").unwrap();
+        let loader = FileGitLoader::spawn(&file_path, 1, true);
+        let file_git_info = loop {
+            if let Some(file_git_info) = loader.poll() {
+                break file_git_info;
+            }
+            thread::sleep(std::time::Duration::from_millis(1));
+        };
+        assert!(file_git_info.is_none());
+    }
+}
@@ -0,0 +1,417 @@
+use chrono::Local;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::file_sorter::SortMode;
+use crate::message_holder::filter_helper::matches_filter;
+
+/// Environment variable overriding the number of directories kept in history
+pub const HISTORY_SIZE_ENV: &str = "ATHENA_VIEWER_HISTORY_SIZE";
+
+/// Number of directories kept in history by default
+pub const DEFAULT_HISTORY_SIZE: usize = 500;
+
+/// Name of the application folder under the XDG data directory
+const APP_DATA_DIR: &str = "athena_viewer";
+
+/// Name of the history state file
+const HISTORY_FILE_NAME: &str = "history";
+
+/// Seconds in an hour, used to age frecency scores
+const HOUR: i64 = 60 * 60;
+
+/// Visit statistics of a directory, used for frecency ranking
+///
+/// # Fields
+///
+/// - `count`: Number of visits
+/// - `last_visit`: Unix timestamp (seconds) of the last visit
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct VisitInfo {
+    pub count: u32,
+    pub last_visit: i64,
+}
+
+/// A visited directory saved across sessions
+///
+/// # Fields
+///
+/// - `path`: Directory path
+/// - `sort_mode`: Sort mode remembered for the directory
+/// - `visit_info`: Visit statistics of the directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    pub sort_mode: SortMode,
+    pub visit_info: VisitInfo,
+}
+
+impl VisitInfo {
+    /// Records a visit happening now
+    pub fn visit(&mut self) {
+        self.count = self.count.saturating_add(1);
+        self.last_visit = Local::now().timestamp();
+    }
+
+    /// Returns the frecency score at `now` (unix timestamp in seconds)
+    ///
+    /// Like zoxide, the visit count is weighted by how recent the last
+    /// visit is: x4 within the hour, x2 within the day, /2 within the week
+    /// and /4 otherwise.
+    pub fn frecency(&self, now: i64) -> f64 {
+        let age = now.saturating_sub(self.last_visit);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < 24 * HOUR {
+            2.0
+        } else if age < 7 * 24 * HOUR {
+            0.5
+        } else {
+            0.25
+        };
+        self.count as f64 * weight
+    }
+}
+
+/// Replaces a state file atomically, creating its parent directories
+///
+/// # Returns
+///
+/// Returns `AppResult<()>` which may contain `AppError::Io` if the file
+/// cannot be written
+pub fn write_atomically(path: &Path, content: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Merges two histories, most recently visited first
+///
+/// Both histories are most recently used first, their own order is kept.
+///
+/// # Arguments
+///
+/// * `first` - History entries, most recently used first
+/// * `second` - Other history entries, most recently used first
+pub fn merge_by_recency(first: Vec<HistoryEntry>, second: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    let mut merged = Vec::with_capacity(first.len() + second.len());
+    let mut first = first.into_iter().peekable();
+    let mut second = second.into_iter().peekable();
+    loop {
+        let next = match (first.peek(), second.peek()) {
+            (Some(a), Some(b)) if b.visit_info.last_visit > a.visit_info.last_visit => {
+                second.next()
+            }
+            (Some(_), _) => first.next(),
+            (None, _) => second.next(),
+        };
+        match next {
+            Some(entry) => merged.push(entry),
+            None => return merged,
+        }
+    }
+}
+
+/// Returns the existing directory ranking best for `query`
+///
+/// Directories are filtered with the same subsequence matcher as the
+/// search views and ranked by frecency.
+///
+/// # Arguments
+///
+/// * `entries` - Saved history entries
+/// * `query` - Search query
+pub fn best_match<'a>(entries: &'a [HistoryEntry], query: &str) -> Option<&'a HistoryEntry> {
+    let now = Local::now().timestamp();
+    entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .path
+                .to_str()
+                .is_some_and(|path| matches_filter(path, query))
+        })
+        .filter(|entry| entry.path.is_dir())
+        .max_by(|a, b| {
+            a.visit_info
+                .frecency(now)
+                .total_cmp(&b.visit_info.frecency(now))
+        })
+}
+
+/// Reads and writes the directory history state file
+///
+/// The file holds one directory per line, most recently used first, with
+/// tab separated fields: path, sort key, reverse flag, directories-first flag,
+/// visit count and last visit timestamp.
+///
+/// # Fields
+///
+/// - `path`: Location of the state file
+/// - `max_size`: Maximum number of directories kept
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    pub path: PathBuf,
+    pub max_size: usize,
+}
+
+/// Returns the application data directory
+///
+/// Uses `$XDG_DATA_HOME/athena_viewer`, falling back to
+/// `$HOME/.local/share/athena_viewer`.
+///
+/// # Returns
+///
+/// Returns `AppResult<PathBuf>` which may contain `AppError::Path` if neither
+/// variable is set
+pub fn data_dir() -> AppResult<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .ok_or(AppError::Path("Unable to locate the data directory".into()))?;
+    Ok(base.join(APP_DATA_DIR))
+}
+
+impl HistoryStore {
+    /// Creates a store for a given state file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Location of the state file
+    /// * `max_size` - Maximum number of directories kept
+    pub fn new(path: PathBuf, max_size: usize) -> Self {
+        Self { path, max_size }
+    }
+
+    /// Creates a store in the XDG data directory
+    ///
+    /// The size defaults to the directory cache size and can be overridden
+    /// with the `ATHENA_VIEWER_HISTORY_SIZE` environment variable.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain:
+    /// - `AppError::Path`: If the data directory cannot be located
+    /// - `AppError::Parse`: If the size override is not a positive number
+    pub fn from_env() -> AppResult<Self> {
+        let max_size = match env::var(HISTORY_SIZE_ENV) {
+            Ok(value) => {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .ok_or(AppError::Parse(format!(
+                        "{} must be a positive number, got {}",
+                        HISTORY_SIZE_ENV, value
+                    )))?
+            }
+            Err(_) => DEFAULT_HISTORY_SIZE,
+        };
+        Ok(Self::new(data_dir()?.join(HISTORY_FILE_NAME), max_size))
+    }
+
+    /// Loads the saved history, most recently used first
+    ///
+    /// A missing state file yields an empty history. Malformed lines are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Vec<HistoryEntry>>` which may contain `AppError::Io`
+    /// if the state file exists but cannot be read
+    pub fn load(&self) -> AppResult<Vec<HistoryEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)?;
+        Ok(content
+            .lines()
+            .filter_map(Self::parse_line)
+            .take(self.max_size)
+            .collect())
+    }
+
+    /// Saves the history, most recently used first
+    ///
+    /// Entries beyond `max_size` are dropped. The file is replaced atomically.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be written
+    pub fn save(&self, entries: &[HistoryEntry]) -> AppResult<()> {
+        let content: String = entries
+            .iter()
+            .filter_map(Self::format_line)
+            .take(self.max_size)
+            .collect();
+        write_atomically(&self.path, &content)
+    }
+
+    fn parse_line(line: &str) -> Option<HistoryEntry> {
+        let mut fields = line.split('\t');
+        let path = PathBuf::from(fields.next().filter(|path| !path.is_empty())?);
+        let mut sort_mode = SortMode::default();
+        if let Some(key) = fields.next().and_then(|key| key.parse().ok()) {
+            sort_mode.key = key;
+        }
+        sort_mode.reverse = fields.next() == Some("1");
+        sort_mode.directories_first = fields.next() == Some("1");
+        let visit_info = VisitInfo {
+            count: fields
+                .next()
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(1),
+            last_visit: fields
+                .next()
+                .and_then(|time| time.parse().ok())
+                .unwrap_or(0),
+        };
+        Some(HistoryEntry {
+            path,
+            sort_mode,
+            visit_info,
+        })
+    }
+
+    fn format_line(entry: &HistoryEntry) -> Option<String> {
+        let path = entry.path.to_str()?;
+        if path.contains(['\t', '\n']) {
+            return None;
+        }
+        Some(format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            path,
+            entry.sort_mode.key,
+            entry.sort_mode.reverse as u8,
+            entry.sort_mode.directories_first as u8,
+            entry.visit_info.count,
+            entry.visit_info.last_visit
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message_holder::file_sorter::SortKey;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = HistoryStore::new(temp_dir.path().join("nested/history"), 2);
+        assert!(store.load().unwrap().is_empty());
+
+        let sort_mode = SortMode {
+            key: SortKey::Size,
+            reverse: true,
+            directories_first: false,
+        };
+        let entries = vec![
+            HistoryEntry {
+                path: PathBuf::from("/a"),
+                sort_mode,
+                visit_info: VisitInfo {
+                    count: 3,
+                    last_visit: 100,
+                },
+            },
+            HistoryEntry {
+                path: PathBuf::from("/b"),
+                sort_mode: SortMode::default(),
+                visit_info: VisitInfo::default(),
+            },
+            HistoryEntry {
+                path: PathBuf::from("/c"),
+                sort_mode: SortMode::default(),
+                visit_info: VisitInfo::default(),
+            },
+        ];
+        store.save(&entries).unwrap();
+        assert_eq!(store.load().unwrap(), entries[..2].to_vec());
+    }
+
+    #[test]
+    fn test_parse_line_defaults() {
+        let entry = HistoryStore::parse_line("/a").unwrap();
+        assert_eq!(entry.path, PathBuf::from("/a"));
+        assert_eq!(entry.sort_mode, SortMode::default());
+        assert_eq!(entry.visit_info.count, 1);
+        assert!(HistoryStore::parse_line("").is_none());
+    }
+
+    #[test]
+    fn test_merge_by_recency() {
+        let entry = |path: &str, last_visit| HistoryEntry {
+            path: PathBuf::from(path),
+            sort_mode: SortMode::default(),
+            visit_info: VisitInfo {
+                count: 1,
+                last_visit,
+            },
+        };
+        let first = vec![entry("/a", 50), entry("/b", 30), entry("/c", 10)];
+        let second = vec![entry("/x", 40), entry("/y", 20)];
+        let paths: Vec<PathBuf> = merge_by_recency(first, second)
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(
+            paths,
+            ["/a", "/x", "/b", "/y", "/c"].map(PathBuf::from).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_frecency() {
+        let now = 10 * 24 * HOUR;
+        let recent = VisitInfo {
+            count: 1,
+            last_visit: now - 10,
+        };
+        let frequent = VisitInfo {
+            count: 10,
+            last_visit: now - 2 * 24 * HOUR,
+        };
+        let old = VisitInfo {
+            count: 10,
+            last_visit: 0,
+        };
+        assert_eq!(recent.frecency(now), 4.0);
+        assert_eq!(frequent.frecency(now), 5.0);
+        assert_eq!(old.frecency(now), 2.5);
+    }
+
+    #[test]
+    fn test_best_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        let other = temp_dir.path().join("proj_other");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let now = Local::now().timestamp();
+        let entry = |path: &PathBuf, count| HistoryEntry {
+            path: path.clone(),
+            sort_mode: SortMode::default(),
+            visit_info: VisitInfo {
+                count,
+                last_visit: now,
+            },
+        };
+        let entries = vec![
+            entry(&temp_dir.path().join("project_removed"), 100),
+            entry(&other, 2),
+            entry(&project, 5),
+        ];
+        assert_eq!(best_match(&entries, "proj").unwrap().path, project);
+        assert_eq!(best_match(&entries, "other").unwrap().path, other);
+        assert!(best_match(&entries, "missing").is_none());
+    }
+}
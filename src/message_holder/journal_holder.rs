@@ -0,0 +1,301 @@
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::trash_holder::{move_path, TrashEntry, TrashHolder};
+
+/// Maximum number of operations that can be undone
+pub const MAX_JOURNAL_SIZE: usize = 100;
+
+/// A file operation that can be undone
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// An item moved to the trash
+    Trash(TrashEntry),
+    /// An item renamed or moved to another directory
+    Move { from: PathBuf, to: PathBuf },
+}
+
+impl Operation {
+    /// Returns the paths touched by the operation, for refreshing the listings
+    pub fn paths(&self) -> Vec<PathBuf> {
+        match self {
+            Operation::Trash(entry) => vec![entry.original_path.clone()],
+            Operation::Move { from, to } => vec![from.clone(), to.clone()],
+        }
+    }
+
+    /// Describes the operation for the log line
+    pub fn describe(&self) -> String {
+        match self {
+            Operation::Trash(entry) => format!("trash of {}", entry.original_path.display()),
+            Operation::Move { from, to } => {
+                format!("move of {} to {}", from.display(), to.display())
+            }
+        }
+    }
+
+    /// Reverts the operation
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>`, the operation to run again on redo
+    fn undo(&self, trash_holder: &TrashHolder) -> AppResult<Self> {
+        match self {
+            Operation::Trash(entry) => trash_holder.restore(entry)?,
+            Operation::Move { from, to } => move_unless_exists(to, from)?,
+        }
+        Ok(self.clone())
+    }
+
+    /// Runs the operation again
+    ///
+    /// An item trashed again may get another name in the trash.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>`, the operation to revert on undo
+    fn redo(&self, trash_holder: &TrashHolder) -> AppResult<Self> {
+        match self {
+            Operation::Trash(entry) => {
+                Ok(Operation::Trash(trash_holder.trash(&entry.original_path)?))
+            }
+            Operation::Move { from, to } => {
+                move_unless_exists(from, to)?;
+                Ok(self.clone())
+            }
+        }
+    }
+}
+
+/// Moves an item, refusing to replace what exists at the destination
+fn move_unless_exists(from: &Path, to: &Path) -> AppResult<()> {
+    if to.symlink_metadata().is_ok() {
+        return Err(AppError::Path(format!(
+            "Unable to move to {}, it exists already",
+            to.display()
+        )));
+    }
+    move_path(from, to)?;
+    Ok(())
+}
+
+/// Describes a group of operations for the log line
+///
+/// # Arguments
+///
+/// * `operations` - Operations run by one action
+pub fn describe_group(operations: &[Operation]) -> String {
+    match operations {
+        [operation] => operation.describe(),
+        operations => format!("{} file operations", operations.len()),
+    }
+}
+
+/// Journal of the file operations of the session, for undo and redo
+///
+/// Operations run by one action, like deleting several entries, form a
+/// group that is undone and redone at once. Running a new action drops
+/// the groups that could be redone. When an operation of a group fails to
+/// be undone or redone, the operations not reached yet stay where they
+/// were, so they can be tried again.
+///
+/// # Fields
+///
+/// - `undo_stack`: Groups that can be undone, oldest first
+/// - `redo_stack`: Undone groups that can be redone, latest undone last
+#[derive(Debug, Default)]
+pub struct JournalHolder {
+    undo_stack: Vec<Vec<Operation>>,
+    redo_stack: Vec<Vec<Operation>>,
+}
+
+impl JournalHolder {
+    /// Records an operation that was just run
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation
+    pub fn record(&mut self, operation: Operation) {
+        self.record_group(vec![operation]);
+    }
+
+    /// Records the operations run by one action
+    ///
+    /// # Arguments
+    ///
+    /// * `operations` - The operations, in the order they were run
+    pub fn record_group(&mut self, operations: Vec<Operation>) {
+        if operations.is_empty() {
+            return;
+        }
+        self.redo_stack.clear();
+        self.undo_stack.push(operations);
+        if self.undo_stack.len() > MAX_JOURNAL_SIZE {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Reverts the latest group of operations, latest operation first
+    ///
+    /// # Arguments
+    ///
+    /// * `trash_holder` - Trash the trashed items are restored from
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Vec<Operation>>` with the reverted operations, which may contain:
+    /// - `AppError::State`: If there is nothing to undo
+    /// - `AppError::Path`: If something else now exists at the original location
+    /// - `AppError::Io`: If an item cannot be moved back
+    pub fn undo(&mut self, trash_holder: &TrashHolder) -> AppResult<Vec<Operation>> {
+        let mut pending = self
+            .undo_stack
+            .pop()
+            .ok_or(AppError::State("Nothing to undo".into()))?;
+        let mut undone = Vec::new();
+        while let Some(operation) = pending.pop() {
+            match operation.undo(trash_holder) {
+                Ok(operation) => undone.insert(0, operation),
+                Err(error) => {
+                    pending.push(operation);
+                    self.undo_stack.push(pending);
+                    if !undone.is_empty() {
+                        self.redo_stack.push(undone);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        self.redo_stack.push(undone.clone());
+        Ok(undone)
+    }
+
+    /// Runs the latest undone group of operations again, in their order
+    ///
+    /// # Arguments
+    ///
+    /// * `trash_holder` - Trash the items are moved to
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Vec<Operation>>` with the operations run again, which may contain:
+    /// - `AppError::State`: If there is nothing to redo
+    /// - `AppError::Path`: If something else now exists at the destination
+    /// - `AppError::Io`: If an item cannot be moved
+    pub fn redo(&mut self, trash_holder: &TrashHolder) -> AppResult<Vec<Operation>> {
+        let pending = self
+            .redo_stack
+            .pop()
+            .ok_or(AppError::State("Nothing to redo".into()))?;
+        let mut redone = Vec::new();
+        let mut pending = pending.into_iter();
+        while let Some(operation) = pending.next() {
+            match operation.redo(trash_holder) {
+                Ok(operation) => redone.push(operation),
+                Err(error) => {
+                    self.redo_stack
+                        .push(iter::once(operation).chain(pending).collect());
+                    if !redone.is_empty() {
+                        self.undo_stack.push(redone);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        self.undo_stack.push(redone.clone());
+        Ok(redone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_undo_redo_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash_holder = TrashHolder {
+            trash_dir: temp_dir.path().join("Trash"),
+        };
+        let from = temp_dir.path().join("a.txt");
+        let to = temp_dir.path().join("b.txt");
+        fs::write(&to, "moved").unwrap();
+
+        let mut journal_holder = JournalHolder::default();
+        journal_holder.record(Operation::Move {
+            from: from.clone(),
+            to: to.clone(),
+        });
+        journal_holder.undo(&trash_holder).unwrap();
+        assert!(from.exists() && !to.exists());
+        assert!(journal_holder.undo(&trash_holder).is_err());
+
+        // a blocked redo stays in the journal
+        fs::write(&to, "other").unwrap();
+        assert!(journal_holder.redo(&trash_holder).is_err());
+        fs::remove_file(&to).unwrap();
+        journal_holder.redo(&trash_holder).unwrap();
+        assert_eq!(fs::read_to_string(&to).unwrap(), "moved");
+        assert!(journal_holder.redo(&trash_holder).is_err());
+    }
+
+    #[test]
+    fn test_undo_redo_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash_holder = TrashHolder {
+            trash_dir: temp_dir.path().join("Trash"),
+        };
+        let path = temp_dir.path().join("notes.txt");
+        fs::write(&path, "notes").unwrap();
+
+        let mut journal_holder = JournalHolder::default();
+        journal_holder.record(Operation::Trash(trash_holder.trash(&path).unwrap()));
+        journal_holder.undo(&trash_holder).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "notes");
+        journal_holder.redo(&trash_holder).unwrap();
+        assert!(!path.exists());
+
+        // a new operation drops what could be redone
+        journal_holder.undo(&trash_holder).unwrap();
+        journal_holder.record(Operation::Trash(trash_holder.trash(&path).unwrap()));
+        assert!(journal_holder.redo(&trash_holder).is_err());
+    }
+
+    #[test]
+    fn test_undo_group_stops_at_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash_holder = TrashHolder {
+            trash_dir: temp_dir.path().join("Trash"),
+        };
+        let paths: Vec<_> = ["a", "b", "c"]
+            .iter()
+            .map(|name| temp_dir.path().join(name))
+            .collect();
+        for path in &paths {
+            fs::write(path, "").unwrap();
+        }
+        let mut journal_holder = JournalHolder::default();
+        journal_holder.record_group(
+            paths
+                .iter()
+                .map(|path| Operation::Trash(trash_holder.trash(path).unwrap()))
+                .collect(),
+        );
+
+        // "a" is in the way, "c" and "b" are restored
+        fs::write(&paths[0], "").unwrap();
+        assert!(journal_holder.undo(&trash_holder).is_err());
+        assert!(paths[1].exists() && paths[2].exists());
+        fs::remove_file(&paths[0]).unwrap();
+        assert_eq!(journal_holder.undo(&trash_holder).unwrap().len(), 1);
+        assert!(paths[0].exists());
+
+        // each part is redone on its own
+        assert_eq!(journal_holder.redo(&trash_holder).unwrap().len(), 1);
+        assert_eq!(journal_holder.redo(&trash_holder).unwrap().len(), 2);
+        assert!(paths.iter().all(|path| !path.exists()));
+    }
+}
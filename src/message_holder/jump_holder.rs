@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+
+/// Maximum number of locations kept in the jump list
+pub const MAX_JUMP_SIZE: usize = 100;
+
+/// A location visited in the viewer
+///
+/// # Fields
+///
+/// - `directory`: Displayed directory
+/// - `highlight_index`: Selection index in the unfiltered directory listing,
+///   `None` to use the selection remembered for the directory
+/// - `file_opened`: Opened file (if any)
+/// - `vertical_scroll`: Vertical scroll position of the opened file
+/// - `horizontal_scroll`: Horizontal scroll position of the opened file
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumpEntry {
+    pub directory: PathBuf,
+    pub highlight_index: Option<usize>,
+    pub file_opened: Option<PathBuf>,
+    pub vertical_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+/// Browser-style back/forward jump list
+///
+/// Locations before `position` can be gone back to, locations after it can
+/// be gone forward to. `position` equals the list length while viewing a
+/// location that is not in the list yet.
+///
+/// # Fields
+///
+/// - `entries`: Visited locations, oldest first
+/// - `position`: Index of the current location in `entries`
+#[derive(Debug, Default)]
+pub struct JumpHolder {
+    entries: Vec<JumpEntry>,
+    position: usize,
+}
+
+impl JumpHolder {
+    /// Records the location being left by a navigation
+    ///
+    /// Locations ahead of the current one are dropped, like following a link
+    /// after going back in a browser.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Location before the navigation
+    pub fn record(&mut self, current: JumpEntry) {
+        self.entries.truncate(self.position);
+        self.entries.push(current);
+        if self.entries.len() > MAX_JUMP_SIZE {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len();
+    }
+
+    /// Goes back one location
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Current location, kept so going forward returns to it
+    ///
+    /// # Returns
+    ///
+    /// Returns the location to show, or `None` at the oldest location
+    pub fn back(&mut self, current: JumpEntry) -> Option<JumpEntry> {
+        if self.position == 0 {
+            return None;
+        }
+        if self.position == self.entries.len() {
+            self.entries.push(current);
+        } else {
+            self.entries[self.position] = current;
+        }
+        self.position -= 1;
+        Some(self.entries[self.position].clone())
+    }
+
+    /// Goes forward one location
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Current location, kept so going back returns to it
+    ///
+    /// # Returns
+    ///
+    /// Returns the location to show, or `None` at the newest location
+    pub fn forward(&mut self, current: JumpEntry) -> Option<JumpEntry> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
+        }
+        self.entries[self.position] = current;
+        self.position += 1;
+        Some(self.entries[self.position].clone())
+    }
+
+    /// Drops the location returned by the last `back`, as it cannot be shown
+    ///
+    /// The location that was current before stays current.
+    pub fn discard_back(&mut self) {
+        if self.position < self.entries.len() {
+            self.entries.remove(self.position);
+        }
+    }
+
+    /// Drops the location returned by the last `forward`, as it cannot be
+    /// shown
+    ///
+    /// The location that was current before stays current.
+    pub fn discard_forward(&mut self) {
+        if self.position > 0 && self.position < self.entries.len() {
+            self.entries.remove(self.position);
+            self.position -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(directory: &str) -> JumpEntry {
+        JumpEntry {
+            directory: PathBuf::from(directory),
+            highlight_index: None,
+            file_opened: None,
+            vertical_scroll: 0,
+            horizontal_scroll: 0,
+        }
+    }
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut holder = JumpHolder::default();
+        assert!(holder.back(entry("/a")).is_none());
+        holder.record(entry("/a"));
+        holder.record(entry("/b"));
+
+        assert_eq!(holder.back(entry("/c")), Some(entry("/b")));
+        assert_eq!(holder.back(entry("/b")), Some(entry("/a")));
+        assert!(holder.back(entry("/a")).is_none());
+
+        let mut scrolled = entry("/a");
+        scrolled.highlight_index = Some(3);
+        assert_eq!(holder.forward(scrolled.clone()), Some(entry("/b")));
+        assert_eq!(holder.forward(entry("/b")), Some(entry("/c")));
+        assert!(holder.forward(entry("/c")).is_none());
+        assert_eq!(holder.back(entry("/c")), Some(entry("/b")));
+        assert_eq!(holder.back(entry("/b")), Some(scrolled));
+    }
+
+    #[test]
+    fn test_record_drops_forward_locations() {
+        let mut holder = JumpHolder::default();
+        holder.record(entry("/a"));
+        holder.record(entry("/b"));
+        assert_eq!(holder.back(entry("/c")), Some(entry("/b")));
+
+        holder.record(entry("/b"));
+        assert!(holder.forward(entry("/d")).is_none());
+        assert_eq!(holder.back(entry("/d")), Some(entry("/b")));
+        assert_eq!(holder.back(entry("/b")), Some(entry("/a")));
+    }
+
+    #[test]
+    fn test_discard_failed_location() {
+        let mut holder = JumpHolder::default();
+        holder.record(entry("/a"));
+        holder.record(entry("/b"));
+
+        assert_eq!(holder.back(entry("/c")), Some(entry("/b")));
+        holder.discard_back();
+        assert_eq!(holder.back(entry("/c")), Some(entry("/a")));
+        assert!(holder.back(entry("/a")).is_none());
+
+        assert_eq!(holder.forward(entry("/a")), Some(entry("/c")));
+        holder.discard_forward();
+        assert!(holder.forward(entry("/a")).is_none());
+        assert!(holder.back(entry("/a")).is_none());
+    }
+
+    #[test]
+    fn test_record_limit() {
+        let mut holder = JumpHolder::default();
+        for index in 0..MAX_JUMP_SIZE + 5 {
+            holder.record(entry(&format!("/{}", index)));
+        }
+        let mut count = 0;
+        while holder.back(entry("/current")).is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_JUMP_SIZE);
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::fs::{self, ReadDir};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::file_helper::FileHolder;
+use crate::message_holder::folder_holder::{EXPAND_MULTI_THREAD_THRESHOLD, EXPAND_THREAD_COUNT};
+
+/// Number of entries sent to the UI thread at once
+pub const LOAD_BATCH_SIZE: usize = 512;
+
+/// Message sent by the worker threads
+#[derive(Debug)]
+enum LoadMessage {
+    Entries(Vec<FileHolder>),
+    /// Every entry of the directory at an index of the directories read
+    Directory(usize, Vec<FileHolder>),
+    Skipped(PathBuf),
+}
+
+/// Progress of a background directory read
+#[derive(Debug)]
+pub enum LoadStatus {
+    /// Nothing new was read since the last check
+    Pending,
+    /// New entries were read, see [`LoadHolder::entries`]
+    Progress,
+    /// Every directory was read, or the entry limit was reached
+    Done(Vec<FileHolder>),
+}
+
+/// Reads directories on background threads
+///
+/// Entries are sent back in batches so large directories show up
+/// incrementally. Unreadable directories and entries are skipped. Dropping
+/// the holder cancels the read.
+///
+/// # Fields
+///
+/// - `receiver`: Batches sent by the worker threads
+/// - `cancel`: Flag asking the worker threads to stop
+/// - `max_entries`: Number of entries after which the read stops
+/// - `entries`: Entries received so far
+/// - `n_shown`: Number of entries already returned by [`LoadHolder::new_entries`]
+/// - `directories`: Directories read by [`LoadHolder::read_directories`], in order
+/// - `pending`: Directories read ahead of the next one in order
+/// - `n_done`: Number of directories whose entries were added, in order
+/// - `skipped`: Directories and entries that could not be read
+/// - `truncated`: Whether the read stopped at `max_entries`
+/// - `cut_off`: Directories left unread when the read stopped at `max_entries`
+#[derive(Debug)]
+pub struct LoadHolder {
+    receiver: Receiver<LoadMessage>,
+    cancel: Arc<AtomicBool>,
+    max_entries: usize,
+    pub entries: Vec<FileHolder>,
+    n_shown: usize,
+    directories: Vec<PathBuf>,
+    pending: HashMap<usize, Vec<FileHolder>>,
+    n_done: usize,
+    pub skipped: Vec<PathBuf>,
+    pub truncated: bool,
+    pub cut_off: Vec<PathBuf>,
+}
+
+impl LoadHolder {
+    /// Reads the entries of an opened directory
+    ///
+    /// The directory is opened by the caller so errors such as missing
+    /// permissions are reported right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_dir` - Opened directory
+    pub fn read_directory(read_dir: ReadDir) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+        thread::spawn(move || Self::send_entries(read_dir, &sender, &worker_cancel));
+        Self::with_receiver(receiver, cancel, usize::MAX, Vec::new())
+    }
+
+    /// Reads the entries of several directories
+    ///
+    /// Large sets of directories are split across `EXPAND_THREAD_COUNT` threads.
+    /// Entries are added in the order of the sorted directories, whole
+    /// directories at a time, so the read stops at `max_entries` on the same
+    /// directories whichever thread is faster. The directories left unread
+    /// are listed in `cut_off`.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Directories to read
+    /// * `max_entries` - Number of entries after which the read stops
+    pub fn read_directories(mut paths: Vec<PathBuf>, max_entries: usize) -> Self {
+        paths.sort();
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let num_threads = if paths.len() < EXPAND_MULTI_THREAD_THRESHOLD {
+            1
+        } else {
+            EXPAND_THREAD_COUNT
+        };
+        for thread_index in 0..num_threads.min(paths.len()) {
+            // directories are interleaved so those first in order are read first
+            let chunk: Vec<(usize, PathBuf)> = paths
+                .iter()
+                .cloned()
+                .enumerate()
+                .skip(thread_index)
+                .step_by(num_threads)
+                .collect();
+            let sender = sender.clone();
+            let worker_cancel = Arc::clone(&cancel);
+            thread::spawn(move || {
+                for (index, path) in chunk {
+                    let entries = match fs::read_dir(&path) {
+                        Ok(read_dir) => Self::collect_entries(read_dir, &sender, &worker_cancel),
+                        Err(_) => {
+                            if sender.send(LoadMessage::Skipped(path)).is_err() {
+                                return;
+                            }
+                            Vec::new()
+                        }
+                    };
+                    if worker_cancel.load(Ordering::Relaxed)
+                        || sender.send(LoadMessage::Directory(index, entries)).is_err()
+                    {
+                        return;
+                    }
+                }
+            });
+        }
+        Self::with_receiver(receiver, cancel, max_entries, paths)
+    }
+
+    fn with_receiver(
+        receiver: Receiver<LoadMessage>,
+        cancel: Arc<AtomicBool>,
+        max_entries: usize,
+        directories: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            receiver,
+            cancel,
+            max_entries,
+            entries: Vec::new(),
+            n_shown: 0,
+            directories,
+            pending: HashMap::new(),
+            n_done: 0,
+            skipped: Vec::new(),
+            truncated: false,
+            cut_off: Vec::new(),
+        }
+    }
+
+    /// Collects the entries read since the last check, without blocking
+    pub fn poll(&mut self) -> LoadStatus {
+        let mut status = LoadStatus::Pending;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => {
+                    if let Some(done) = self.receive(message) {
+                        return done;
+                    }
+                    status = LoadStatus::Progress;
+                }
+                Err(TryRecvError::Empty) => return status,
+                Err(TryRecvError::Disconnected) => {
+                    return LoadStatus::Done(std::mem::take(&mut self.entries))
+                }
+            }
+        }
+    }
+
+    /// Returns the entries received since the last call
+    pub fn new_entries(&mut self) -> Vec<FileHolder> {
+        let new_entries = self.entries[self.n_shown..].to_vec();
+        self.n_shown = self.entries.len();
+        new_entries
+    }
+
+    /// Checks if [`LoadHolder::new_entries`] returned any entry yet
+    pub fn has_shown_entries(&self) -> bool {
+        self.n_shown > 0
+    }
+
+    /// Waits for the read to finish, at most for a given time
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - Maximum time to wait
+    pub fn wait(&mut self, budget: Duration) -> LoadStatus {
+        let deadline = Instant::now() + budget;
+        let mut status = LoadStatus::Pending;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.receiver.recv_timeout(timeout) {
+                Ok(message) => {
+                    if let Some(done) = self.receive(message) {
+                        return done;
+                    }
+                    status = LoadStatus::Progress;
+                }
+                Err(RecvTimeoutError::Timeout) => return status,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return LoadStatus::Done(std::mem::take(&mut self.entries))
+                }
+            }
+        }
+    }
+
+    /// Asks the worker threads to stop
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Stores a message, returns the final status once the entry limit is reached
+    fn receive(&mut self, message: LoadMessage) -> Option<LoadStatus> {
+        match message {
+            LoadMessage::Entries(entries) => {
+                self.entries.extend(entries);
+                if self.entries.len() >= self.max_entries {
+                    self.entries.truncate(self.max_entries);
+                    return Some(self.stop());
+                }
+            }
+            LoadMessage::Directory(index, entries) => {
+                self.pending.insert(index, entries);
+                while let Some(entries) = self.pending.remove(&self.n_done) {
+                    if self.entries.len() + entries.len() > self.max_entries {
+                        self.cut_off = self.directories[self.n_done..].to_vec();
+                        return Some(self.stop());
+                    }
+                    self.entries.extend(entries);
+                    self.n_done += 1;
+                }
+            }
+            LoadMessage::Skipped(path) => self.skipped.push(path),
+        }
+        None
+    }
+
+    /// Stops the read at the entry limit, returning the entries kept
+    fn stop(&mut self) -> LoadStatus {
+        self.cancel();
+        self.truncated = true;
+        LoadStatus::Done(std::mem::take(&mut self.entries))
+    }
+
+    /// Reads every entry of a directory, unless cancelled
+    fn collect_entries(
+        read_dir: ReadDir,
+        sender: &Sender<LoadMessage>,
+        cancel: &AtomicBool,
+    ) -> Vec<FileHolder> {
+        let mut entries = Vec::new();
+        for entry in read_dir.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            match FileHolder::try_from(entry.path()) {
+                Ok(file_holder) => entries.push(file_holder),
+                Err(_) => {
+                    let _ = sender.send(LoadMessage::Skipped(entry.path()));
+                }
+            }
+        }
+        entries
+    }
+
+    fn send_entries(read_dir: ReadDir, sender: &Sender<LoadMessage>, cancel: &AtomicBool) {
+        let mut batch = Vec::with_capacity(LOAD_BATCH_SIZE);
+        for entry in read_dir.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            match FileHolder::try_from(entry.path()) {
+                Ok(file_holder) => batch.push(file_holder),
+                Err(_) => {
+                    if sender.send(LoadMessage::Skipped(entry.path())).is_err() {
+                        return;
+                    }
+                }
+            }
+            if batch.len() == LOAD_BATCH_SIZE {
+                let full_batch = std::mem::replace(&mut batch, Vec::with_capacity(LOAD_BATCH_SIZE));
+                if sender.send(LoadMessage::Entries(full_batch)).is_err() {
+                    return; // receiver dropped, stop processing
+                }
+            }
+        }
+        if !batch.is_empty() {
+            let _ = sender.send(LoadMessage::Entries(batch));
+        }
+    }
+
+    fn read_error(path: &Path) -> AppError {
+        AppError::Parse(format!("Unable to read {}", path.to_string_lossy()))
+    }
+}
+
+impl Drop for LoadHolder {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Opens a directory for [`LoadHolder::read_directory`]
+///
+/// # Returns
+///
+/// Returns `AppResult<ReadDir>` which may contain `AppError::Parse` if the
+/// directory cannot be read
+pub fn open_directory(path: &Path) -> AppResult<ReadDir> {
+    fs::read_dir(path).map_err(|_| LoadHolder::read_error(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn wait_done(load_holder: &mut LoadHolder) -> Vec<FileHolder> {
+        loop {
+            if let LoadStatus::Done(entries) = load_holder.wait(Duration::from_secs(5)) {
+                return entries;
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_directory_in_batches() {
+        let temp_dir = TempDir::new().unwrap();
+        for index in 0..LOAD_BATCH_SIZE + 10 {
+            fs::write(temp_dir.path().join(format!("{}.txt", index)), "").unwrap();
+        }
+        let mut load_holder = LoadHolder::read_directory(open_directory(temp_dir.path()).unwrap());
+        assert_eq!(wait_done(&mut load_holder).len(), LOAD_BATCH_SIZE + 10);
+    }
+
+    #[test]
+    fn test_new_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        for index in 0..LOAD_BATCH_SIZE + 10 {
+            fs::write(temp_dir.path().join(format!("{}.txt", index)), "").unwrap();
+        }
+        let mut load_holder = LoadHolder::read_directory(open_directory(temp_dir.path()).unwrap());
+
+        // each batch is returned once
+        let mut n_new = 0;
+        loop {
+            match load_holder.wait(Duration::from_millis(1)) {
+                LoadStatus::Pending => {}
+                LoadStatus::Progress => {
+                    n_new += load_holder.new_entries().len();
+                    assert_eq!(n_new, load_holder.entries.len());
+                    assert!(load_holder.new_entries().is_empty());
+                }
+                LoadStatus::Done(entries) => {
+                    assert_eq!(entries.len(), LOAD_BATCH_SIZE + 10);
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut paths = Vec::new();
+        for index in 0..EXPAND_MULTI_THREAD_THRESHOLD {
+            let path = temp_dir.path().join(index.to_string());
+            fs::create_dir(&path).unwrap();
+            fs::write(path.join("a"), "").unwrap();
+            fs::write(path.join("b"), "").unwrap();
+            paths.push(path);
+        }
+        let mut load_holder = LoadHolder::read_directories(paths.clone(), usize::MAX);
+        assert_eq!(
+            wait_done(&mut load_holder).len(),
+            2 * EXPAND_MULTI_THREAD_THRESHOLD
+        );
+        assert!(!load_holder.truncated);
+
+        // unreadable directories are skipped
+        let missing = temp_dir.path().join("missing");
+        paths.push(missing.clone());
+        let mut load_holder = LoadHolder::read_directories(paths.clone(), usize::MAX);
+        assert_eq!(
+            wait_done(&mut load_holder).len(),
+            2 * EXPAND_MULTI_THREAD_THRESHOLD
+        );
+        assert_eq!(load_holder.skipped, vec![missing]);
+
+        // reading stops at the entry limit, on whole directories in order
+        paths.sort();
+        let mut load_holder = LoadHolder::read_directories(paths.clone(), 3);
+        let entries = wait_done(&mut load_holder);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.parent == paths[0]));
+        assert!(load_holder.truncated);
+        assert_eq!(load_holder.cut_off, paths[1..].to_vec());
+    }
+}
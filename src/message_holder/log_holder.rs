@@ -0,0 +1,739 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime};
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+};
+use serde_json::{Map, Value};
+use std::env;
+use std::fmt;
+use std::path::Path;
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::file_helper::FileTextInfo;
+
+/// Environment variable selecting the JSON fields shown as columns, comma separated
+pub const LOG_FIELDS_ENV: &str = "ATHENA_VIEWER_LOG_FIELDS";
+
+/// File extensions opened in the log view by default
+const LOG_EXTENSIONS: &[&str] = &["log", "jsonl"];
+
+/// JSON keys holding the timestamp, by priority
+const TIME_KEYS: &[&str] = &["timestamp", "time", "ts", "@timestamp", "datetime"];
+
+/// JSON keys holding the level, by priority
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "loglevel"];
+
+/// JSON keys holding the message, by priority
+const MESSAGE_KEYS: &[&str] = &["message", "msg"];
+
+/// Maximum width of a JSON column, except the last one
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Number of leading words searched for a level in text lines
+const LEVEL_SEARCH_WORDS: usize = 8;
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Parses a level name, ignoring case
+    ///
+    /// Aliases such as `WARNING`, `FATAL` or `CRITICAL` map to the closest level.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "TRACE" => Some(Self::Trace),
+            "DEBUG" => Some(Self::Debug),
+            "INFO" | "NOTICE" => Some(Self::Info),
+            "WARN" | "WARNING" => Some(Self::Warn),
+            "ERROR" | "ERR" | "FATAL" | "CRITICAL" | "CRIT" | "ALERT" | "EMERG" | "PANIC" => {
+                Some(Self::Error)
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts a numeric level as used by bunyan and pino (10 to 60)
+    fn from_number(value: i64) -> Option<Self> {
+        match value {
+            10..=19 => Some(Self::Trace),
+            20..=29 => Some(Self::Debug),
+            30..=39 => Some(Self::Info),
+            40..=49 => Some(Self::Warn),
+            50.. => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// Returns the next minimum level to filter by
+    ///
+    /// Cycles through no filter, `DEBUG`, `INFO`, `WARN` and `ERROR`.
+    pub fn next_min_level(level: Option<Self>) -> Option<Self> {
+        match level {
+            None | Some(Self::Trace) => Some(Self::Debug),
+            Some(Self::Debug) => Some(Self::Info),
+            Some(Self::Info) => Some(Self::Warn),
+            Some(Self::Warn) => Some(Self::Error),
+            Some(Self::Error) => None,
+        }
+    }
+
+    /// Returns the style of lines with this level
+    pub fn style(&self) -> Style {
+        match self {
+            Self::Trace | Self::Debug => Color::DarkGray.into(),
+            Self::Info => Style::default(),
+            Self::Warn => Color::Yellow.into(),
+            Self::Error => Color::Red.into(),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Trace => "TRACE",
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Parses a timestamp in local time
+///
+/// Accepts RFC3339 and `YYYY-MM-DD[ HH:MM[:SS[.f]]]`, with `T` or a space
+/// between date and time and `.` or `,` before the fraction. A time with a
+/// UTC offset is converted to local time, one without is taken as local.
+pub fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim().replace(',', ".");
+    if let Ok(time) = DateTime::parse_from_rfc3339(&value) {
+        return Some(time.with_timezone(&Local).naive_local());
+    }
+    let value = value.replacen('T', " ", 1);
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Inclusive time range used to filter the log view
+///
+/// # Fields
+///
+/// - `start`: Earliest shown timestamp (if any)
+/// - `end`: Latest shown timestamp (if any)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TimeRange {
+    pub start: Option<NaiveDateTime>,
+    pub end: Option<NaiveDateTime>,
+}
+
+impl TimeRange {
+    /// Parses a range written as `FROM..TO`
+    ///
+    /// Either side may be left out. An empty input clears the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Range typed by the user, see [`parse_time`] for the time formats
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Parse` if a
+    /// time is invalid or the range is empty
+    pub fn parse(input: &str) -> AppResult<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+        let (start, end) = input.split_once("..").ok_or(AppError::Parse(
+            "Expected a time range like FROM..TO".into(),
+        ))?;
+        let parse_bound = |bound: &str| -> AppResult<Option<NaiveDateTime>> {
+            if bound.trim().is_empty() {
+                return Ok(None);
+            }
+            parse_time(bound)
+                .map(Some)
+                .ok_or(AppError::Parse(format!("Invalid time {:?}", bound.trim())))
+        };
+        let range = Self {
+            start: parse_bound(start)?,
+            end: parse_bound(end)?,
+        };
+        if let (Some(start), Some(end)) = (range.start, range.end) {
+            if start > end {
+                return Err(AppError::Parse("Time range ends before it starts".into()));
+            }
+        }
+        Ok(range)
+    }
+
+    /// Checks if the range is unbounded
+    pub fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    /// Checks if a line with a given timestamp is shown
+    ///
+    /// Lines without timestamp are only shown when the range is unbounded.
+    pub fn contains(&self, timestamp: Option<NaiveDateTime>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        timestamp.is_some_and(|timestamp| {
+            self.start.is_none_or(|start| start <= timestamp)
+                && self.end.is_none_or(|end| timestamp <= end)
+        })
+    }
+}
+
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |time: Option<NaiveDateTime>| {
+            time.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default()
+        };
+        write!(f, "{}..{}", format(self.start), format(self.end))
+    }
+}
+
+/// A parsed line of a log file
+///
+/// # Fields
+///
+/// - `text`: Raw line
+/// - `level`: Detected level (if any)
+/// - `timestamp`: Detected timestamp (if any)
+/// - `fields`: Parsed object for JSON-lines logs
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub text: String,
+    pub level: Option<LogLevel>,
+    pub timestamp: Option<NaiveDateTime>,
+    pub fields: Option<Map<String, Value>>,
+}
+
+impl LogLine {
+    /// Parses a line as a JSON object, or as text otherwise
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim_end_matches('\r');
+        if text.starts_with('{') {
+            if let Ok(fields) = serde_json::from_str::<Map<String, Value>>(text) {
+                return Self {
+                    text: text.to_string(),
+                    level: Self::find_field(&fields, LEVEL_KEYS).and_then(|value| match value {
+                        Value::String(level) => LogLevel::parse(level),
+                        Value::Number(level) => level.as_i64().and_then(LogLevel::from_number),
+                        _ => None,
+                    }),
+                    timestamp: Self::find_field(&fields, TIME_KEYS).and_then(|value| match value {
+                        Value::String(time) => parse_time(time),
+                        Value::Number(time) => time.as_i64().and_then(Self::from_epoch),
+                        _ => None,
+                    }),
+                    fields: Some(fields),
+                };
+            }
+        }
+        Self {
+            text: text.to_string(),
+            level: Self::detect_level(text),
+            timestamp: Self::detect_timestamp(text, Local::now().year()),
+            fields: None,
+        }
+    }
+
+    fn find_field<'a>(fields: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+        keys.iter().find_map(|key| fields.get(*key))
+    }
+
+    /// Converts a unix timestamp in seconds or milliseconds to local time
+    fn from_epoch(value: i64) -> Option<NaiveDateTime> {
+        let seconds = if value.abs() >= 1_000_000_000_000 {
+            value / 1000
+        } else {
+            value
+        };
+        DateTime::from_timestamp(seconds, 0).map(|time| time.with_timezone(&Local).naive_local())
+    }
+
+    /// Finds an upper case level name or a `level=` pair among the leading words
+    fn detect_level(text: &str) -> Option<LogLevel> {
+        text.split_whitespace()
+            .take(LEVEL_SEARCH_WORDS)
+            .find_map(|word| {
+                let word = word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '=');
+                match word.split_once('=') {
+                    Some((key, value)) if LEVEL_KEYS.contains(&key.to_lowercase().as_str()) => {
+                        LogLevel::parse(value.trim_matches('"'))
+                    }
+                    Some(_) => None,
+                    None if word.chars().all(|c| c.is_ascii_uppercase()) => LogLevel::parse(word),
+                    None => None,
+                }
+            })
+    }
+
+    /// Parses a leading RFC3339, `YYYY-MM-DD HH:MM:SS` or syslog timestamp
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Raw line
+    /// * `year` - Year of the syslog timestamps, which have none
+    fn detect_timestamp(text: &str, year: i32) -> Option<NaiveDateTime> {
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .take(3)
+            .map(|word| word.trim_matches(['[', ']']))
+            .collect();
+        let first = words.first()?;
+        words
+            .get(1)
+            .and_then(|second| parse_time(&format!("{} {}", first, second)))
+            .or_else(|| parse_time(first))
+            .or_else(|| {
+                let syslog_time = format!("{} {}", year, words.get(..3)?.join(" "));
+                NaiveDateTime::parse_from_str(&syslog_time, "%Y %b %d %H:%M:%S").ok()
+            })
+    }
+}
+
+/// Log-aware view of a file
+///
+/// Lines are colored by level and filtered by minimum level and time range.
+/// JSON-lines logs are shown as columns of selected fields. Lines without
+/// level or timestamp, such as stack traces, belong to the line above.
+///
+/// # Fields
+///
+/// - `lines`: Parsed lines of the file, the last one not terminated yet
+/// - `fields`: JSON fields shown as columns, empty to pick time, level and message
+/// - `known_keys`: Time, level and message keys found in the JSON lines
+/// - `widths`: Column widths of the last built view
+/// - `min_level`: Minimum level shown (if any)
+/// - `time_range`: Time range shown
+#[derive(Debug, Default)]
+pub struct LogHolder {
+    lines: Vec<LogLine>,
+    fields: Vec<String>,
+    known_keys: Vec<&'static str>,
+    widths: Vec<usize>,
+    pub min_level: Option<LogLevel>,
+    pub time_range: TimeRange,
+}
+
+impl LogHolder {
+    /// Creates an empty log view
+    ///
+    /// The JSON columns can be chosen with the `ATHENA_VIEWER_LOG_FIELDS`
+    /// environment variable.
+    pub fn new() -> Self {
+        let fields = env::var(LOG_FIELDS_ENV)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|field| !field.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            fields,
+            ..Default::default()
+        }
+    }
+
+    /// Checks if a file is opened in the log view by default
+    pub fn is_log_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| LOG_EXTENSIONS.contains(&extension))
+    }
+
+    /// Replaces the content of the view
+    pub fn load(&mut self, content: &str) {
+        self.lines.clear();
+        self.known_keys.clear();
+        self.append(content);
+    }
+
+    /// Appends text written to the end of the file
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Appended text, starting with the unterminated last line (if any)
+    pub fn append(&mut self, text: &str) {
+        self.lines.pop();
+        for line in text.split('\n') {
+            let mut log_line = LogLine::parse(line);
+            if log_line.fields.is_none() && log_line.level.is_none() && log_line.timestamp.is_none()
+            {
+                if let Some(previous) = self.lines.last() {
+                    log_line.level = previous.level;
+                    log_line.timestamp = previous.timestamp;
+                }
+            }
+            if let Some(fields) = &log_line.fields {
+                for key in TIME_KEYS.iter().chain(LEVEL_KEYS).chain(MESSAGE_KEYS) {
+                    if fields.contains_key(*key) && !self.known_keys.contains(key) {
+                        self.known_keys.push(key);
+                    }
+                }
+            }
+            self.lines.push(log_line);
+        }
+    }
+
+    /// Appends text written to the end of the file, and its shown lines to a
+    /// view built by [`LogHolder::to_text_info`]
+    ///
+    /// Only the appended lines are formatted, unless they change the columns
+    /// or need a wider one, then the whole view is built again.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Appended text, starting with the unterminated last line (if any)
+    /// * `file_text_info` - View to append to
+    pub fn append_to(&mut self, text: &str, file_text_info: &mut FileTextInfo) {
+        let columns = self.columns();
+        if self.lines.last().is_some_and(|line| self.is_shown(line)) {
+            file_text_info.formatted_text.pop();
+        }
+        let first_new = self.lines.len().saturating_sub(1);
+        self.append(text);
+
+        let new_lines: Vec<&LogLine> = self.lines[first_new..]
+            .iter()
+            .filter(|line| self.is_shown(line))
+            .collect();
+        let is_wider = new_lines
+            .iter()
+            .filter_map(|line| line.fields.as_ref())
+            .any(|fields| {
+                Self::values(&columns, fields)
+                    .iter()
+                    .zip(&self.widths)
+                    .any(|(value, width)| value.chars().count().min(MAX_COLUMN_WIDTH) > *width)
+            });
+        if is_wider || self.columns() != columns {
+            *file_text_info = self.to_text_info();
+            return;
+        }
+
+        let formatted_text: Vec<Line<'static>> = new_lines
+            .iter()
+            .map(|line| Self::format_line(line, &columns, &self.widths))
+            .collect();
+        file_text_info.max_line_length = formatted_text
+            .iter()
+            .map(Self::line_length)
+            .fold(file_text_info.max_line_length, usize::max);
+        file_text_info.formatted_text.extend(formatted_text);
+        file_text_info.n_rows = file_text_info.formatted_text.len();
+    }
+
+    /// Describes the active filters, for the view title
+    pub fn describe(&self) -> String {
+        let mut description = "log".to_string();
+        if let Some(min_level) = self.min_level {
+            description.push_str(&format!(", {}+", min_level));
+        }
+        if !self.time_range.is_empty() {
+            description.push_str(&format!(", {}", self.time_range));
+        }
+        description
+    }
+
+    /// Builds the displayed lines from the shown log lines
+    pub fn to_text_info(&mut self) -> FileTextInfo {
+        let shown: Vec<&LogLine> = self
+            .lines
+            .iter()
+            .filter(|line| self.is_shown(line))
+            .collect();
+
+        let columns = self.columns();
+        let mut widths = vec![0; columns.len()];
+        for fields in shown.iter().filter_map(|line| line.fields.as_ref()) {
+            for (width, value) in widths.iter_mut().zip(Self::values(&columns, fields)) {
+                *width = (*width).max(value.chars().count()).min(MAX_COLUMN_WIDTH);
+            }
+        }
+
+        let formatted_text: Vec<Line<'static>> = shown
+            .iter()
+            .map(|line| Self::format_line(line, &columns, &widths))
+            .collect();
+        self.widths = widths;
+
+        FileTextInfo {
+            n_rows: formatted_text.len(),
+            max_line_length: formatted_text
+                .iter()
+                .map(Self::line_length)
+                .max()
+                .unwrap_or(0),
+            formatted_text,
+        }
+    }
+
+    /// Checks if a line passes the level and time filters
+    fn is_shown(&self, line: &LogLine) -> bool {
+        self.min_level
+            .is_none_or(|min_level| line.level.is_some_and(|level| level >= min_level))
+            && self.time_range.contains(line.timestamp)
+    }
+
+    /// Returns the JSON fields shown as columns
+    ///
+    /// Defaults to the first time, level and message keys found in the file.
+    fn columns(&self) -> Vec<String> {
+        if !self.fields.is_empty() {
+            return self.fields.clone();
+        }
+        [TIME_KEYS, LEVEL_KEYS, MESSAGE_KEYS]
+            .iter()
+            .filter_map(|keys| keys.iter().find(|key| self.known_keys.contains(key)))
+            .map(|key| key.to_string())
+            .collect()
+    }
+
+    /// Returns the values of the columns in a JSON line
+    fn values(columns: &[String], fields: &Map<String, Value>) -> Vec<String> {
+        columns
+            .iter()
+            .map(|column| match fields.get(column) {
+                Some(Value::String(value)) => value.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            })
+            .collect()
+    }
+
+    /// Formats a shown line, colored by its level
+    fn format_line(line: &LogLine, columns: &[String], widths: &[usize]) -> Line<'static> {
+        let text = match &line.fields {
+            Some(fields) => Self::format_columns(Self::values(columns, fields), widths),
+            None => line.text.clone(),
+        };
+        Line::styled(
+            text,
+            line.level.map(|level| level.style()).unwrap_or_default(),
+        )
+    }
+
+    fn line_length(line: &Line) -> usize {
+        line.spans
+            .iter()
+            .map(|span| span.content.chars().count())
+            .sum()
+    }
+
+    fn format_columns(values: Vec<String>, widths: &[usize]) -> String {
+        let last = values.len().saturating_sub(1);
+        values
+            .into_iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(index, (value, width))| {
+                if index == last {
+                    value
+                } else {
+                    let value: String = value.chars().take(*width).collect();
+                    format!("{:<width$}", value, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").unwrap()
+    }
+
+    /// converts an RFC3339 time to the local time shown
+    fn local_time(value: &str) -> NaiveDateTime {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Local)
+            .naive_local()
+    }
+
+    fn shown(log_holder: &mut LogHolder) -> Vec<String> {
+        log_holder
+            .to_text_info()
+            .formatted_text
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_text_lines() {
+        let line = LogLine::parse("2024-05-01T10:00:00.250+02:00 [WARN] disk almost full");
+        assert_eq!(line.level, Some(LogLevel::Warn));
+        assert_eq!(
+            line.timestamp,
+            Some(local_time("2024-05-01T10:00:00.250+02:00"))
+        );
+
+        let line = LogLine::parse("2024-05-01 10:00:00,500 ERROR main: crashed");
+        assert_eq!(line.level, Some(LogLevel::Error));
+        assert_eq!(line.timestamp, Some(time("2024-05-01 10:00:00.500")));
+
+        let line = LogLine::parse("May  1 10:00:00 host sshd[42]: info about login");
+        assert_eq!(line.level, None);
+        assert_eq!(
+            LogLine::detect_timestamp(&line.text, 2024),
+            Some(time("2024-05-01 10:00:00"))
+        );
+        assert_eq!(
+            LogLine::detect_timestamp("Feb 29 10:00:00 host cron: leap day", 2024),
+            Some(time("2024-02-29 10:00:00"))
+        );
+
+        let line = LogLine::parse("ts=2024-05-01T10:00:00Z level=debug msg=hello");
+        assert_eq!(line.level, Some(LogLevel::Debug));
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let line =
+            LogLine::parse(r#"{"time":"2024-05-01T10:00:00Z","level":"warning","msg":"slow"}"#);
+        assert_eq!(line.level, Some(LogLevel::Warn));
+        assert_eq!(line.timestamp, Some(local_time("2024-05-01T10:00:00Z")));
+
+        // epoch times are in the same time zone as written ones
+        let line = LogLine::parse(r#"{"time":1714557600000,"level":50,"msg":"failed"}"#);
+        assert_eq!(line.level, Some(LogLevel::Error));
+        assert_eq!(line.timestamp, Some(local_time("2024-05-01T10:00:00Z")));
+        let line = LogLine::parse(r#"{"time":1714557600,"msg":"failed"}"#);
+        assert_eq!(
+            line.timestamp,
+            Some(local_time("2024-05-01T12:00:00+02:00"))
+        );
+    }
+
+    #[test]
+    fn test_time_range() {
+        let range = TimeRange::parse("2024-05-01 10:00..2024-05-01").unwrap_err();
+        assert!(matches!(range, AppError::Parse(_)));
+        assert!(TimeRange::parse("yesterday..").is_err());
+        assert!(TimeRange::parse("2024-05-01").is_err());
+        assert!(TimeRange::parse("").unwrap().is_empty());
+
+        let range = TimeRange::parse("2024-05-01 10:00..").unwrap();
+        assert!(range.contains(Some(time("2024-05-01 10:00:00"))));
+        assert!(!range.contains(Some(time("2024-05-01 09:59:59"))));
+        assert!(!range.contains(None));
+    }
+
+    #[test]
+    fn test_filters() {
+        let mut log_holder = LogHolder::default();
+        log_holder.load(
+            "2024-05-01 10:00:00 INFO started\n\
+             2024-05-01 10:05:00 ERROR failed\n\
+             \tat main.rs:10\n\
+             \n\
+             2024-05-01 10:10:00 WARN retrying",
+        );
+        assert_eq!(shown(&mut log_holder).len(), 5);
+
+        // blank lines are kept with the line above
+        log_holder.min_level = Some(LogLevel::Warn);
+        assert_eq!(
+            shown(&mut log_holder),
+            vec![
+                "2024-05-01 10:05:00 ERROR failed",
+                "\tat main.rs:10",
+                "",
+                "2024-05-01 10:10:00 WARN retrying"
+            ]
+        );
+
+        log_holder.time_range = TimeRange::parse("..2024-05-01 10:05").unwrap();
+        assert_eq!(shown(&mut log_holder).len(), 3);
+        assert_eq!(log_holder.describe(), "log, WARN+, ..2024-05-01 10:05:00");
+    }
+
+    #[test]
+    fn test_append_and_columns() {
+        let mut log_holder = LogHolder::default();
+        log_holder.load(
+            "{\"ts\":\"2024-05-01T10:00:00Z\",\"level\":\"info\",\"msg\":\"a\",\"id\":1}\n{\"ts\":",
+        );
+        log_holder.append("{\"ts\":\"2024-05-01T10:00:01Z\",\"level\":\"error\",\"msg\":\"b\"}\n");
+        assert_eq!(
+            shown(&mut log_holder),
+            vec![
+                "2024-05-01T10:00:00Z  info   a",
+                "2024-05-01T10:00:01Z  error  b",
+                ""
+            ]
+        );
+
+        log_holder.fields = vec!["id".into(), "msg".into()];
+        assert_eq!(shown(&mut log_holder), vec!["1  a", "   b", ""]);
+    }
+
+    #[test]
+    fn test_append_to_view() {
+        let mut log_holder = LogHolder {
+            min_level: Some(LogLevel::Info),
+            ..Default::default()
+        };
+        log_holder.load("{\"ts\":\"10:00\",\"level\":\"info\",\"msg\":\"a\"}\n{\"ts\":");
+        let mut file_text_info = log_holder.to_text_info();
+
+        // the partial line is completed, a filtered line is skipped
+        log_holder.append_to(
+            "{\"ts\":\"10:01\",\"level\":\"warn\",\"msg\":\"b\"}\n\
+             {\"ts\":\"10:02\",\"level\":\"debug\",\"msg\":\"c\"}\n",
+            &mut file_text_info,
+        );
+        let lines: Vec<String> = file_text_info
+            .formatted_text
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(lines, vec!["10:00  info  a", "10:01  warn  b"]);
+        assert_eq!(file_text_info.n_rows, 2);
+        assert_eq!(file_text_info.max_line_length, 14);
+
+        // a wider value lays out the whole view again
+        log_holder.append_to(
+            "{\"ts\":\"10:03\",\"level\":\"error\",\"msg\":\"d\"}\n",
+            &mut file_text_info,
+        );
+        let lines: Vec<String> = file_text_info
+            .formatted_text
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(lines, shown(&mut log_holder));
+        assert_eq!(lines[0], "10:00  info   a");
+        // the empty last line belongs to the error above
+        assert_eq!(file_text_info.n_rows, 4);
+    }
+}
@@ -13,32 +13,103 @@
 //! - [`FileHolder`]: Individual file/folder metadata
 //! - [`FileTextInfo`]: File content with formatting
 //! - [`CodeHighlighter`]: Syntax highlighting using syntect
+//! - [`SortMode`]: Ordering of directory listings
+//! - [`GitStatusHolder`](git_helper::GitStatusHolder): Git status decorations for directory listings
+//! - [`FileHistoryHolder`]: Git history browser for the opened file
+//! - [`HistoryStore`]: Directory history persisted across sessions
+//! - [`BookmarkHolder`]: Named marks on directories and files
+//! - [`JumpHolder`]: Back/forward navigation history
+//! - [`WatchHolder`]: Filesystem watching of the displayed paths
+//! - [`LoadHolder`]: Background directory reads
+//! - [`FollowHolder`]: Following a growing file, like `tail -f`
+//! - [`LogHolder`]: Log-aware view with level coloring and filters
+//! - [`PreviewHolder`]: Cached previews of the selected file
+//! - [`TrashHolder`]: Freedesktop trash for deleted files
+//! - [`JournalHolder`]: Undo and redo of file operations
+//! - [`TransferHolder`]: Background copies and moves of pasted items
+//! - [`Confirmation`]: Operations waiting for an answer in a popup
+//! - [`RenamePlan`]: Renames edited in the bulk rename buffer
+//! - [`EditorRequest`]: Files edited in the external editor
+//! - [`CommandHolder`]: Shell commands run on the selected entries
 
+pub mod bookmark_holder;
+pub mod clipboard_helper;
 pub mod code_highlighter;
+pub mod command_holder;
+pub mod delete_holder;
+pub mod dialog_holder;
+pub mod editor_holder;
 pub mod file_helper;
+pub mod file_history_holder;
+pub mod file_sorter;
+pub mod filter_helper;
 pub mod folder_holder;
+pub mod follow_holder;
+pub mod git_helper;
+pub mod history_store;
+pub mod journal_holder;
+pub mod jump_holder;
+pub mod load_holder;
+pub mod log_holder;
+pub mod preview_holder;
+pub mod rename_holder;
+pub mod transfer_holder;
+pub mod trash_holder;
+pub mod watch_holder;
 
+use lru::LruCache;
 use ratatui::style::Stylize;
 use ratatui::symbols::scrollbar;
 use ratatui::{
-    layout::{Margin, Rect},
+    layout::{Constraint, Flex, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    text::Line,
-    widgets::{Block, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    text::{Line, Span},
+    widgets::{
+        Block, Clear, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
     Frame,
 };
-use std::fs;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::iter;
+use std::num::NonZeroUsize;
 
 use std::cell::RefCell;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::bookmark_holder::{Bookmark, BookmarkHolder};
 use crate::message_holder::code_highlighter::CodeHighlighter;
+use crate::message_holder::command_holder::{describe_status, CommandHolder, ShellCommand};
+use crate::message_holder::delete_holder::{DeleteHolder, SizeHolder};
+use crate::message_holder::dialog_holder::{
+    check_name, unique_path, Confirmation, ConflictChoice, PendingDelete, PendingPaste, Prompt,
+    MAX_LISTED_ITEMS,
+};
+use crate::message_holder::editor_holder::{create_buffer, EditorRequest, EditorTask};
 use crate::message_holder::file_helper::{FileHolder, FileTextInfo};
-use crate::message_holder::folder_holder::FolderHolder;
+use crate::message_holder::file_history_holder::FileHistoryHolder;
+use crate::message_holder::folder_holder::{FolderHolder, LoadUpdate, DEFAULT_CACHE_SIZE};
+use crate::message_holder::follow_holder::{FollowHolder, FollowUpdate};
+use crate::message_holder::git_helper::{FileGitInfo, FileGitLoader, GitStatus};
+use crate::message_holder::history_store::HistoryStore;
+use crate::message_holder::journal_holder::{describe_group, JournalHolder, Operation};
+use crate::message_holder::jump_holder::{JumpEntry, JumpHolder};
+use crate::message_holder::log_holder::{LogHolder, LogLevel, TimeRange};
+use crate::message_holder::preview_holder::PreviewHolder;
+use crate::message_holder::rename_holder::{buffer_text, RenamePlan};
+use crate::message_holder::transfer_holder::{
+    Clipboard, TransferHolder, TransferJob, TransferMode,
+};
+use crate::message_holder::trash_holder::{rename_no_replace, TrashEntry, TrashHolder};
 use crate::state_holder::StateHolder;
 
+/// Maximum width of the author column in blame view
+const MAX_BLAME_AUTHOR_WIDTH: usize = 16;
+
 /// Main controller for file viewing and directory navigation
 ///
 /// Coordinates between the UI, file system operations, and state management.
@@ -52,6 +123,30 @@ use crate::state_holder::StateHolder;
 /// - `raw_highlight_index`: Current selection index (before wrapping)
 /// - `file_opened`: Currently open file path (if any)
 /// - `file_text_info`: Loaded file content and metadata (if file open)
+/// - `file_git_info`: Line changes and blame of the opened file (if tracked by git)
+/// - `file_git_loading`: Line changes and blame being read in the background (if any)
+/// - `show_blame`: Whether the blame column is displayed in file view
+/// - `follow_holder`: Follow state of the opened file (if following)
+/// - `log_holder`: Log view of the opened file (if shown as a log)
+/// - `file_history_holder`: Git history browser of the opened file (if shown)
+/// - `bookmark_holder`: Named marks and the bookmark picker
+/// - `jump_holder`: Back/forward navigation history
+/// - `file_scroll_holder`: Last scroll position (vertical, horizontal) per file
+/// - `show_columns`: Whether the folder view shows parent, listing and preview panes
+/// - `show_quick_look`: Whether the preview pane is shown while typing a search filter
+/// - `preview_holder`: Cached previews of the selected file
+/// - `trash_holder`: Trash deleted files are moved to
+/// - `size_holder`: Measure of the items waiting for delete (if any)
+/// - `delete_holder`: Delete in progress (if any)
+/// - `pending_confirm`: Operation waiting for confirmation (if any)
+/// - `pending_prompt`: What the name typed in the input is for (if any)
+/// - `command_holder`: Shell command shown in the command view (if any)
+/// - `pending_command`: Command to run with the terminal once the event is handled (if any)
+/// - `clipboard`: Items copied or cut, kept while navigating (if any)
+/// - `pending_clipboard`: Text to copy to the terminal clipboard once the event is handled (if any)
+/// - `transfer_holder`: Paste in progress (if any)
+/// - `journal_holder`: File operations of the session that can be undone
+/// - `selection_after_load`: Selection to restore once the directory being read is listed
 /// - `vertical_scroll_state`: Scrollbar state for vertical scrolling
 /// - `horizontal_scroll_state`: Scrollbar state for horizontal scrolling
 /// - `vertical_scroll`: Current vertical scroll position
@@ -64,6 +159,30 @@ pub struct MessageHolder {
     pub raw_highlight_index: i32,
     pub file_opened: Option<PathBuf>,
     pub file_text_info: Option<FileTextInfo>,
+    pub file_git_info: Option<FileGitInfo>,
+    file_git_loading: Option<FileGitLoader>,
+    pub show_blame: bool,
+    pub follow_holder: Option<FollowHolder>,
+    pub log_holder: Option<LogHolder>,
+    pub file_history_holder: Option<FileHistoryHolder>,
+    pub bookmark_holder: BookmarkHolder,
+    pub jump_holder: JumpHolder,
+    file_scroll_holder: LruCache<PathBuf, (usize, usize)>,
+    pub show_columns: bool,
+    pub show_quick_look: bool,
+    pub preview_holder: PreviewHolder,
+    pub trash_holder: TrashHolder,
+    size_holder: Option<SizeHolder>,
+    pub delete_holder: Option<DeleteHolder>,
+    pub pending_confirm: Option<Confirmation>,
+    pub pending_prompt: Option<Prompt>,
+    pub command_holder: Option<CommandHolder>,
+    pub pending_command: Option<ShellCommand>,
+    pub clipboard: Option<Clipboard>,
+    pub pending_clipboard: Option<String>,
+    pub transfer_holder: Option<TransferHolder>,
+    pub journal_holder: JournalHolder,
+    selection_after_load: Option<(PathBuf, Option<usize>)>,
     pub vertical_scroll_state: ScrollbarState,
     pub horizontal_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
@@ -96,6 +215,33 @@ impl MessageHolder {
             raw_highlight_index: 0,
             file_opened: Default::default(),
             file_text_info: Default::default(),
+            file_git_info: Default::default(),
+            file_git_loading: None,
+            show_blame: false,
+            follow_holder: None,
+            log_holder: None,
+            file_history_holder: None,
+            bookmark_holder: BookmarkHolder::default(),
+            jump_holder: JumpHolder::default(),
+            file_scroll_holder: LruCache::new(
+                NonZeroUsize::new(DEFAULT_CACHE_SIZE)
+                    .ok_or(AppError::Cache("Unable to setup the scroll cache!".into()))?,
+            ),
+            show_columns: false,
+            show_quick_look: false,
+            preview_holder: PreviewHolder::new()?,
+            trash_holder: TrashHolder::default(),
+            size_holder: None,
+            delete_holder: None,
+            pending_confirm: None,
+            pending_prompt: None,
+            command_holder: None,
+            pending_command: None,
+            clipboard: None,
+            pending_clipboard: None,
+            transfer_holder: None,
+            journal_holder: JournalHolder::default(),
+            selection_after_load: None,
             vertical_scroll_state: Default::default(),
             horizontal_scroll_state: Default::default(),
             vertical_scroll: Default::default(),
@@ -115,122 +261,2080 @@ impl MessageHolder {
         self.raw_highlight_index = self.raw_highlight_index.saturating_sub(1);
     }
 
-    /// Moves the selection down by one item
+    /// Moves the selection down by one item
+    ///
+    /// Uses saturating addition to prevent overflow
+    pub fn move_down(&mut self) {
+        self.raw_highlight_index = self.raw_highlight_index.saturating_add(1);
+    }
+
+    /// Updates the filtered view based on search input
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Optional search filter string
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if update fails
+    pub fn update(&mut self, input: Option<String>) -> AppResult<()> {
+        self.folder_holder.update(input)?;
+        self.reset_index();
+        Ok(())
+    }
+
+    /// Expands all directories recursively
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::State`: If a directory is still being read
+    pub fn expand(&mut self) -> AppResult<()> {
+        self.folder_holder.expand()?;
+        Ok(())
+    }
+
+    /// Expands or collapses the listing to a given depth
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Expansion level to reach, 0 being the plain listing
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If path resolution fails
+    /// - `AppError::State`: If a directory is still being read
+    pub fn expand_to_depth(&mut self, depth: usize) -> AppResult<()> {
+        self.folder_holder.expand_to_depth(depth)
+    }
+
+    /// Collapses expanded directories
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` on invalid state
+    pub fn collapse(&mut self) -> AppResult<()> {
+        self.folder_holder.collapse()?;
+        Ok(())
+    }
+
+    /// Expands the selected directory as a tree node
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::State`: If a directory is still being read
+    pub fn expand_selected(&mut self) -> AppResult<()> {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() {
+            return Ok(());
+        }
+        let highlight_index = self.get_highlight_index(path_holder.len())?;
+        self.folder_holder.expand_node(highlight_index)
+    }
+
+    /// Collapses the tree node of the selected entry and selects the node
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Path` if path
+    /// resolution fails
+    pub fn collapse_selected(&mut self) -> AppResult<()> {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() {
+            return Ok(());
+        }
+        let highlight_index = self.get_highlight_index(path_holder.len())?;
+        if let Some(node_index) = self.folder_holder.collapse_node(highlight_index)? {
+            self.raw_highlight_index = node_index.try_into().unwrap_or(0);
+        }
+        Ok(())
+    }
+
+    /// Toggles the parent, listing and preview panes of the folder view
+    pub fn toggle_columns(&mut self) {
+        self.show_columns = !self.show_columns;
+    }
+
+    /// Expands or collapses the selected directory if it is inside the tree
+    ///
+    /// Directories of the current directory are left to
+    /// [`MessageHolder::submit`], which enters them: `Enter` is the only key
+    /// going down into a directory, while `l`/`h` expand and collapse any
+    /// directory in place.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>`, `true` if a node was toggled, which may
+    /// contain the errors of [`MessageHolder::expand_selected`] and
+    /// [`MessageHolder::collapse_selected`]
+    pub fn toggle_selected_node(&mut self) -> AppResult<bool> {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() {
+            return Ok(false);
+        }
+        let highlight_index = self.get_highlight_index(path_holder.len())?;
+        match self.folder_holder.tree_node_state(highlight_index) {
+            Some(true) => self.collapse_selected()?,
+            Some(false) => self.expand_selected()?,
+            None => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Toggles the preview pane shown while typing a search filter
+    pub fn toggle_quick_look(&mut self) {
+        self.show_quick_look = !self.show_quick_look;
+    }
+
+    /// Cycles the sort key of the current directory
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` if the
+    /// current directory is not cached
+    pub fn cycle_sort_key(&mut self) -> AppResult<()> {
+        self.folder_holder
+            .change_sort_mode(|mode| mode.key = mode.key.next())
+    }
+
+    /// Toggles reverse order for the current directory
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` if the
+    /// current directory is not cached
+    pub fn toggle_sort_reverse(&mut self) -> AppResult<()> {
+        self.folder_holder
+            .change_sort_mode(|mode| mode.reverse = !mode.reverse)
+    }
+
+    /// Toggles listing directories before files for the current directory
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` if the
+    /// current directory is not cached
+    pub fn toggle_directories_first(&mut self) -> AppResult<()> {
+        self.folder_holder
+            .change_sort_mode(|mode| mode.directories_first = !mode.directories_first)
+    }
+
+    /// Navigates to the parent directory
+    ///
+    /// Selects the directory we came from, unless another entry of the
+    /// parent was selected when it was last left
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain directory navigation errors
+    pub fn to_parent(&mut self) -> AppResult<()> {
+        let parent = self
+            .folder_holder
+            .current_directory
+            .parent()
+            .ok_or(AppError::Path("Already at the root directory".into()))?
+            .to_path_buf();
+        self.remember_selection();
+        self.navigate(|holder| holder.enter_directory(parent))
+    }
+
+    /// Returns the path of the selected entry of the current listing
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<PathBuf>>`, `None` if nothing is listed,
+    /// which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is selected
+    /// - `AppError::State`: If selection is invalid
+    fn selected_entry_path(&self) -> AppResult<Option<PathBuf>> {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() || self.state_holder.borrow().is_history_search() {
+            return Ok(None);
+        }
+        let highlight_index = self.get_highlight_index(path_holder.len())?;
+        let entry = &path_holder[highlight_index];
+        if entry.file_name == ".." {
+            return Err(AppError::Path(
+                "Cannot change the parent directory shortcut".into(),
+            ));
+        }
+        Ok(Some(entry.to_path()))
+    }
+
+    /// Returns the paths the batch operations apply to
+    ///
+    /// The multi-selection is used when entries are selected, otherwise
+    /// the highlighted entry. Selected entries inside a selected directory
+    /// are left out, the directory holds them already.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Vec<PathBuf>>`, empty if nothing is listed, which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is highlighted
+    /// - `AppError::State`: If selection is invalid
+    pub fn selected_paths(&mut self) -> AppResult<Vec<PathBuf>> {
+        self.folder_holder.prune_selection();
+        if self.folder_holder.multi_selection.is_empty()
+            || self.state_holder.borrow().is_history_search()
+        {
+            return Ok(self.selected_entry_path()?.into_iter().collect());
+        }
+        // the selection is sorted, entries follow the directory holding them
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for path in &self.folder_holder.multi_selection {
+            if paths.last().is_none_or(|last| !path.starts_with(last)) {
+                paths.push(path.clone());
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Adds the highlighted entry to the multi-selection, or removes it,
+    /// then highlights the next entry
+    pub fn toggle_selected(&mut self) -> AppResult<()> {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() || self.state_holder.borrow().is_history_search() {
+            return Ok(());
+        }
+        let highlight_index = self.get_highlight_index(path_holder.len())?;
+        self.folder_holder.toggle_selection(highlight_index);
+        self.move_down();
+        Ok(())
+    }
+
+    /// Selects every entry kept by the filter
+    pub fn select_all(&mut self) {
+        if !self.state_holder.borrow().is_history_search() {
+            self.folder_holder.select_all();
+        }
+    }
+
+    /// Toggles the selection of every entry kept by the filter
+    pub fn invert_selection(&mut self) {
+        if !self.state_holder.borrow().is_history_search() {
+            self.folder_holder.invert_selection();
+        }
+    }
+
+    /// Empties the multi-selection
+    ///
+    /// # Returns
+    ///
+    /// Returns true if some entries were selected
+    pub fn clear_selection(&mut self) -> bool {
+        self.folder_holder.clear_selection()
+    }
+
+    /// Asks to confirm the delete of the selected files and directories
+    ///
+    /// The confirmation popup is opened while the items are measured in
+    /// the background, nothing is deleted until
+    /// [`MessageHolder::confirm_delete`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is selected
+    /// - `AppError::State`: If selection is invalid or a delete is in progress
+    pub fn delete(&mut self) -> AppResult<()> {
+        if self.delete_holder.is_some() {
+            return Err(AppError::State("A delete is in progress".into()));
+        }
+        let paths = self.selected_paths()?;
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut n_dirs = 0;
+        for path in &paths {
+            let meta_data = path
+                .symlink_metadata()
+                .map_err(|_| AppError::Path(format!("Unable to delete {}", path.display())))?;
+            if meta_data.is_dir() {
+                n_dirs += 1;
+            }
+        }
+        self.size_holder = Some(SizeHolder::start(paths.clone()));
+        self.pending_confirm = Some(Confirmation::Delete(PendingDelete {
+            tree_size: None,
+            paths,
+            n_dirs,
+        }));
+        self.state_holder.borrow_mut().to_confirm()
+    }
+
+    /// Starts the delete waiting for confirmation in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `permanent` - If true the items are removed, otherwise they are moved to the trash
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may
+    /// contain `AppError::State` if no delete is waiting for confirmation
+    pub fn confirm_delete(&mut self, permanent: bool) -> AppResult<String> {
+        let Some(Confirmation::Delete(pending_delete)) = self.pending_confirm.take() else {
+            return Err(AppError::State("No delete to confirm".into()));
+        };
+        self.size_holder = None;
+        let deleted = Self::deleted_items(&pending_delete.paths, pending_delete.paths.len());
+        let message = if permanent {
+            format!("Deleting {}", deleted)
+        } else {
+            format!("Moving {} to the trash", deleted)
+        };
+        self.delete_holder = Some(DeleteHolder::start(
+            pending_delete.paths,
+            permanent,
+            self.trash_holder.clone(),
+        ));
+        Ok(message)
+    }
+
+    /// Checks if items are measured or deleted in the background
+    pub fn is_deleting(&self) -> bool {
+        self.size_holder.is_some() || self.delete_holder.is_some()
+    }
+
+    /// Applies the progress of the measure and the delete in progress
+    ///
+    /// Once finished, items moved to the trash form one group in the
+    /// journal and the listing is refreshed. When an item cannot be
+    /// deleted, the ones deleted before it stay deleted.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<String>>` with the message to show once the
+    /// delete is finished, which may contain:
+    /// - `AppError::Path`: If an item cannot be deleted or trashed
+    /// - `AppError::Io`: If the directory cannot be read again
+    pub fn poll_delete(&mut self) -> AppResult<Option<String>> {
+        if let Some(tree_size) = self.size_holder.as_ref().and_then(SizeHolder::poll) {
+            self.size_holder = None;
+            if let Some(Confirmation::Delete(pending_delete)) = &mut self.pending_confirm {
+                pending_delete.tree_size = Some(tree_size);
+            }
+        }
+        let Some(delete_holder) = &mut self.delete_holder else {
+            return Ok(None);
+        };
+        if !delete_holder.poll() {
+            return Ok(None);
+        }
+        let Some(mut delete_holder) = self.delete_holder.take() else {
+            return Ok(None);
+        };
+        let n_done = delete_holder.n_done();
+        let operations = delete_holder
+            .trashed
+            .drain(..)
+            .map(Operation::Trash)
+            .collect();
+        self.journal_holder.record_group(operations);
+        self.folder_holder.prune_selection();
+        let deleted = Self::deleted_items(&delete_holder.paths, n_done);
+        let message = if delete_holder.permanent {
+            format!("Deleted {}", deleted)
+        } else {
+            format!("Moved {} to the trash", deleted)
+        };
+        let result = match delete_holder.error.take() {
+            Some(error) if n_done == 0 => Err(AppError::Path(error)),
+            Some(error) => Err(AppError::Path(format!("{}, failed: {}", message, error))),
+            None => Ok(Some(message)),
+        };
+        // the listing is refreshed even if the delete failed, whose error comes first
+        let refresh_result = self.folder_holder.refresh();
+        let message = result?;
+        refresh_result?;
+        Ok(message)
+    }
+
+    /// Stops the delete in progress after the item being deleted
+    ///
+    /// # Returns
+    ///
+    /// Returns whether a delete was in progress
+    pub fn cancel_delete(&mut self) -> bool {
+        let Some(delete_holder) = &self.delete_holder else {
+            return false;
+        };
+        delete_holder.cancel();
+        true
+    }
+
+    /// Drops the operation waiting for confirmation
+    pub fn cancel_confirm(&mut self) {
+        self.size_holder = None;
+        self.pending_confirm = None;
+    }
+
+    /// Starts typing a new name for the selected entry
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<String>>` with the current name to edit,
+    /// `None` if nothing is selected, which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is selected
+    /// - `AppError::State`: If the name cannot be typed from the current mode
+    pub fn start_rename(&mut self) -> AppResult<Option<String>> {
+        let Some(path) = self.selected_entry_path()? else {
+            return Ok(None);
+        };
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        self.state_holder.borrow_mut().to_prompt()?;
+        self.pending_prompt = Some(Prompt::Rename(path));
+        Ok(Some(name))
+    }
+
+    /// Starts typing the name of a file or directory to create
+    ///
+    /// # Arguments
+    ///
+    /// * `is_dir` - Whether a directory is created
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// name cannot be typed from the current mode
+    pub fn start_create(&mut self, is_dir: bool) -> AppResult<()> {
+        self.state_holder.borrow_mut().to_prompt()?;
+        self.pending_prompt = Some(if is_dir {
+            Prompt::NewDirectory
+        } else {
+            Prompt::NewFile
+        });
+        Ok(())
+    }
+
+    /// Renames or creates an entry with the typed name, and selects it
+    ///
+    /// Renames are recorded in the journal so they can be undone. A typed
+    /// command is run with [`MessageHolder::run_command`].
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Typed name or command
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If no name was asked for
+    /// - `AppError::Path`: If the name is invalid or exists already
+    /// - `AppError::Io`: If the entry cannot be renamed or created
+    pub fn submit_prompt(&mut self, text: &str) -> AppResult<String> {
+        let prompt = self
+            .pending_prompt
+            .take()
+            .ok_or(AppError::State("No name was asked for".into()))?;
+        let (directory, renamed, is_dir) = match prompt {
+            Prompt::Rename(from) => (
+                from.parent().map(Path::to_path_buf).unwrap_or_default(),
+                Some(from),
+                false,
+            ),
+            Prompt::NewFile => (self.folder_holder.current_directory.clone(), None, false),
+            Prompt::NewDirectory => (self.folder_holder.current_directory.clone(), None, true),
+            Prompt::Command => return self.run_command(text),
+        };
+        let name = check_name(text)?;
+        let path = directory.join(name);
+        if renamed.as_ref() == Some(&path) {
+            return Ok(String::new());
+        }
+        let exists_error = || AppError::Path(format!("{} exists already", path.display()));
+        if path.symlink_metadata().is_ok() {
+            return Err(exists_error());
+        }
+        // the target may be created meanwhile, nothing existing is replaced
+        let map_error = |error: io::Error| match error.kind() {
+            ErrorKind::AlreadyExists => exists_error(),
+            _ => AppError::Io(error),
+        };
+        let (message, mut changed) = match renamed {
+            Some(from) => {
+                rename_no_replace(&from, &path).map_err(map_error)?;
+                self.journal_holder.record(Operation::Move {
+                    from: from.clone(),
+                    to: path.clone(),
+                });
+                (
+                    format!("Renamed {} to {}", from.display(), name),
+                    vec![from],
+                )
+            }
+            None if is_dir => {
+                fs::create_dir(&path).map_err(map_error)?;
+                (format!("Created directory {}", path.display()), Vec::new())
+            }
+            None => {
+                OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)
+                    .map_err(map_error)?;
+                (format!("Created file {}", path.display()), Vec::new())
+            }
+        };
+        changed.push(path.clone());
+        self.refresh_and_select(changed.into_iter().collect(), Some(path))?;
+        Ok(message)
+    }
+
+    /// Drops the name being typed
+    pub fn cancel_prompt(&mut self) {
+        self.pending_prompt = None;
+    }
+
+    /// Returns the files to open in the external editor
+    ///
+    /// In file view the opened file is edited at the first line shown,
+    /// except in the log view whose lines are filtered. In the folder view
+    /// the selected files are edited, directories are left out.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<EditorRequest>` which may contain:
+    /// - `AppError::State`: If nothing is selected
+    /// - `AppError::Path`: If the parent directory shortcut or only directories are selected
+    pub fn open_in_editor(&mut self) -> AppResult<EditorRequest> {
+        if self.state_holder.borrow().is_file_view() {
+            if let Some(file_path) = &self.file_opened {
+                return Ok(EditorRequest {
+                    paths: vec![file_path.clone()],
+                    line: self
+                        .log_holder
+                        .is_none()
+                        .then_some(self.vertical_scroll + 1),
+                    task: EditorTask::Open { n_skipped: 0 },
+                });
+            }
+        }
+        let paths = self.selected_paths()?;
+        if paths.is_empty() {
+            return Err(AppError::State("Nothing to open".into()));
+        }
+        let (directories, paths): (Vec<PathBuf>, Vec<PathBuf>) =
+            paths.into_iter().partition(|path| path.is_dir());
+        if paths.is_empty() {
+            return Err(AppError::Path(
+                "Cannot open directories in the editor".into(),
+            ));
+        }
+        Ok(EditorRequest {
+            paths,
+            line: None,
+            task: EditorTask::Open {
+                n_skipped: directories.len(),
+            },
+        })
+    }
+
+    /// Reads changed files and the current directory again
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Files changed by the external editor or a command
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the opened file cannot be read
+    /// - `AppError::Parse`: If a directory cannot be read
+    pub fn reload_paths(&mut self, paths: &[PathBuf]) -> AppResult<()> {
+        let mut changed: HashSet<PathBuf> = paths.iter().cloned().collect();
+        changed.insert(self.folder_holder.current_directory.clone());
+        self.apply_file_changes(&changed)?;
+        // a followed file is not reloaded on changes, it is followed from its new end
+        if let Some(file_path) = self.file_opened.clone() {
+            if self.follow_holder.is_some() && changed.contains(&file_path) {
+                self.reload_file(&file_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts typing a shell command to run on the selected entries
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// command cannot be typed from the current mode
+    pub fn start_command(&mut self) -> AppResult<()> {
+        self.state_holder.borrow_mut().to_prompt()?;
+        self.pending_prompt = Some(Prompt::Command);
+        Ok(())
+    }
+
+    /// Runs a typed shell command, showing its output in the command view
+    ///
+    /// In file view `%s` is the opened file and `%l` the first line shown,
+    /// otherwise `%s` is the selected entries. Commands prefixed with `!`
+    /// are kept for [`MessageHolder::pending_command`], to run with the
+    /// terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed` - Typed command
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If a placeholder has no value or the command view
+    ///   cannot be opened
+    /// - `AppError::Path`: If the parent directory shortcut is selected
+    /// - `AppError::Io`: If the shell cannot be run
+    pub fn run_command(&mut self, typed: &str) -> AppResult<String> {
+        let (paths, line) = match &self.file_opened {
+            Some(file_path) if self.state_holder.borrow().is_file_view() => (
+                vec![file_path.clone()],
+                self.log_holder
+                    .is_none()
+                    .then_some(self.vertical_scroll + 1),
+            ),
+            _ => (self.selected_paths()?, None),
+        };
+        let command =
+            ShellCommand::new(typed, &paths, &self.folder_holder.current_directory, line)?;
+        if command.interactive {
+            self.pending_command = Some(command);
+            return Ok(String::new());
+        }
+        let message = format!("Running {}", command.command);
+        self.command_holder = Some(CommandHolder::start(command)?);
+        self.state_holder.borrow_mut().to_command_view()?;
+        Ok(message)
+    }
+
+    /// Checks if the output of a command is still being read
+    pub fn is_command_running(&self) -> bool {
+        self.command_holder
+            .as_ref()
+            .is_some_and(|command_holder| !command_holder.is_done())
+    }
+
+    /// Reads the output of the command shown in the command view
+    ///
+    /// Once the command exits, the paths it was given are refreshed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<String>>` with the exit status to show once
+    /// the command exits, which may contain:
+    /// - `AppError::Io`: If the command cannot be waited for
+    /// - `AppError::Parse`: If a directory cannot be read
+    pub fn poll_command(&mut self) -> AppResult<Option<String>> {
+        let Some(command_holder) = &mut self.command_holder else {
+            return Ok(None);
+        };
+        let Some(status) = command_holder.poll()? else {
+            return Ok(None);
+        };
+        let command = command_holder.command.clone();
+        self.finish_command(&command)?;
+        Ok(Some(format!(
+            "{} ended with {}",
+            command.command,
+            describe_status(status)
+        )))
+    }
+
+    /// Stops the command shown in the command view, if still running
+    ///
+    /// # Returns
+    ///
+    /// Returns whether the command was running
+    pub fn cancel_command(&mut self) -> bool {
+        self.command_holder
+            .as_mut()
+            .is_some_and(|command_holder| command_holder.cancel())
+    }
+
+    /// Closes the command view, stopping the command if still running
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the command view is not opened
+    /// - `AppError::Parse`: If a directory cannot be read
+    pub fn close_command(&mut self) -> AppResult<()> {
+        let command_holder = self
+            .command_holder
+            .take()
+            .ok_or(AppError::State("No command is shown".into()))?;
+        self.state_holder.borrow_mut().pop_mode()?;
+        if command_holder.is_running() {
+            let command = command_holder.command.clone();
+            drop(command_holder);
+            self.finish_command(&command)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the paths given to a command and their directories again
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - Command that ran
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the opened file cannot be read
+    /// - `AppError::Parse`: If a directory cannot be read
+    pub fn finish_command(&mut self, command: &ShellCommand) -> AppResult<()> {
+        let parents = command.affected.iter().filter_map(|path| path.parent());
+        let paths: Vec<PathBuf> = parents
+            .map(Path::to_path_buf)
+            .chain(command.affected.iter().cloned())
+            .collect();
+        self.reload_paths(&paths)
+    }
+
+    /// Writes the names to rename to a buffer for the external editor
+    ///
+    /// The selected entries are listed, or every entry kept by the filter
+    /// when nothing is selected.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<EditorRequest>` which may contain:
+    /// - `AppError::State`: If nothing is listed or in the history view
+    /// - `AppError::Path`: If a name cannot be written on one line
+    /// - `AppError::Io`: If the buffer cannot be written
+    pub fn start_bulk_rename(&mut self) -> AppResult<EditorRequest> {
+        if self.state_holder.borrow().is_history_search() {
+            return Err(AppError::State(
+                "Bulk rename works in the folder view".into(),
+            ));
+        }
+        self.folder_holder.prune_selection();
+        let paths: Vec<PathBuf> = if self.folder_holder.multi_selection.is_empty() {
+            self.folder_holder
+                .selected_path_holder
+                .iter()
+                .filter(|entry| entry.file_name != "..")
+                .map(FileHolder::to_path)
+                .collect()
+        } else {
+            self.folder_holder.multi_selection.iter().cloned().collect()
+        };
+        if paths.is_empty() {
+            return Err(AppError::State("Nothing to rename".into()));
+        }
+        let text = buffer_text(&self.folder_holder.current_directory, &paths)?;
+        let buffer = create_buffer("rename", &text)?;
+        Ok(EditorRequest {
+            paths: vec![buffer.clone()],
+            line: None,
+            task: EditorTask::BulkRename {
+                buffer,
+                originals: paths,
+            },
+        })
+    }
+
+    /// Compares the edited buffer with the names and asks to confirm the renames
+    ///
+    /// # Arguments
+    ///
+    /// * `originals` - Items listed in the buffer, in order
+    /// * `text` - Edited buffer
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::Parse`: If lines were added or removed
+    /// - `AppError::Path`: If the renames would collide or a name is invalid
+    pub fn finish_bulk_rename(&mut self, originals: &[PathBuf], text: &str) -> AppResult<String> {
+        let plan = RenamePlan::new(&self.folder_holder.current_directory, originals, text)?;
+        if plan.renames.is_empty() {
+            return Ok("Nothing renamed".into());
+        }
+        self.pending_confirm = Some(Confirmation::Rename(plan));
+        self.state_holder.borrow_mut().to_confirm()?;
+        Ok(String::new())
+    }
+
+    /// Runs the renames waiting for confirmation
+    ///
+    /// The renames form one group in the journal, and renamed entries
+    /// stay selected.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If no rename is waiting for confirmation
+    /// - `AppError::Path`: If a name was taken since the buffer was edited
+    /// - `AppError::Io`: If an item cannot be renamed
+    pub fn confirm_rename(&mut self) -> AppResult<String> {
+        let Some(Confirmation::Rename(plan)) = self.pending_confirm.take() else {
+            return Err(AppError::State("No rename to confirm".into()));
+        };
+        let (operations, result) = plan.apply();
+        let changed: HashSet<PathBuf> = operations.iter().flat_map(Operation::paths).collect();
+        self.journal_holder.record_group(operations);
+        if result.is_ok() {
+            for job in &plan.renames {
+                if self.folder_holder.multi_selection.remove(&job.from) {
+                    self.folder_holder.multi_selection.insert(job.to.clone());
+                }
+            }
+        }
+        self.folder_holder.prune_selection();
+        self.apply_file_changes(&changed)?;
+        result?;
+        Ok(format!("Renamed {} items", plan.renames.len()))
+    }
+
+    /// Puts the selected entries in the clipboard
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Whether the entries are copied or moved when pasted
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is selected
+    /// - `AppError::State`: If selection is invalid
+    pub fn yank(&mut self, mode: TransferMode) -> AppResult<String> {
+        let paths = self.selected_paths()?;
+        let action = match mode {
+            TransferMode::Copy => "Copied",
+            TransferMode::Move => "Cut",
+        };
+        let message = match paths.as_slice() {
+            [] => return Ok(String::new()),
+            [path] => format!("{} {}, <P> to paste", action, path.display()),
+            paths => format!("{} {} items, <P> to paste", action, paths.len()),
+        };
+        self.clipboard = Some(Clipboard { paths, mode });
+        Ok(message)
+    }
+
+    /// Copies the paths of the selected entries to the terminal clipboard
+    ///
+    /// Every selected entry is copied, one path per line, or the
+    /// highlighted entry if none is selected. The text is kept in
+    /// [`MessageHolder::pending_clipboard`] until written to the terminal.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::Path`: If the parent directory shortcut is highlighted
+    /// - `AppError::State`: If selection is invalid
+    pub fn yank_paths(&mut self) -> AppResult<String> {
+        self.folder_holder.prune_selection();
+        let paths: Vec<PathBuf> = if self.folder_holder.multi_selection.is_empty()
+            || self.state_holder.borrow().is_history_search()
+        {
+            self.selected_entry_path()?.into_iter().collect()
+        } else {
+            self.folder_holder.multi_selection.iter().cloned().collect()
+        };
+        let message = match paths.as_slice() {
+            [] => return Ok(String::new()),
+            [path] => format!("Copied path {}", path.display()),
+            paths => format!("Copied {} paths", paths.len()),
+        };
+        let text: Vec<String> = paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        self.pending_clipboard = Some(text.join("\n"));
+        Ok(message)
+    }
+
+    /// Pastes the clipboard into the current directory
+    ///
+    /// Items whose name exists already in the directory open the conflict
+    /// popup, the others are pasted in the background.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If the clipboard is empty or a paste is in progress
+    /// - `AppError::Path`: If a directory would be pasted into itself
+    pub fn paste(&mut self) -> AppResult<String> {
+        if self.transfer_holder.is_some() {
+            return Err(AppError::State("A paste is in progress".into()));
+        }
+        let clipboard = self
+            .clipboard
+            .clone()
+            .ok_or(AppError::State("Nothing to paste".into()))?;
+        let directory = self.folder_holder.current_directory.clone();
+        let mut pending_paste = PendingPaste {
+            mode: clipboard.mode,
+            jobs: Vec::new(),
+            conflicts: Vec::new(),
+        };
+        for from in clipboard.paths {
+            let Some(name) = from.file_name() else {
+                continue;
+            };
+            let to = directory.join(name);
+            if clipboard.mode == TransferMode::Move && to == from {
+                continue;
+            }
+            if directory.starts_with(&from) {
+                return Err(AppError::Path(format!(
+                    "Cannot paste {} into itself",
+                    from.display()
+                )));
+            }
+            let job = TransferJob { from, to };
+            if job.to.symlink_metadata().is_ok() {
+                pending_paste.conflicts.push(job);
+            } else {
+                pending_paste.jobs.push(job);
+            }
+        }
+        if pending_paste.conflicts.is_empty() {
+            return self.start_transfer(pending_paste.jobs, pending_paste.mode, Vec::new());
+        }
+        self.pending_confirm = Some(Confirmation::Conflict(pending_paste));
+        self.state_holder.borrow_mut().to_confirm()?;
+        Ok(String::new())
+    }
+
+    /// Pastes once the conflicts are resolved
+    ///
+    /// Overwritten items are moved to the trash, so they can be restored
+    /// with undo. Items whose existing item cannot be trashed, or holds
+    /// the pasted item, are not pasted, the others are.
+    ///
+    /// # Arguments
+    ///
+    /// * `choice` - How the pasted items replace the existing ones
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If no paste is waiting for confirmation
+    /// - `AppError::Path`: Listing the existing items that cannot be overwritten
+    pub fn resolve_conflict(&mut self, choice: ConflictChoice) -> AppResult<String> {
+        let Some(Confirmation::Conflict(pending_paste)) = self.pending_confirm.take() else {
+            return Err(AppError::State("No paste to confirm".into()));
+        };
+        let mut jobs = pending_paste.jobs;
+        let mut trashed = Vec::new();
+        let mut errors = Vec::new();
+        for job in pending_paste.conflicts {
+            match choice {
+                ConflictChoice::Skip => (),
+                // an item pasted over itself is left as it is
+                ConflictChoice::Overwrite if job.from == job.to => (),
+                // trashing a directory holding the pasted item would trash it too
+                ConflictChoice::Overwrite if job.from.starts_with(&job.to) => errors.push(format!(
+                    "{}: it holds {}",
+                    job.to.display(),
+                    job.from.display()
+                )),
+                ConflictChoice::Overwrite => match self.trash_holder.trash(&job.to) {
+                    Ok(trash_entry) => {
+                        trashed.push(trash_entry);
+                        jobs.push(job);
+                    }
+                    // the item is not pasted when the existing one is kept
+                    Err(error) => errors.push(format!("{}: {}", job.to.display(), error)),
+                },
+                ConflictChoice::KeepBoth => jobs.push(TransferJob {
+                    to: unique_path(&job.to),
+                    from: job.from,
+                }),
+            }
+        }
+        let message = self.start_transfer(jobs, pending_paste.mode, trashed)?;
+        if !errors.is_empty() {
+            return Err(AppError::Path(format!(
+                "{}, not overwritten: {}",
+                message,
+                errors.join(", ")
+            )));
+        }
+        Ok(message)
+    }
+
+    /// Starts pasting in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `jobs` - Items to paste and where
+    /// * `mode` - Whether the items are copied or moved
+    /// * `trashed` - Existing items trashed to be overwritten, journaled with the paste
+    fn start_transfer(
+        &mut self,
+        jobs: Vec<TransferJob>,
+        mode: TransferMode,
+        trashed: Vec<TrashEntry>,
+    ) -> AppResult<String> {
+        if jobs.is_empty() {
+            self.journal_holder
+                .record_group(trashed.into_iter().map(Operation::Trash).collect());
+            return Ok("Nothing to paste".into());
+        }
+        // the cut items are gone once moved
+        if mode == TransferMode::Move {
+            self.clipboard = None;
+        }
+        let message = format!("Pasting {} items", jobs.len());
+        let mut transfer_holder = TransferHolder::start(jobs, mode);
+        transfer_holder.trashed = trashed;
+        self.transfer_holder = Some(transfer_holder);
+        Ok(message)
+    }
+
+    /// Checks if a paste is in progress
+    pub fn is_transferring(&self) -> bool {
+        self.transfer_holder.is_some()
+    }
+
+    /// Applies the progress of the paste in progress
+    ///
+    /// Once finished, the moves and the items trashed to be overwritten
+    /// are recorded as one group in the journal, and the listings of the
+    /// touched directories are refreshed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Option<String>>` with the message to show once
+    /// finished, which may contain `AppError::Path` listing the items that
+    /// could not be pasted
+    pub fn poll_transfer(&mut self) -> AppResult<Option<String>> {
+        let Some(transfer_holder) = &mut self.transfer_holder else {
+            return Ok(None);
+        };
+        if !transfer_holder.poll() {
+            return Ok(None);
+        }
+        let Some(mut transfer_holder) = self.transfer_holder.take() else {
+            return Ok(None);
+        };
+        let mut changed = HashSet::new();
+        // overwritten items and the paste are undone together
+        let mut operations: Vec<Operation> = transfer_holder
+            .trashed
+            .drain(..)
+            .map(Operation::Trash)
+            .collect();
+        for job in &transfer_holder.finished {
+            changed.insert(job.to.clone());
+            if transfer_holder.mode == TransferMode::Move {
+                changed.insert(job.from.clone());
+                operations.push(Operation::Move {
+                    from: job.from.clone(),
+                    to: job.to.clone(),
+                });
+            }
+        }
+        for job in &transfer_holder.kept {
+            changed.insert(job.to.clone());
+            changed.insert(job.from.clone());
+        }
+        self.journal_holder.record_group(operations);
+        self.folder_holder.prune_selection();
+        let selected = transfer_holder.finished.first().map(|job| job.to.clone());
+        self.refresh_and_select(changed, selected)?;
+        let action = match transfer_holder.mode {
+            TransferMode::Copy => "Copied",
+            TransferMode::Move => "Moved",
+        };
+        let message = format!(
+            "{} {} items ({})",
+            action,
+            transfer_holder.finished.len(),
+            transfer_holder.done
+        );
+        if !transfer_holder.errors.is_empty() {
+            return Err(AppError::Path(format!(
+                "{}, failed: {}",
+                message,
+                transfer_holder.errors.join(", ")
+            )));
+        }
+        Ok(Some(message))
+    }
+
+    /// Stops the paste in progress after the file being copied
+    ///
+    /// # Returns
+    ///
+    /// Returns whether a paste was in progress
+    pub fn cancel_transfer(&mut self) -> bool {
+        let Some(transfer_holder) = &self.transfer_holder else {
+            return false;
+        };
+        transfer_holder.cancel();
+        true
+    }
+
+    /// Refreshes the listings holding changed paths, then selects an entry
+    ///
+    /// # Arguments
+    ///
+    /// * `changed` - Paths created, removed or renamed
+    /// * `selected` - Entry of the current directory to select (if any)
+    fn refresh_and_select(
+        &mut self,
+        changed: HashSet<PathBuf>,
+        selected: Option<PathBuf>,
+    ) -> AppResult<()> {
+        self.apply_file_changes(&changed)?;
+        let Some(selected) = selected else {
+            return Ok(());
+        };
+        if selected.parent() == Some(self.folder_holder.current_directory.as_path()) {
+            self.folder_holder.remember_path(selected);
+            let current_directory = self.folder_holder.current_directory.clone();
+            self.restore_selection(current_directory, None);
+        }
+        Ok(())
+    }
+
+    /// Reverts the latest trash, rename or move action of the session
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If there is nothing to undo
+    /// - `AppError::Path`: If something else now exists at the original location
+    /// - `AppError::Io`: If an item cannot be moved back
+    pub fn undo(&mut self) -> AppResult<String> {
+        let result = self.journal_holder.undo(&self.trash_holder);
+        let operations = self.refresh_after_journal(result)?;
+        Ok(format!("Undid {}", describe_group(&operations)))
+    }
+
+    /// Runs the latest undone action again
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<String>` with the message to show, which may contain:
+    /// - `AppError::State`: If there is nothing to redo
+    /// - `AppError::Path`: If something else now exists at the destination
+    /// - `AppError::Io`: If an item cannot be moved
+    pub fn redo(&mut self) -> AppResult<String> {
+        let result = self.journal_holder.redo(&self.trash_holder);
+        let operations = self.refresh_after_journal(result)?;
+        Ok(format!("Redid {}", describe_group(&operations)))
+    }
+
+    /// Refreshes the cached listings holding the paths touched by an undo or redo
+    ///
+    /// A group stopped by a failure may have been partly run, so the
+    /// current listing is read again before the error is returned.
+    fn refresh_after_journal(
+        &mut self,
+        result: AppResult<Vec<Operation>>,
+    ) -> AppResult<Vec<Operation>> {
+        let operations = match result {
+            Ok(operations) => operations,
+            Err(error @ AppError::State(_)) => return Err(error),
+            Err(error) => {
+                self.folder_holder.refresh()?;
+                return Err(error);
+            }
+        };
+        let changed: HashSet<PathBuf> = operations.iter().flat_map(Operation::paths).collect();
+        self.apply_file_changes(&changed)?;
+        Ok(operations)
+    }
+
+    /// Refreshes the current folder's cache
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Cache` or `AppError::Io`
+    pub fn refresh_current_folder_cache(&mut self) -> AppResult<()> {
+        self.folder_holder.refresh()?;
+        Ok(())
+    }
+
+    /// Restores the directory history saved by a previous session
+    ///
+    /// # Arguments
+    ///
+    /// * `history_store` - State file to load from and save to
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be read
+    pub fn load_history(&mut self, history_store: HistoryStore) -> AppResult<()> {
+        self.folder_holder.load_history(history_store)
+    }
+
+    /// Saves the directory history for the next session
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be written
+    pub fn save_history(&self) -> AppResult<()> {
+        self.folder_holder.save_history()
+    }
+
+    /// Restores the named marks saved by a previous session
+    ///
+    /// Marks outside the initial directory are kept for saving, but cannot be
+    /// picked or jumped to.
+    ///
+    /// # Arguments
+    ///
+    /// * `store_path` - State file to load from and save to on every change
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Io` if the state
+    /// file cannot be read
+    pub fn load_bookmarks(&mut self, store_path: PathBuf) -> AppResult<()> {
+        self.bookmark_holder =
+            BookmarkHolder::load(store_path, self.folder_holder.initial_directory())?;
+        Ok(())
+    }
+
+    /// Marks the opened file at its scroll position, or the current directory
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Mark letter
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If `name` is not a letter
+    /// - `AppError::Io`: If the marks cannot be saved
+    pub fn set_bookmark(&mut self, name: char) -> AppResult<()> {
+        let bookmark = match self.shown_file() {
+            Some(file_path) => Bookmark {
+                path: file_path,
+                vertical_scroll: self.vertical_scroll,
+                horizontal_scroll: self.horizontal_scroll,
+            },
+            None => Bookmark {
+                path: self.folder_holder.current_directory.clone(),
+                vertical_scroll: 0,
+                horizontal_scroll: 0,
+            },
+        };
+        self.bookmark_holder.set(name, bookmark)
+    }
+
+    /// Jumps to a named mark
+    ///
+    /// A directory mark opens the directory in search view. A file mark opens
+    /// its parent directory, then the file at the marked scroll position.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Mark letter
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the mark is not set
+    /// - `AppError::Path`: If the marked path no longer exists or is outside
+    ///   the initial directory
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::Io`: If the file cannot be read
+    pub fn jump_to_bookmark(&mut self, name: char) -> AppResult<()> {
+        let bookmark = self.bookmark_holder.get(name)?.clone();
+        let path = bookmark.path.canonicalize().map_err(|_| {
+            AppError::Path(format!(
+                "Mark {:?} points to missing {:?}",
+                name, bookmark.path
+            ))
+        })?;
+        let location = if path.is_dir() {
+            JumpEntry {
+                directory: path,
+                highlight_index: None,
+                file_opened: None,
+                vertical_scroll: 0,
+                horizontal_scroll: 0,
+            }
+        } else {
+            JumpEntry {
+                directory: path
+                    .parent()
+                    .ok_or(AppError::Path(format!(
+                        "Unable to get parent folder for {:?}",
+                        path
+                    )))?
+                    .to_path_buf(),
+                highlight_index: None,
+                file_opened: Some(path),
+                vertical_scroll: bookmark.vertical_scroll,
+                horizontal_scroll: bookmark.horizontal_scroll,
+            }
+        };
+
+        self.navigate(|holder| holder.restore_location(location))
+    }
+
+    /// Jumps to the mark selected in the bookmark picker
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If no mark matches the filter
+    /// - Errors of [`MessageHolder::jump_to_bookmark`]
+    pub fn submit_bookmark(&mut self) -> AppResult<()> {
+        let name = self.bookmark_holder.selected()?;
+        self.jump_to_bookmark(name)
+    }
+
+    /// Goes back to the previous location in the jump list
+    ///
+    /// A location that cannot be shown anymore is dropped from the list.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If already at the oldest location
+    /// - `AppError::Path`: If the location no longer exists
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::Io`: If the file cannot be read
+    pub fn jump_back(&mut self) -> AppResult<()> {
+        let location = self
+            .jump_holder
+            .back(self.current_location())
+            .ok_or(AppError::State("Already at the oldest location".into()))?;
+        let result = self.restore_location(location);
+        if result.is_err() {
+            self.jump_holder.discard_back();
+        }
+        result
+    }
+
+    /// Goes forward to the next location in the jump list
+    ///
+    /// A location that cannot be shown anymore is dropped from the list.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If already at the newest location
+    /// - `AppError::Path`: If the location no longer exists
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::Io`: If the file cannot be read
+    pub fn jump_forward(&mut self) -> AppResult<()> {
+        let location = self
+            .jump_holder
+            .forward(self.current_location())
+            .ok_or(AppError::State("Already at the newest location".into()))?;
+        let result = self.restore_location(location);
+        if result.is_err() {
+            self.jump_holder.discard_forward();
+        }
+        result
+    }
+
+    /// Closes the opened file and returns to the previous mode
+    ///
+    /// The file is recorded in the jump list so it can be gone back to.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if no file
+    /// view is opened
+    pub fn close_file_view(&mut self) -> AppResult<()> {
+        self.record_jump();
+        self.reset_file_view();
+        self.state_holder.borrow_mut().pop_mode()
+    }
+
+    /// Goes back to the opened file from the search opened over it
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>` telling whether a search was left, which may
+    /// contain `AppError::State` if the search is not nested
+    pub fn close_nested_search(&mut self) -> AppResult<bool> {
+        if self.file_opened.is_none() || !self.state_holder.borrow().is_search() {
+            return Ok(false);
+        }
+        self.state_holder.borrow_mut().pop_mode()?;
+        Ok(true)
+    }
+
+    /// Returns the location currently shown
+    fn current_location(&self) -> JumpEntry {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        let highlight_index = if path_holder.is_empty() {
+            None
+        } else {
+            self.get_highlight_index(path_holder.len())
+                .ok()
+                .and_then(|index| self.folder_holder.unfiltered_index(index))
+        };
+        JumpEntry {
+            directory: self.folder_holder.current_directory.clone(),
+            highlight_index,
+            file_opened: self.file_opened.clone(),
+            vertical_scroll: self.vertical_scroll,
+            horizontal_scroll: self.horizontal_scroll,
+        }
+    }
+
+    /// Records the current location before navigating away from it
+    fn record_jump(&mut self) {
+        let location = self.current_location();
+        self.jump_holder.record(location);
+    }
+
+    /// Runs a navigation, recording the location it leaves once it succeeds
+    ///
+    /// # Arguments
+    ///
+    /// * `navigation` - Moves to the new location
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain the errors of `navigation`
+    fn navigate(&mut self, navigation: impl FnOnce(&mut Self) -> AppResult<()>) -> AppResult<()> {
+        let location = self.current_location();
+        navigation(self)?;
+        self.jump_holder.record(location);
+        Ok(())
+    }
+
+    /// Shows a location: its directory in search view, then the file if any
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - Location to show
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If the directory is outside the initial directory,
+    ///   or the file no longer exists
+    /// - `AppError::Parse`: If the directory cannot be read
+    /// - `AppError::Io`: If the file cannot be read
+    ///
+    /// The view is left unchanged when the directory cannot be shown.
+    fn restore_location(&mut self, location: JumpEntry) -> AppResult<()> {
+        self.folder_holder.check_directory(&location.directory)?;
+        if let Some(file_path) = &location.file_opened {
+            if !file_path.is_file() {
+                return Err(AppError::Path(format!("{:?} no longer exists", file_path)));
+            }
+        }
+        self.remember_selection();
+        self.reset_file_view();
+        let previous_directory = self.folder_holder.current_directory.clone();
+        self.folder_holder
+            .submit_new_working_directory(location.directory)?;
+        self.restore_selection(previous_directory, location.highlight_index);
+        self.state_holder.borrow_mut().unwind_to_search()?;
+
+        let Some(file_path) = location.file_opened else {
+            return Ok(());
+        };
+        self.open_file(file_path)?;
+        if let Some(file_text_info) = &self.file_text_info {
+            self.vertical_scroll = location.vertical_scroll.min(file_text_info.n_rows);
+            self.horizontal_scroll = location
+                .horizontal_scroll
+                .min(file_text_info.max_line_length);
+        }
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.horizontal_scroll_state = self
+            .horizontal_scroll_state
+            .position(self.horizontal_scroll);
+        Ok(())
+    }
+
+    /// Remembers the selected entry of the current directory in its cache
+    ///
+    /// Does nothing in history view, where the selection is not an entry of
+    /// the current directory.
+    fn remember_selection(&mut self) {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        if path_holder.is_empty() || self.state_holder.borrow().is_history_search() {
+            return;
+        }
+        if let Ok(highlight_index) = self.get_highlight_index(path_holder.len()) {
+            self.folder_holder.remember_selection(highlight_index);
+        }
+    }
+
+    /// Changes to a directory and restores the selection remembered for it
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - New directory path
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Cache`: If cache lookup fails
+    /// - `AppError::Path`: If path resolution fails
+    fn enter_directory(&mut self, path: PathBuf) -> AppResult<()> {
+        let previous_directory = self.folder_holder.current_directory.clone();
+        self.folder_holder.submit_new_working_directory(path)?;
+        self.restore_selection(previous_directory, None);
+        Ok(())
+    }
+
+    /// Selects an entry of the current directory, once it is listed
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_directory` - Directory to select if no entry was remembered
+    /// * `highlight_index` - Index to select instead of the remembered one
+    fn restore_selection(&mut self, previous_directory: PathBuf, highlight_index: Option<usize>) {
+        if self.folder_holder.is_loading() {
+            self.raw_highlight_index = 0;
+            self.selection_after_load = Some((previous_directory, highlight_index));
+            return;
+        }
+        self.selection_after_load = None;
+        let highlight_index = highlight_index
+            .unwrap_or_else(|| self.folder_holder.remembered_index(&previous_directory));
+        self.raw_highlight_index = highlight_index.try_into().unwrap_or(0);
+    }
+
+    /// Applies the directory entries read in the background
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>` telling whether the listing changed, which
+    /// may contain `AppError::Path` if path resolution fails
+    pub fn poll_loading(&mut self) -> AppResult<bool> {
+        let update = self.folder_holder.poll_loading().inspect_err(|_| {
+            self.selection_after_load = None;
+        })?;
+        if update == LoadUpdate::Finished {
+            if let Some((previous_directory, highlight_index)) = self.selection_after_load.take() {
+                self.restore_selection(previous_directory, highlight_index);
+            }
+        }
+        Ok(update != LoadUpdate::Unchanged)
+    }
+
+    /// Stops reading directories in the background
+    ///
+    /// # Returns
+    ///
+    /// Returns whether a read was in progress
+    pub fn cancel_loading(&mut self) -> bool {
+        self.selection_after_load = None;
+        self.folder_holder.cancel_loading()
+    }
+
+    /// Opens the directory history search
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If history search cannot be opened from the current mode
+    /// - `AppError::Path`: If path resolution fails
+    pub fn open_history_search(&mut self) -> AppResult<()> {
+        self.remember_selection();
+        self.state_holder.borrow_mut().to_history_search()?;
+        self.reset()
+    }
+
+    /// Returns the paths to watch for changes
+    ///
+    /// These are the listed directories and the opened file.
+    pub fn watched_paths(&self) -> HashSet<PathBuf> {
+        let mut paths = self.folder_holder.listed_directories();
+        paths.extend(self.file_opened.clone());
+        paths
+    }
+
+    /// Updates the view after files changed on disk
+    ///
+    /// Cached listings of the affected directories are invalidated. The
+    /// current listing is read again, keeping the selected entry, and the
+    /// opened file is reloaded, keeping the scroll position, unless it is
+    /// followed already.
+    ///
+    /// # Arguments
+    ///
+    /// * `changed` - Paths reported as changed
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Parse`: If a directory cannot be read
+    /// - `AppError::Io`: If the opened file cannot be read
+    pub fn apply_file_changes(&mut self, changed: &HashSet<PathBuf>) -> AppResult<()> {
+        let directories: HashSet<PathBuf> = changed
+            .iter()
+            .flat_map(|path| [Some(path.clone()), path.parent().map(Path::to_path_buf)])
+            .flatten()
+            .collect();
+        self.folder_holder.invalidate(&directories);
+
+        let is_listed = !self
+            .folder_holder
+            .listed_directories()
+            .is_disjoint(&directories);
+        if is_listed && !self.state_holder.borrow().is_history_search() {
+            self.remember_selection();
+            self.folder_holder.refresh()?;
+            let current_directory = self.folder_holder.current_directory.clone();
+            self.restore_selection(current_directory, None);
+        }
+
+        if let Some(file_path) = self.file_opened.clone() {
+            if changed.contains(&file_path) && self.follow_holder.is_none() {
+                self.reload_file(&file_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the opened file again, keeping the scroll position
+    ///
+    /// A followed file is followed again from its current end.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path of the opened file
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Parse`: If highlighting fails
+    fn reload_file(&mut self, file_path: &Path) -> AppResult<()> {
+        let content = match &self.follow_holder {
+            Some(follow_holder) => {
+                let is_paused = follow_holder.is_paused;
+                let (mut follow_holder, content) = FollowHolder::new(file_path)?;
+                follow_holder.is_paused = is_paused;
+                self.follow_holder = Some(follow_holder);
+                content
+            }
+            None => FileTextInfo::read_content(file_path)?,
+        };
+        let file_text_info = self.format_content(&content, file_path)?;
+        self.replace_file_text_info(file_path, file_text_info);
+        Ok(())
+    }
+
+    /// Formats the content of the opened file, as a log if the log view is on
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<FileTextInfo>` which may contain `AppError::Parse`
+    /// if highlighting fails
+    fn format_content(&mut self, content: &str, file_path: &Path) -> AppResult<FileTextInfo> {
+        match &mut self.log_holder {
+            Some(log_holder) => {
+                log_holder.load(content);
+                Ok(log_holder.to_text_info())
+            }
+            None => FileTextInfo::from_content(content, file_path, &self.code_highlighter),
+        }
+    }
+
+    /// Shows new content of the opened file, keeping the scroll position
+    ///
+    /// The previous git information is shown until read again.
+    fn replace_file_text_info(&mut self, file_path: &Path, file_text_info: FileTextInfo) {
+        self.load_file_git_info(file_path, file_text_info.n_rows);
+        self.vertical_scroll = self.vertical_scroll.min(file_text_info.n_rows);
+        self.horizontal_scroll = self.horizontal_scroll.min(file_text_info.max_line_length);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.horizontal_scroll_state = self
+            .horizontal_scroll_state
+            .position(self.horizontal_scroll);
+        self.file_text_info = Some(file_text_info);
+    }
+
+    /// Starts, resumes or stops following the opened file
+    ///
+    /// Starting reads the file again, after that only appended lines are
+    /// read and highlighted. A paused follow resumes without reading.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If no file is opened
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Parse`: If highlighting fails
+    pub fn toggle_follow(&mut self) -> AppResult<()> {
+        match &mut self.follow_holder {
+            Some(follow_holder) if follow_holder.is_paused => follow_holder.is_paused = false,
+            Some(_) => self.follow_holder = None,
+            None => {
+                let file_path = self
+                    .file_opened
+                    .clone()
+                    .ok_or(AppError::State("No file opened".into()))?;
+                let (follow_holder, content) = FollowHolder::new(&file_path)?;
+                let file_text_info = self.format_content(&content, &file_path)?;
+                self.replace_file_text_info(&file_path, file_text_info);
+                self.follow_holder = Some(follow_holder);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops keeping the view at the end of the followed file
+    ///
+    /// New lines are still read, following resumes with [`MessageHolder::toggle_follow`].
+    pub fn pause_follow(&mut self) {
+        if let Some(follow_holder) = &mut self.follow_holder {
+            follow_holder.is_paused = true;
+        }
+    }
+
+    /// Checks if the view is kept at the end of the followed file
+    pub fn is_following(&self) -> bool {
+        self.follow_holder
+            .as_ref()
+            .is_some_and(|follow_holder| !follow_holder.is_paused)
+    }
+
+    /// Reads lines appended to the followed file
+    ///
+    /// A truncated or rotated file is read again from the start. Following
+    /// stops on the first read error, so that it is reported once.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<bool>`, true if the content changed, which may contain:
+    /// - `AppError::State`: If the file cannot be read anymore
+    /// - `AppError::Parse`: If highlighting fails
+    pub fn poll_follow(&mut self) -> AppResult<bool> {
+        let Some(follow_holder) = &mut self.follow_holder else {
+            return Ok(false);
+        };
+        let file_path = follow_holder.file_path.clone();
+        let update = match follow_holder.read_new() {
+            Ok(update) => update,
+            Err(error) => {
+                self.follow_holder = None;
+                return Err(AppError::State(format!("Follow stopped: {}", error)));
+            }
+        };
+        match update {
+            FollowUpdate::Unchanged => return Ok(false),
+            FollowUpdate::Appended {
+                text,
+                replaces_partial_line,
+            } => {
+                let file_text_info = self
+                    .file_text_info
+                    .as_mut()
+                    .ok_or(AppError::Parse("Unexpected, file should be opened".into()))?;
+                match &mut self.log_holder {
+                    Some(log_holder) => log_holder.append_to(&text, file_text_info),
+                    None => file_text_info.append(
+                        &text,
+                        replaces_partial_line,
+                        &file_path,
+                        &self.code_highlighter,
+                    )?,
+                }
+                let n_rows = file_text_info.n_rows;
+                self.vertical_scroll = self.vertical_scroll.min(file_text_info.n_rows);
+                self.horizontal_scroll = self.horizontal_scroll.min(file_text_info.max_line_length);
+                self.vertical_scroll_state =
+                    self.vertical_scroll_state.position(self.vertical_scroll);
+                self.horizontal_scroll_state = self
+                    .horizontal_scroll_state
+                    .position(self.horizontal_scroll);
+                // a read in progress is redone once done, see `poll_file_git_info`
+                if self.file_git_info.is_some() && self.file_git_loading.is_none() {
+                    self.load_file_git_info(&file_path, n_rows);
+                }
+            }
+            FollowUpdate::Reopened(content) => {
+                let file_text_info = self.format_content(&content, &file_path)?;
+                self.replace_file_text_info(&file_path, file_text_info);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Toggles the log view of the opened file
+    ///
+    /// Files with a `.log` or `.jsonl` extension open in the log view.
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If no file is opened
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Parse`: If highlighting fails
+    pub fn toggle_log_view(&mut self) -> AppResult<()> {
+        let file_path = self
+            .file_opened
+            .clone()
+            .ok_or(AppError::State("No file opened".into()))?;
+        self.log_holder = match self.log_holder {
+            Some(_) => None,
+            None => Some(LogHolder::new()),
+        };
+        self.reload_file(&file_path)
+    }
+
+    /// Cycles the minimum level shown in the log view
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// log view is off
+    pub fn cycle_log_level(&mut self) -> AppResult<()> {
+        let log_holder = self.log_holder_mut()?;
+        log_holder.min_level = LogLevel::next_min_level(log_holder.min_level);
+        self.refresh_log_view()
+    }
+
+    /// Sets the time range shown in the log view
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Range written as `FROM..TO`, empty to show all lines
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the log view is off
+    /// - `AppError::Parse`: If the range is invalid
+    pub fn set_log_time_range(&mut self, input: &str) -> AppResult<()> {
+        let time_range = TimeRange::parse(input)?;
+        self.log_holder_mut()?.time_range = time_range;
+        self.refresh_log_view()
+    }
+
+    fn log_holder_mut(&mut self) -> AppResult<&mut LogHolder> {
+        self.log_holder.as_mut().ok_or(AppError::State(
+            "Log view is off, toggle it with <Shift+L>".into(),
+        ))
+    }
+
+    /// Shows the log lines matching the current filters
+    fn refresh_log_view(&mut self) -> AppResult<()> {
+        let file_text_info = self.log_holder_mut()?.to_text_info();
+        self.vertical_scroll = self.vertical_scroll.min(file_text_info.n_rows);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.file_text_info = Some(file_text_info);
+        Ok(())
+    }
+
+    /// Resets the message holder to initial state
+    ///
+    /// Clears input, resets file view, and resets selection index
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain directory update errors
+    pub fn reset(&mut self) -> AppResult<()> {
+        self.folder_holder.input.clear();
+        self.folder_holder.update(None)?;
+        self.reset_file_view();
+        self.reset_index();
+        Ok(())
+    }
+
+    /// Resets the file view state
+    ///
+    /// Clears the currently opened file and its text info
+    ///
+    /// The scroll position is remembered for when the file is reopened
+    pub fn reset_file_view(&mut self) {
+        if let Some(file_path) = self.file_opened.take() {
+            self.file_scroll_holder
+                .put(file_path, (self.vertical_scroll, self.horizontal_scroll));
+        }
+        self.file_text_info = None;
+        self.file_git_info = None;
+        self.follow_holder = None;
+        self.log_holder = None;
+        self.file_git_loading = None;
+        self.show_blame = false;
+        self.vertical_scroll = 0;
+        self.horizontal_scroll = 0;
+    }
+
+    /// Starts reading the line changes of the opened file in the background
     ///
-    /// Uses saturating addition to prevent overflow
-    pub fn move_down(&mut self) {
-        self.raw_highlight_index = self.raw_highlight_index.saturating_add(1);
-    }
-
-    /// Updates the filtered view based on search input
+    /// The blame is read too while it is shown.
     ///
     /// # Arguments
     ///
-    /// * `input` - Optional search filter string
-    ///
-    /// # Returns
-    ///
-    /// Returns `AppResult<()>` which may contain `AppError::State` if update fails
-    pub fn update(&mut self, input: Option<String>) -> AppResult<()> {
-        self.folder_holder.update(input)?;
-        self.reset_index();
-        Ok(())
+    /// * `file_path` - Path of the opened file
+    /// * `n_rows` - Number of lines of the opened file
+    fn load_file_git_info(&mut self, file_path: &Path, n_rows: usize) {
+        self.file_git_loading = Some(FileGitLoader::spawn(file_path, n_rows, self.show_blame));
     }
 
-    /// Expands all directories recursively
+    /// Follows the selected entry for its preview and stores the directory
+    /// previews read in the background in the directory cache
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain:
-    /// - `AppError::Io`: If directory reading fails
-    /// - `AppError::Path`: If path resolution fails
-    pub fn expand(&mut self) -> AppResult<()> {
-        self.folder_holder.expand()?;
-        Ok(())
+    /// Returns whether a preview became due or was read
+    pub fn poll_previews(&mut self) -> bool {
+        let selected = self.previewed_path();
+        let is_due = self.preview_holder.select(selected.as_deref());
+        let listings = self.preview_holder.poll_directories();
+        let is_read = !listings.is_empty();
+        for (path, entries) in listings {
+            self.folder_holder.store_listing(path, entries);
+        }
+        is_due || is_read
     }
 
-    /// Collapses expanded directories
-    ///
-    /// # Returns
-    ///
-    /// Returns `AppResult<()>` which may contain `AppError::State` on invalid state
-    pub fn collapse(&mut self) -> AppResult<()> {
-        self.folder_holder.collapse()?;
-        Ok(())
+    /// Returns the path of the entry shown in the preview pane, if drawn
+    fn previewed_path(&self) -> Option<PathBuf> {
+        if !self.shows_preview() {
+            return None;
+        }
+        let path_holder = &self.folder_holder.selected_path_holder;
+        let highlight_index = self.get_highlight_index(path_holder.len()).ok()?;
+        let entry = path_holder.get(highlight_index)?;
+        if entry.is_file {
+            Some(entry.to_path())
+        } else {
+            entry.to_path_canonicalize().ok()
+        }
     }
 
-    /// Navigates to the parent directory
+    /// Applies the git information of the opened file once read in the background
     ///
-    /// Resets the selection index and refreshes the view
+    /// The blame column is hidden if the blame could not be read. The
+    /// information is read again if lines were appended meanwhile.
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain directory navigation errors
-    pub fn to_parent(&mut self) -> AppResult<()> {
-        self.raw_highlight_index = 0;
-        self.update(Some("".into()))?;
-        self.submit()?;
-        Ok(())
+    /// Returns whether the information changed
+    pub fn poll_file_git_info(&mut self) -> bool {
+        let Some(file_git_loading) = &self.file_git_loading else {
+            return false;
+        };
+        let Some(file_git_info) = file_git_loading.poll() else {
+            return false;
+        };
+        self.show_blame = self.show_blame
+            && file_git_info
+                .as_ref()
+                .is_some_and(|file_git_info| file_git_info.blame.is_some());
+        self.file_git_loading = None;
+        if let (Some(file_git_info), Some(file_path), Some(file_text_info)) =
+            (&file_git_info, &self.file_opened, &self.file_text_info)
+        {
+            if file_git_info.line_changes.len() != file_text_info.n_rows {
+                self.load_file_git_info(&file_path.clone(), file_text_info.n_rows);
+            }
+        }
+        self.file_git_info = file_git_info;
+        true
+    }
+
+    /// Checks if the git information of the opened file is being read
+    pub fn is_file_git_loading(&self) -> bool {
+        self.file_git_loading.is_some()
     }
 
-    /// Deletes the currently selected file or directory
+    /// Toggles the blame column of the opened file
+    ///
+    /// Blame is read in the background from the local repository the first
+    /// time it is shown, the column shows it is loading meanwhile.
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain:
-    /// - `AppError::Path`: If path resolution fails
-    /// - `AppError::State`: If selection is invalid
-    pub fn delete(&mut self) -> AppResult<()> {
-        let path_holder = &self.folder_holder.selected_path_holder;
-        if path_holder.is_empty() {
+    /// Returns `AppResult<()>` which may contain `AppError::State` if no
+    /// file is opened or it is not tracked by git
+    pub fn toggle_blame(&mut self) -> AppResult<()> {
+        if self.show_blame {
+            self.show_blame = false;
             return Ok(());
         }
-
-        let highlight_index = self.get_highlight_index(path_holder.len())?;
-        if let Ok(path) = self.folder_holder.submit(highlight_index) {
-            if path.is_dir() {
-                let _ = fs::remove_dir_all(path);
-            } else {
-                let _ = fs::remove_file(path);
-            }
-            self.folder_holder.refresh()?;
+        let (Some(file_path), Some(file_text_info)) = (&self.file_opened, &self.file_text_info)
+        else {
+            return Err(AppError::State("No file opened".into()));
+        };
+        if self.file_git_info.is_none() && self.file_git_loading.is_none() {
+            return Err(AppError::State(
+                "File is not inside a git repository".into(),
+            ));
+        }
+        let is_blame_loaded = self
+            .file_git_info
+            .as_ref()
+            .is_some_and(|file_git_info| file_git_info.blame.is_some());
+        self.show_blame = true;
+        if !is_blame_loaded {
+            let (file_path, n_rows) = (file_path.clone(), file_text_info.n_rows);
+            self.load_file_git_info(&file_path, n_rows);
         }
         Ok(())
     }
 
-    /// Refreshes the current folder's cache
+    /// Opens the git history browser for the opened file
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain `AppError::Cache` or `AppError::Io`
-    pub fn refresh_current_folder_cache(&mut self) -> AppResult<()> {
-        self.folder_holder.refresh()?;
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If no file is opened or it has no git history
+    /// - `AppError::Parse`: If git log fails
+    pub fn open_file_history(&mut self) -> AppResult<()> {
+        let file_path = self
+            .file_opened
+            .as_ref()
+            .ok_or(AppError::State("No file opened".into()))?;
+        self.file_history_holder = Some(FileHistoryHolder::new(file_path)?);
+        self.state_holder.borrow_mut().to_file_history()?;
         Ok(())
     }
 
-    /// Resets the message holder to initial state
+    /// Closes the git history browser and returns to the live file
     ///
-    /// Clears input, resets file view, and resets selection index
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// history browser is not opened
+    pub fn close_file_history(&mut self) -> AppResult<()> {
+        self.file_history_holder = None;
+        self.state_holder.borrow_mut().pop_mode()
+    }
+
+    /// Shows the selected commit of the git history browser
     ///
     /// # Returns
     ///
-    /// Returns `AppResult<()>` which may contain directory update errors
-    pub fn reset(&mut self) -> AppResult<()> {
-        self.folder_holder.input.clear();
-        self.folder_holder.update(None)?;
-        self.reset_file_view();
-        self.reset_index();
-        Ok(())
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the history browser is not opened
+    /// - `AppError::Parse`: If git show or highlighting fails
+    pub fn open_file_revision(&mut self) -> AppResult<()> {
+        self.file_history_holder
+            .as_mut()
+            .ok_or(AppError::State("File history is not opened".into()))?
+            .open_revision(&self.code_highlighter)
     }
 
-    /// Resets the file view state
+    /// Switches the git history browser between file content and diff
     ///
-    /// Clears the currently opened file and its text info
-    pub fn reset_file_view(&mut self) {
-        self.file_opened = None;
-        self.file_text_info = None;
-        self.vertical_scroll = 0;
-        self.horizontal_scroll = 0;
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::State`: If the history browser is not opened
+    /// - `AppError::Parse`: If git show or highlighting fails
+    pub fn toggle_file_revision_diff(&mut self) -> AppResult<()> {
+        self.file_history_holder
+            .as_mut()
+            .ok_or(AppError::State("File history is not opened".into()))?
+            .toggle_diff(&self.code_highlighter)
     }
 
     /// Converts raw highlight index to wrapped index within bounds
@@ -278,18 +2382,17 @@ impl MessageHolder {
         let new_entrypoint_canonicalized_result = self.folder_holder.submit(highlight_index);
         match new_entrypoint_canonicalized_result {
             Ok(new_entrypoint) => {
-                if new_entrypoint.is_dir() {
-                    if self.state_holder.borrow().is_history_search() {
-                        self.state_holder.borrow_mut().to_search();
+                self.remember_selection();
+                self.navigate(|holder| {
+                    if new_entrypoint.is_dir() {
+                        if holder.state_holder.borrow().is_history_search() {
+                            holder.state_holder.borrow_mut().to_search()?;
+                        }
+                        holder.enter_directory(new_entrypoint)
+                    } else {
+                        holder.open_file(new_entrypoint)
                     }
-                    self.folder_holder
-                        .submit_new_working_directory(new_entrypoint)?;
-                } else {
-                    self.file_text_info =
-                        Some(FileTextInfo::new(&new_entrypoint, &self.code_highlighter)?);
-                    self.file_opened = Some(new_entrypoint);
-                    self.state_holder.borrow_mut().to_file_view();
-                }
+                })?;
             }
             Err(_) => {
                 if self.state_holder.borrow().is_history_search() {
@@ -303,6 +2406,46 @@ impl MessageHolder {
         Ok(())
     }
 
+    /// Opens a file in file view, at the scroll position it was last left at
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Canonicalized path of the file
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Parse`: If highlighting fails
+    fn open_file(&mut self, file_path: PathBuf) -> AppResult<()> {
+        let content = FileTextInfo::read_content(&file_path)?;
+        // a file opened from the search over the file view replaces its file
+        if self.close_nested_search()? {
+            self.reset_file_view();
+        }
+        self.log_holder = LogHolder::is_log_file(&file_path).then(LogHolder::new);
+        let file_text_info = self.format_content(&content, &file_path)?;
+        self.file_git_info = None;
+        self.load_file_git_info(&file_path, file_text_info.n_rows);
+        let (vertical_scroll, horizontal_scroll) = self
+            .file_scroll_holder
+            .get(&file_path)
+            .copied()
+            .unwrap_or_default();
+        self.vertical_scroll = vertical_scroll.min(file_text_info.n_rows);
+        self.horizontal_scroll = horizontal_scroll.min(file_text_info.max_line_length);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        self.horizontal_scroll_state = self
+            .horizontal_scroll_state
+            .position(self.horizontal_scroll);
+        self.file_text_info = Some(file_text_info);
+        self.file_opened = Some(file_path);
+        if !self.state_holder.borrow().is_file_view() {
+            self.state_holder.borrow_mut().to_file_view()?;
+        }
+        Ok(())
+    }
+
     /// Renders the current view to the terminal
     ///
     /// # Arguments
@@ -314,24 +2457,362 @@ impl MessageHolder {
     ///
     /// Returns `AppResult<()>` which may contain rendering errors
     pub fn draw(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
-        match self.file_opened.clone() {
-            None => self.draw_folder_view(area, frame),
-            Some(file_path) => self.draw_file_view(area, frame, &file_path),
+        if self.state_holder.borrow().is_file_history() {
+            return self.draw_file_history_view(area, frame);
+        }
+        if self.state_holder.borrow().is_bookmark_view() {
+            return self.draw_bookmark_view(area, frame);
+        }
+        if self.state_holder.borrow().is_command_view() {
+            return self.draw_command_view(area, frame);
+        }
+        match self.shown_file() {
+            None if self.show_columns && !self.state_holder.borrow().is_history_search() => {
+                self.draw_columns_view(area, frame)?
+            }
+            None if self.show_quick_look && self.state_holder.borrow().is_search_edit() => {
+                self.draw_quick_look_view(area, frame)?
+            }
+            None => self.draw_folder_view(area, frame)?,
+            Some(file_path) => self.draw_file_view(area, frame, &file_path)?,
+        }
+        if self.state_holder.borrow().is_confirm_view() {
+            self.draw_confirm_popup(area, frame);
+        }
+        Ok(())
+    }
+
+    /// Draws the popup asking to confirm the pending operation over the view
+    fn draw_confirm_popup(&self, area: Rect, frame: &mut Frame) {
+        let (title, mut text, answers) = match &self.pending_confirm {
+            None => return,
+            Some(Confirmation::Delete(pending_delete)) => {
+                let mut text = match pending_delete.paths.as_slice() {
+                    [path] => {
+                        let kind = if pending_delete.n_dirs > 0 {
+                            "directory"
+                        } else {
+                            "file"
+                        };
+                        vec![Line::from(format!("Delete {} {}?", kind, path.display()))]
+                    }
+                    paths => {
+                        let mut text = vec![Line::from(format!(
+                            "Delete {} items, {}?",
+                            paths.len(),
+                            Self::count_of(pending_delete.n_dirs, "directory", "directories")
+                        ))];
+                        text.extend(Self::listed_names(paths.iter()));
+                        text
+                    }
+                };
+                text.push(Line::from(match &pending_delete.tree_size {
+                    Some(tree_size) => tree_size.to_string(),
+                    None => "Measuring...".into(),
+                }));
+                let answers = vec![
+                    " Trash ".into(),
+                    "<Y/Enter>".light_blue().bold(),
+                    " Delete permanently ".into(),
+                    "<Shift+D>".light_blue().bold(),
+                ];
+                ("Confirm delete", text, answers)
+            }
+            Some(Confirmation::Conflict(pending_paste)) => {
+                let conflicts = &pending_paste.conflicts;
+                let mut text = vec![Line::from(format!(
+                    "{} items exist already in {}:",
+                    conflicts.len(),
+                    self.folder_holder.current_directory.display()
+                ))];
+                text.extend(Self::listed_names(conflicts.iter().map(|job| &job.to)));
+                let answers = vec![
+                    " Overwrite ".into(),
+                    "<O>".light_blue().bold(),
+                    " Skip ".into(),
+                    "<S>".light_blue().bold(),
+                    " Keep both ".into(),
+                    "<K>".light_blue().bold(),
+                ];
+                ("Paste conflict", text, answers)
+            }
+            Some(Confirmation::Rename(plan)) => {
+                let mut text = vec![Line::from(format!("Rename {} items?", plan.renames.len()))];
+                let directory = &self.folder_holder.current_directory;
+                let relative = |path: &Path| {
+                    path.strip_prefix(directory)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string()
+                };
+                text.extend(plan.renames.iter().take(MAX_LISTED_ITEMS).map(|job| {
+                    Line::from(format!(
+                        "  {} -> {}",
+                        relative(&job.from),
+                        relative(&job.to)
+                    ))
+                }));
+                if plan.renames.len() > MAX_LISTED_ITEMS {
+                    text.push(Line::from(format!(
+                        "  and {} more",
+                        plan.renames.len() - MAX_LISTED_ITEMS
+                    )));
+                }
+                if plan.n_cycles > 0 {
+                    text.push(Line::from(format!(
+                        "{} cycles go through a temporary name",
+                        plan.n_cycles
+                    )));
+                }
+                let answers = vec![" Rename ".into(), "<Y/Enter>".light_blue().bold()];
+                ("Confirm rename", text, answers)
+            }
+        };
+        text.push(Line::default());
+        text.push(Line::from(
+            [
+                answers,
+                vec![" Cancel ".into(), "<N/Esc>".light_blue().bold()],
+            ]
+            .concat(),
+        ));
+        let [popup_area] = Layout::vertical([Constraint::Length(text.len() as u16 + 4)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup_area] = Layout::horizontal([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(popup_area);
+        let popup = Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(title));
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    /// Returns the opened file if it is shown, not hidden by a search opened over it
+    fn shown_file(&self) -> Option<PathBuf> {
+        self.file_opened
+            .clone()
+            .filter(|_| self.state_holder.borrow().is_over_file_view())
+    }
+
+    /// Checks if the preview pane is drawn, in columns or quick look view
+    fn shows_preview(&self) -> bool {
+        let state_holder = self.state_holder.borrow();
+        self.shown_file().is_none()
+            && !state_holder.is_file_history()
+            && !state_holder.is_bookmark_view()
+            && !state_holder.is_command_view()
+            && ((self.show_columns && !state_holder.is_history_search())
+                || (self.show_quick_look && state_holder.is_search_edit()))
+    }
+
+    /// Names the deleted items, by its path for a single one
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Items to delete
+    /// * `n_items` - Number of items to name when there are several
+    fn deleted_items(paths: &[PathBuf], n_items: usize) -> String {
+        match paths {
+            [path] => path.display().to_string(),
+            _ => Self::count_of(n_items, "item", "items"),
+        }
+    }
+
+    /// Formats a number with the singular or plural of what is counted
+    fn count_of(n: usize, singular: &str, plural: &str) -> String {
+        format!("{} {}", n, if n == 1 { singular } else { plural })
+    }
+
+    /// Lists the first names of items in the confirmation popup
+    fn listed_names<'a>(paths: impl ExactSizeIterator<Item = &'a PathBuf>) -> Vec<Line<'static>> {
+        let n_paths = paths.len();
+        let mut text: Vec<Line> = paths
+            .take(MAX_LISTED_ITEMS)
+            .map(|path| {
+                Line::from(format!(
+                    "  {}",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ))
+            })
+            .collect();
+        if n_paths > MAX_LISTED_ITEMS {
+            text.push(Line::from(format!(
+                "  and {} more",
+                n_paths - MAX_LISTED_ITEMS
+            )));
+        }
+        text
+    }
+
+    fn draw_quick_look_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let [listing_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .spacing(1)
+                .areas(area);
+        self.draw_folder_view(listing_area, frame)?;
+        self.draw_preview_pane(preview_area, frame);
+        Ok(())
+    }
+
+    fn draw_columns_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let [parent_area, listing_area, preview_area] = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Percentage(40),
+            Constraint::Percentage(40),
+        ])
+        .spacing(1)
+        .areas(area);
+        self.draw_parent_pane(parent_area, frame);
+        self.draw_folder_view(listing_area, frame)?;
+        self.draw_preview_pane(preview_area, frame);
+        Ok(())
+    }
+
+    /// Draws the listing of the parent directory, with the current directory selected
+    fn draw_parent_pane(&mut self, area: Rect, frame: &mut Frame) {
+        let current_directory = self.folder_holder.current_directory.clone();
+        let Some(parent) = current_directory.parent() else {
+            return;
+        };
+        let block = Block::default().title(parent.display().to_string());
+        self.draw_directory_preview(parent, Some(&current_directory), block, area, frame);
+    }
+
+    /// Draws the content of the selected file, or the entries of the selected directory
+    fn draw_preview_pane(&mut self, area: Rect, frame: &mut Frame) {
+        let path_holder = &self.folder_holder.selected_path_holder;
+        let Ok(highlight_index) = self.get_highlight_index(path_holder.len()) else {
+            return;
+        };
+        let Some(entry) = path_holder.get(highlight_index).cloned() else {
+            return;
+        };
+        let block = Block::default().title(entry.file_name.clone());
+        let paragraph = if entry.is_file {
+            if !self.preview_holder.is_due(&entry.to_path()) {
+                frame.render_widget(block, area);
+                return;
+            }
+            match self.preview_holder.preview(
+                &entry.to_path(),
+                area.height.into(),
+                &self.code_highlighter,
+            ) {
+                Ok(preview) => Paragraph::new(preview.lines.clone()),
+                Err(error) => Paragraph::new(error.to_string()),
+            }
+        } else {
+            match entry.to_path_canonicalize() {
+                Ok(path)
+                    if self.folder_holder.listing(&path).is_none()
+                        && !self.preview_holder.is_due(&path) =>
+                {
+                    frame.render_widget(block, area);
+                    return;
+                }
+                Ok(path) => {
+                    self.draw_directory_preview(&path, None, block, area, frame);
+                    return;
+                }
+                Err(error) => Paragraph::new(error.to_string()),
+            }
+        };
+        frame.render_widget(paragraph.block(block), area);
+    }
+
+    /// Draws the entries of a directory, read in the background if not cached
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory to list
+    /// * `selected` - Entry to select (if any)
+    /// * `block` - Block around the listing
+    /// * `area` - The rectangular area to render in
+    /// * `frame` - The ratatui frame to render to
+    fn draw_directory_preview(
+        &mut self,
+        path: &Path,
+        selected: Option<&Path>,
+        block: Block,
+        area: Rect,
+        frame: &mut Frame,
+    ) {
+        if let Some(entries) = self.folder_holder.listing(path) {
+            let selected = selected
+                .and_then(|selected| entries.iter().position(|entry| entry.to_path() == selected));
+            let list = List::new(Self::listing_items(&entries))
+                .block(block)
+                .highlight_style(Modifier::REVERSED);
+            let mut list_state = ListState::default().with_selected(selected);
+            frame.render_stateful_widget(list, area, &mut list_state);
+            return;
         }
+        let paragraph = match self.preview_holder.read_directory(path) {
+            Ok(()) => Paragraph::new(Line::from("Loading...").italic()),
+            Err(error) => Paragraph::new(error.to_string()),
+        };
+        frame.render_widget(paragraph.block(block), area);
+    }
+
+    fn listing_items(entries: &[&FileHolder]) -> Vec<ListItem<'static>> {
+        entries
+            .iter()
+            .map(|entry| {
+                let style = if entry.is_file {
+                    Style::default()
+                } else {
+                    Color::LightCyan.into()
+                };
+                ListItem::new(Line::from(entry.file_name.clone()).style(style))
+            })
+            .collect()
     }
 
     fn draw_folder_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let is_history_search = self.state_holder.borrow().is_history_search();
+        let git_statuses = if is_history_search {
+            Vec::new()
+        } else {
+            self.folder_holder.git_statuses()
+        };
+        let git_status = if is_history_search {
+            None
+        } else {
+            self.folder_holder.git_status.as_ref()
+        };
         let mut path_holder: Vec<ListItem> = self
             .folder_holder
             .selected_path_holder
             .iter()
-            .filter_map(|entry| {
-                self.get_text(entry).ok().map(|text| {
-                    ListItem::new(Line::from(text).style(if entry.is_file {
+            .zip(
+                self.folder_holder
+                    .tree_guides
+                    .iter()
+                    .map(String::as_str)
+                    .chain(iter::repeat("")),
+            )
+            .enumerate()
+            .filter_map(|(index, (entry, guide))| {
+                self.get_text(entry, guide).ok().map(|text| {
+                    let style = if self.folder_holder.is_selected(index) {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else if entry.is_file {
                         Style::default()
                     } else {
                         Color::LightCyan.into()
-                    }))
+                    };
+                    let line = match git_status {
+                        Some(_) => Line::from(vec![
+                            Self::get_git_marker(git_statuses.get(index).copied().flatten()),
+                            text.into(),
+                        ]),
+                        None => Line::from(text),
+                    };
+                    ListItem::new(line.style(style))
                 })
             })
             .collect();
@@ -347,15 +2828,36 @@ impl MessageHolder {
         let block = if self.state_holder.borrow().is_history_search() {
             Block::default().title(format!("History: {} items", path_holder.len()))
         } else {
-            Block::default()
-                .title(self.folder_holder.current_directory.display().to_string())
-                .title_bottom(
-                    self.folder_holder
-                        .peek()?
-                        .update_time
-                        .format("%Y-%m-%d %H:%M:%S")
-                        .to_string(),
-                )
+            let group_holder = self.folder_holder.peek()?;
+            let mut title = self.folder_holder.current_directory.display().to_string();
+            if let Some(branch) = git_status.and_then(|status| status.branch.as_ref()) {
+                title = format!("{} [{}]", title, branch);
+            }
+            if let Some(loading_status) = self.folder_holder.loading_status() {
+                title = format!("{} ({})", title, loading_status);
+            }
+            if let Some(delete_holder) = &self.delete_holder {
+                title = format!("{} ({})", title, delete_holder.status());
+            }
+            if !self.folder_holder.multi_selection.is_empty() {
+                title = format!(
+                    "{} ({} selected)",
+                    title,
+                    self.folder_holder.multi_selection.len()
+                );
+            }
+            if let Some(transfer_holder) = &self.transfer_holder {
+                title = format!("{} ({})", title, transfer_holder.status());
+            }
+            let mut bottom_title = format!(
+                "{} sort: {}",
+                group_holder.update_time.format("%Y-%m-%d %H:%M:%S"),
+                group_holder.sort_mode
+            );
+            if let Some(load_notice) = self.folder_holder.load_notice() {
+                bottom_title = format!("{} ({})", bottom_title, load_notice);
+            }
+            Block::default().title(title).title_bottom(bottom_title)
         };
 
         let messages = List::new(path_holder).block(block);
@@ -363,12 +2865,188 @@ impl MessageHolder {
         Ok(())
     }
 
-    fn get_text(&self, entry: &FileHolder) -> AppResult<String> {
+    fn get_git_marker(status: Option<GitStatus>) -> Span<'static> {
+        match status {
+            Some(status) => Span::styled(format!("{} ", status.marker()), status.color()),
+            None => "  ".into(),
+        }
+    }
+
+    fn get_text(&self, entry: &FileHolder, guide: &str) -> AppResult<String> {
         if self.state_holder.borrow().is_history_search() {
             Ok(entry.to_path_canonicalize()?.to_string_lossy().into_owned())
-        } else {
+        } else if guide.is_empty() {
             entry.relative_to(&self.folder_holder.current_directory)
+        } else {
+            Ok(format!("{}{}", guide, entry.file_name))
+        }
+    }
+
+    fn draw_file_history_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let file_history_holder = self
+            .file_history_holder
+            .as_ref()
+            .ok_or(AppError::State("File history is not opened".into()))?;
+
+        if let Some(revision) = &file_history_holder.revision {
+            let commit = file_history_holder.selected()?;
+            let title = format!(
+                "{} @ {}{}",
+                commit.path,
+                commit.hash,
+                if file_history_holder.show_diff {
+                    " (diff)"
+                } else {
+                    ""
+                }
+            );
+            let revision_preview = Paragraph::new(revision.formatted_text.clone())
+                .block(Block::default().title(title))
+                .scroll((
+                    file_history_holder.vertical_scroll as u16,
+                    file_history_holder.horizontal_scroll as u16,
+                ));
+            frame.render_widget(revision_preview, area);
+            return Ok(());
         }
+
+        let highlight_index = file_history_holder.highlight_index();
+        let commits: Vec<ListItem> = file_history_holder
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(index, commit)| {
+                let item = ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", commit.hash), Color::Yellow),
+                    Span::styled(format!("{} ", commit.date), Color::DarkGray),
+                    Span::styled(format!("{} ", commit.author), Color::LightCyan),
+                    commit.subject.clone().into(),
+                ]));
+                if index == highlight_index {
+                    item.add_modifier(Modifier::REVERSED)
+                } else {
+                    item
+                }
+            })
+            .collect();
+
+        let block = Block::default().title(format!(
+            "History of {}: {} commits",
+            file_history_holder.file_path.display(),
+            commits.len()
+        ));
+        let mut list_state = ListState::default().with_selected(Some(highlight_index));
+        frame.render_stateful_widget(List::new(commits).block(block), area, &mut list_state);
+        Ok(())
+    }
+
+    fn draw_command_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let command_holder = self
+            .command_holder
+            .as_ref()
+            .ok_or(AppError::State("No command is shown".into()))?;
+        let lines: Vec<Line> = command_holder
+            .lines
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        let output = Paragraph::new(lines)
+            .block(Block::default().title(command_holder.title()))
+            .scroll((
+                command_holder.vertical_scroll as u16,
+                command_holder.horizontal_scroll as u16,
+            ));
+        frame.render_widget(output, area);
+
+        let mut horizontal_scroll_state = ScrollbarState::new(command_holder.max_line_length)
+            .position(command_holder.horizontal_scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::HorizontalBottom).symbols(scrollbar::HORIZONTAL),
+            area.inner(Margin {
+                vertical: 0,
+                horizontal: 1,
+            }),
+            &mut horizontal_scroll_state,
+        );
+        Ok(())
+    }
+
+    fn draw_bookmark_view(&mut self, area: Rect, frame: &mut Frame) -> AppResult<()> {
+        let bookmark_holder = &self.bookmark_holder;
+        let highlight_index = bookmark_holder.highlight_index();
+        let marks: Vec<ListItem> = bookmark_holder
+            .selected_mark_holder
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                bookmark_holder.describe(*name).ok().map(|text| {
+                    let item = ListItem::new(text);
+                    if index == highlight_index {
+                        item.add_modifier(Modifier::REVERSED)
+                    } else {
+                        item
+                    }
+                })
+            })
+            .collect();
+
+        let block = Block::default().title(format!("Bookmarks: {} marks", marks.len()));
+        let mut list_state = ListState::default().with_selected(Some(highlight_index));
+        frame.render_stateful_widget(List::new(marks).block(block), area, &mut list_state);
+        Ok(())
+    }
+
+    /// Builds the gutter lines shown left of the file content
+    ///
+    /// Each line holds the optional blame column and the change marker,
+    /// aligned with `FileTextInfo::formatted_text`.
+    fn get_gutter(
+        file_git_info: &FileGitInfo,
+        show_blame: bool,
+        n_rows: usize,
+    ) -> Vec<Line<'static>> {
+        let blame = file_git_info.blame.as_ref().filter(|_| show_blame);
+        let is_blame_loading = show_blame && blame.is_none();
+        let author_width = blame
+            .map(|blame| {
+                blame
+                    .iter()
+                    .map(|b| b.author.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+            .min(MAX_BLAME_AUTHOR_WIDTH);
+
+        (0..n_rows)
+            .map(|index| {
+                let mut spans = Vec::new();
+                if let Some(blame) = blame {
+                    let text = match blame.get(index) {
+                        Some(line) => format!(
+                            "{} {:<width$.width$} {} ",
+                            line.commit,
+                            line.author,
+                            line.date,
+                            width = author_width
+                        ),
+                        None => " ".repeat(author_width + 20),
+                    };
+                    spans.push(Span::styled(text, Color::DarkGray));
+                } else if is_blame_loading {
+                    spans.push(Span::styled("loading... ", Color::DarkGray));
+                }
+                let marker = match file_git_info.line_changes.get(index).copied().flatten() {
+                    Some(change) => {
+                        let (marker, color) = change.marker();
+                        Span::styled(format!("{} ", marker), color)
+                    }
+                    None => "  ".into(),
+                };
+                spans.push(marker);
+                Line::from(spans)
+            })
+            .collect()
     }
 
     fn draw_file_view(&mut self, area: Rect, frame: &mut Frame, file_path: &Path) -> AppResult<()> {
@@ -376,8 +3054,46 @@ impl MessageHolder {
             .file_text_info
             .as_ref()
             .ok_or(AppError::Parse("Unexpected, file should be opened".into()))?;
+        let mut title = file_path.to_string_lossy().into_owned();
+        if let Some(log_holder) = &self.log_holder {
+            title.push_str(&format!(" [{}]", log_holder.describe()));
+        }
+        match &self.follow_holder {
+            Some(follow_holder) if follow_holder.is_paused => title.push_str(" [paused]"),
+            Some(_) => title.push_str(" [following]"),
+            None => (),
+        }
+        let block = Block::default().title(title);
+        let mut text_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        if self.is_following() {
+            self.vertical_scroll = file_text_info
+                .formatted_text
+                .len()
+                .saturating_sub(text_area.height as usize);
+            self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        }
+
+        // log lines are filtered, so the gutter would not line up
+        if let Some(file_git_info) = self
+            .file_git_info
+            .as_ref()
+            .filter(|_| self.log_holder.is_none())
+        {
+            let gutter = Self::get_gutter(file_git_info, self.show_blame, file_text_info.n_rows);
+            let gutter_width = gutter.iter().map(|line| line.width()).max().unwrap_or(0) as u16;
+            let [gutter_area, rest_area] =
+                Layout::horizontal([Constraint::Length(gutter_width), Constraint::Min(1)])
+                    .areas(text_area);
+            frame.render_widget(
+                Paragraph::new(gutter).scroll((self.vertical_scroll as u16, 0)),
+                gutter_area,
+            );
+            text_area = rest_area;
+        }
+
         let file_preview = Paragraph::new(file_text_info.formatted_text.clone())
-            .block(Block::default().title(file_path.to_string_lossy().into_owned()))
             .scroll((self.vertical_scroll as u16, self.horizontal_scroll as u16));
 
         self.vertical_scroll_state = self
@@ -387,7 +3103,7 @@ impl MessageHolder {
             .horizontal_scroll_state
             .content_length(file_text_info.max_line_length);
 
-        frame.render_widget(file_preview, area);
+        frame.render_widget(file_preview, text_area);
 
         frame.render_stateful_widget(
             Scrollbar::new(ScrollbarOrientation::HorizontalBottom).symbols(scrollbar::HORIZONTAL),
@@ -404,6 +3120,8 @@ impl MessageHolder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message_holder::git_helper::LineChange;
+
     #[test]
     fn test_get_highlight_index_helper_common() {
         let act = MessageHolder::get_highlight_index_helper(1, 10).unwrap();
@@ -428,4 +3146,18 @@ mod tests {
         let exp = 2;
         assert_eq!(act, exp);
     }
+
+    #[test]
+    fn test_get_gutter_while_blame_loads() {
+        let file_git_info = FileGitInfo {
+            line_changes: vec![Some(LineChange::Added), None],
+            blame: None,
+        };
+        let gutter = MessageHolder::get_gutter(&file_git_info, true, 2);
+        assert_eq!(gutter[0].to_string(), "loading... + ");
+        assert_eq!(gutter[1].to_string(), "loading...   ");
+
+        let gutter = MessageHolder::get_gutter(&file_git_info, false, 2);
+        assert_eq!(gutter[0].to_string(), "+ ");
+    }
 }
@@ -0,0 +1,389 @@
+use lru::LruCache;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs::{self, File};
+use std::io::Read;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::code_highlighter::CodeHighlighter;
+use crate::message_holder::file_helper::{FileHolder, MAX_FILE_SIZE};
+use crate::message_holder::load_holder::{open_directory, LoadHolder, LoadStatus};
+
+/// Number of file previews kept in memory
+pub const PREVIEW_CACHE_SIZE: usize = 64;
+
+/// Number of bytes read from the start of a file for its preview
+pub const PREVIEW_READ_SIZE: u64 = 64 * 1024;
+
+/// Time the selection must stay on a file before its preview is made
+pub const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Source of the current time for the preview debounce
+///
+/// Replaced in tests so the debounce can pass without waiting.
+pub trait Clock: Debug {
+    /// Returns the current time
+    fn now(&self) -> Instant;
+}
+
+/// Clock reading the system time
+#[derive(Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Selected entry waiting for its preview
+///
+/// # Fields
+///
+/// - `path`: Selected file or directory
+/// - `since`: When the entry was selected
+/// - `is_due`: Whether the selection stayed on it for `PREVIEW_DEBOUNCE`
+#[derive(Debug)]
+struct PendingPreview {
+    path: PathBuf,
+    since: Instant,
+    is_due: bool,
+}
+
+/// Highlighted first lines of a file
+///
+/// # Fields
+///
+/// - `lines`: Highlighted lines, at most the requested number
+/// - `n_rows`: Number of lines the preview was made for
+/// - `modified`: Modification time of the file when it was read
+#[derive(Debug, Clone)]
+pub struct FilePreview {
+    pub lines: Vec<Line<'static>>,
+    n_rows: usize,
+    modified: Option<SystemTime>,
+}
+
+/// Caches file previews and reads directory previews shown next to the listing
+///
+/// Previews are made on demand for the selected entry, once the selection
+/// stayed on it for `PREVIEW_DEBOUNCE`, so they load lazily as the selection moves.
+/// A preview is made again when the file changes or a taller one is needed.
+/// Binary and oversized files get a notice instead of their content.
+/// Directories are read in the background, their entries are then kept in
+/// the directory cache of [`FolderHolder`](crate::message_holder::folder_holder::FolderHolder).
+///
+/// # Fields
+///
+/// - `cache_holder`: LRU cache of previews by file path
+/// - `directory_loading`: Directories being read for their preview, by path
+/// - `pending`: Selected entry waiting for its preview
+/// - `clock`: Source of the current time for the debounce
+#[derive(Debug)]
+pub struct PreviewHolder {
+    cache_holder: LruCache<PathBuf, FilePreview>,
+    directory_loading: HashMap<PathBuf, LoadHolder>,
+    pending: Option<PendingPreview>,
+    clock: Box<dyn Clock>,
+}
+
+impl PreviewHolder {
+    /// Creates an empty preview cache
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Cache` if the
+    /// cache cannot be set up
+    pub fn new() -> AppResult<Self> {
+        let cache_size = NonZeroUsize::new(PREVIEW_CACHE_SIZE)
+            .ok_or(AppError::Cache("Unable to setup the preview cache!".into()))?;
+        Ok(Self {
+            cache_holder: LruCache::new(cache_size),
+            directory_loading: HashMap::new(),
+            pending: None,
+            clock: Box::new(SystemClock),
+        })
+    }
+
+    /// Replaces the clock used for the debounce
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Source of the current time
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Follows the entry whose preview is shown, on every tick
+    ///
+    /// The debounce starts again whenever the selection moves to another
+    /// entry, and nothing is waited for while no preview is shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - Selected file or directory, None if no preview is shown
+    ///
+    /// # Returns
+    ///
+    /// Returns whether the preview of the selected entry just became due
+    pub fn select(&mut self, selected: Option<&Path>) -> bool {
+        let Some(selected) = selected else {
+            self.pending = None;
+            return false;
+        };
+        let now = self.clock.now();
+        match &mut self.pending {
+            Some(pending) if pending.path == selected => {
+                if pending.is_due || now.duration_since(pending.since) < PREVIEW_DEBOUNCE {
+                    return false;
+                }
+                pending.is_due = true;
+                true
+            }
+            _ => {
+                self.pending = Some(PendingPreview {
+                    path: selected.to_path_buf(),
+                    since: now,
+                    is_due: false,
+                });
+                false
+            }
+        }
+    }
+
+    /// Checks whether the preview of a file or directory can be shown
+    ///
+    /// An entry without a cached preview is due once the selection stayed
+    /// on it for `PREVIEW_DEBOUNCE`, see [`PreviewHolder::select`].
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Selected file or directory
+    pub fn is_due(&self, file_path: &Path) -> bool {
+        self.cache_holder.contains(file_path)
+            || self
+                .pending
+                .as_ref()
+                .is_some_and(|pending| pending.is_due && pending.path == file_path)
+    }
+
+    /// Returns the preview of a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - File to preview
+    /// * `n_rows` - Number of lines to show
+    /// * `code_highlighter` - Syntax highlighter for formatting
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<&FilePreview>` which may contain:
+    /// - `AppError::Io`: If the file cannot be read
+    /// - `AppError::Parse`: If syntax highlighting fails
+    pub fn preview(
+        &mut self,
+        file_path: &Path,
+        n_rows: usize,
+        code_highlighter: &CodeHighlighter,
+    ) -> AppResult<&FilePreview> {
+        let modified = fs::metadata(file_path)?.modified().ok();
+        let is_fresh = self
+            .cache_holder
+            .peek(file_path)
+            .is_some_and(|preview| preview.modified == modified && preview.n_rows >= n_rows);
+        if !is_fresh {
+            let preview = FilePreview {
+                lines: Self::read_lines(file_path, n_rows, code_highlighter)?,
+                n_rows,
+                modified,
+            };
+            self.cache_holder.put(file_path.to_path_buf(), preview);
+        }
+        self.cache_holder
+            .get(file_path)
+            .ok_or(AppError::Cache(format!(
+                "Unable to get preview of {}",
+                file_path.display()
+            )))
+    }
+
+    /// Starts reading a directory for its preview, unless already read
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Directory to preview
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::Parse` if the
+    /// directory cannot be read, it is tried again when next asked for
+    pub fn read_directory(&mut self, path: &Path) -> AppResult<()> {
+        if !self.directory_loading.contains_key(path) {
+            let load_holder = LoadHolder::read_directory(open_directory(path)?);
+            self.directory_loading
+                .insert(path.to_path_buf(), load_holder);
+        }
+        Ok(())
+    }
+
+    /// Collects the directory previews read since the last check
+    ///
+    /// # Returns
+    ///
+    /// Returns the directories read entirely, with their unsorted entries
+    pub fn poll_directories(&mut self) -> Vec<(PathBuf, Vec<FileHolder>)> {
+        let mut listings = Vec::new();
+        self.directory_loading
+            .retain(|path, load_holder| match load_holder.poll() {
+                LoadStatus::Done(entries) => {
+                    listings.push((path.clone(), entries));
+                    false
+                }
+                _ => true,
+            });
+        listings
+    }
+
+    /// Checks if a directory preview is being read
+    pub fn is_loading(&self) -> bool {
+        !self.directory_loading.is_empty()
+    }
+
+    /// Reads and highlights the first lines of a file
+    fn read_lines(
+        file_path: &Path,
+        n_rows: usize,
+        code_highlighter: &CodeHighlighter,
+    ) -> AppResult<Vec<Line<'static>>> {
+        let file = File::open(file_path)?;
+        if file.metadata()?.len() > MAX_FILE_SIZE {
+            return Ok(vec![Line::from("File too large, no preview").italic()]);
+        }
+        let mut bytes = Vec::new();
+        file.take(PREVIEW_READ_SIZE).read_to_end(&mut bytes)?;
+        if bytes.contains(&0) {
+            return Ok(vec![Line::from("Binary file, no preview").italic()]);
+        }
+        let content = String::from_utf8_lossy(&bytes);
+        let head: String = content.split_inclusive('\n').take(n_rows).collect();
+        code_highlighter.highlight(&head, file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Clone)]
+    struct TestClock(Rc<Cell<Instant>>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn test_preview_is_sized_and_refreshed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        fs::write(&path, "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+        let code_highlighter = CodeHighlighter::default();
+        let mut preview_holder = PreviewHolder::new().unwrap();
+        assert!(!preview_holder.is_due(&path));
+
+        let preview = preview_holder.preview(&path, 2, &code_highlighter).unwrap();
+        assert_eq!(preview.lines.len(), 2);
+        assert!(preview_holder.is_due(&path));
+        let preview = preview_holder.preview(&path, 5, &code_highlighter).unwrap();
+        assert_eq!(preview.lines.len(), 3);
+
+        // a change of the file is picked up
+        fs::write(&path, "fn a() {}\n").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(1))
+            .unwrap();
+        let preview = preview_holder.preview(&path, 5, &code_highlighter).unwrap();
+        assert_eq!(preview.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_directory_preview_loaded_in_background() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "b").unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        let mut preview_holder = PreviewHolder::new().unwrap();
+
+        preview_holder.read_directory(temp_dir.path()).unwrap();
+        preview_holder.read_directory(temp_dir.path()).unwrap();
+        let mut listings = Vec::new();
+        while preview_holder.is_loading() {
+            listings.extend(preview_holder.poll_directories());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(listings.len(), 1);
+        let (path, entries) = &listings[0];
+        assert_eq!(path, temp_dir.path());
+        assert_eq!(entries.len(), 2);
+
+        // a failed read is tried again
+        let missing = temp_dir.path().join("missing");
+        assert!(preview_holder.read_directory(&missing).is_err());
+        assert!(!preview_holder.is_loading());
+        fs::create_dir(&missing).unwrap();
+        preview_holder.read_directory(&missing).unwrap();
+        assert!(preview_holder.is_loading());
+    }
+
+    #[test]
+    fn test_selection_debounced() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        let clock = TestClock(Rc::new(Cell::new(Instant::now())));
+        let mut preview_holder = PreviewHolder::new().unwrap();
+        preview_holder.set_clock(clock.clone());
+        let advance = |duration: Duration| clock.0.set(clock.0.get() + duration);
+
+        // the preview is due once, after the selection settled
+        assert!(!preview_holder.select(Some(&first)));
+        advance(PREVIEW_DEBOUNCE / 2);
+        assert!(!preview_holder.select(Some(&first)));
+        assert!(!preview_holder.is_due(&first));
+        advance(PREVIEW_DEBOUNCE / 2);
+        assert!(preview_holder.select(Some(&first)));
+        assert!(preview_holder.is_due(&first));
+        assert!(!preview_holder.select(Some(&first)));
+
+        // moving the selection starts over
+        assert!(!preview_holder.select(Some(&second)));
+        assert!(!preview_holder.is_due(&first));
+        assert!(!preview_holder.is_due(&second));
+
+        // nothing is waited for while no preview is shown
+        preview_holder.select(None);
+        advance(PREVIEW_DEBOUNCE);
+        assert!(!preview_holder.is_due(&second));
+        assert!(!preview_holder.select(Some(&second)));
+    }
+
+    #[test]
+    fn test_preview_skips_binary_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("image.bin");
+        fs::write(&path, [0x89, b'P', b'N', b'G', 0, 1, 2]).unwrap();
+        let mut preview_holder = PreviewHolder::new().unwrap();
+        let preview = preview_holder
+            .preview(&path, 10, &CodeHighlighter::default())
+            .unwrap();
+        assert_eq!(preview.lines[0].to_string(), "Binary file, no preview");
+    }
+}
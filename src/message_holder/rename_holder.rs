@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use crate::app::app_error::{AppError, AppResult};
+use crate::message_holder::dialog_holder::unique_path;
+use crate::message_holder::journal_holder::Operation;
+use crate::message_holder::transfer_holder::TransferJob;
+use crate::message_holder::trash_holder::move_path;
+
+/// Renames typed in the bulk rename buffer, checked and ordered
+///
+/// Names are relative to the directory shown, one per line in the order
+/// of the listed items. Items renamed to the name of another renamed item
+/// wait for it to move first, and items renaming each other in a cycle go
+/// through a temporary name.
+///
+/// # Fields
+///
+/// - `renames`: Items whose name changed, with their new path
+/// - `steps`: Moves running the renames, in order
+/// - `n_cycles`: Number of cycles broken with a temporary name
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenamePlan {
+    pub renames: Vec<TransferJob>,
+    pub steps: Vec<TransferJob>,
+    pub n_cycles: usize,
+}
+
+impl RenamePlan {
+    /// Compares the edited buffer with the original names
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - Directory the names are relative to
+    /// * `originals` - Items listed in the buffer, in order
+    /// * `text` - Edited buffer
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain:
+    /// - `AppError::Parse`: If lines were added or removed
+    /// - `AppError::Path`: If a name is invalid, two items get the same name,
+    ///   a name exists already or an item is renamed with its content
+    pub fn new(directory: &Path, originals: &[PathBuf], text: &str) -> AppResult<Self> {
+        let lines: Vec<&str> = text.lines().collect();
+        if lines.len() != originals.len() {
+            return Err(AppError::Parse(format!(
+                "Expected {} names, got {}, lines cannot be added or removed",
+                originals.len(),
+                lines.len()
+            )));
+        }
+        let mut renames = Vec::new();
+        for (from, line) in originals.iter().zip(lines) {
+            let to = directory.join(check_relative_name(line)?);
+            if to != *from {
+                renames.push(TransferJob {
+                    from: from.clone(),
+                    to,
+                });
+            }
+        }
+        Self::check(&renames)?;
+        let (steps, n_cycles) = Self::order(&renames);
+        Ok(Self {
+            renames,
+            steps,
+            n_cycles,
+        })
+    }
+
+    /// Refuses renames that would lose or overwrite an item
+    fn check(renames: &[TransferJob]) -> AppResult<()> {
+        let sources: HashSet<&Path> = renames.iter().map(|job| job.from.as_path()).collect();
+        let mut targets: HashMap<&Path, &Path> = HashMap::new();
+        for job in renames {
+            if let Some(other) = targets.insert(&job.to, &job.from) {
+                return Err(AppError::Path(format!(
+                    "{} and {} would both be renamed to {}",
+                    other.display(),
+                    job.from.display(),
+                    job.to.display()
+                )));
+            }
+            if job.to.symlink_metadata().is_ok() && !sources.contains(job.to.as_path()) {
+                return Err(AppError::Path(format!(
+                    "{} exists already",
+                    job.to.display()
+                )));
+            }
+            let parent = job.to.parent().unwrap_or(Path::new(""));
+            if !parent.is_dir() {
+                return Err(AppError::Path(format!(
+                    "Directory {} does not exist",
+                    parent.display()
+                )));
+            }
+            if let Some(source) = sources
+                .iter()
+                .find(|source| **source != job.from && job.from.starts_with(source))
+            {
+                return Err(AppError::Path(format!(
+                    "Cannot rename {} and its content at once",
+                    source.display()
+                )));
+            }
+            if job.to.starts_with(&job.from) {
+                return Err(AppError::Path(format!(
+                    "Cannot move {} into itself",
+                    job.from.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Orders the renames so no item is moved over one that has not moved yet
+    ///
+    /// # Returns
+    ///
+    /// Returns the moves to run and the number of cycles broken
+    fn order(renames: &[TransferJob]) -> (Vec<TransferJob>, usize) {
+        let mut pending = renames.to_vec();
+        let mut steps = Vec::new();
+        let mut n_cycles = 0;
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|job| !pending.iter().any(|other| other.from == job.to));
+            if let Some(index) = ready {
+                steps.push(pending.remove(index));
+                continue;
+            }
+            // every target is still taken, the first item steps aside
+            let job = &mut pending[0];
+            let name = job.from.file_name().unwrap_or_default().to_string_lossy();
+            let temporary = unique_path(&job.from.with_file_name(format!(".{}.rename", name)));
+            steps.push(TransferJob {
+                from: job.from.clone(),
+                to: temporary.clone(),
+            });
+            job.from = temporary;
+            n_cycles += 1;
+        }
+        (steps, n_cycles)
+    }
+
+    /// Runs the renames, stopping at the first that fails
+    ///
+    /// # Returns
+    ///
+    /// Returns the moves that ran, for the journal, and whether every
+    /// rename ran
+    pub fn apply(&self) -> (Vec<Operation>, AppResult<()>) {
+        let mut operations = Vec::new();
+        for step in &self.steps {
+            if step.to.symlink_metadata().is_ok() {
+                let error = AppError::Path(format!("{} exists already", step.to.display()));
+                return (operations, Err(error));
+            }
+            if let Err(error) = move_path(&step.from, &step.to) {
+                return (operations, Err(error.into()));
+            }
+            operations.push(Operation::Move {
+                from: step.from.clone(),
+                to: step.to.clone(),
+            });
+        }
+        (operations, Ok(()))
+    }
+}
+
+/// Returns the buffer listing the names to edit, one per line
+///
+/// # Arguments
+///
+/// * `directory` - Directory the names are relative to
+/// * `paths` - Items to rename
+///
+/// # Returns
+///
+/// Returns `AppResult<String>` which may contain `AppError::Path` if an
+/// item is outside of `directory`, or its name has a line break or is not
+/// valid UTF-8
+pub fn buffer_text(directory: &Path, paths: &[PathBuf]) -> AppResult<String> {
+    let mut text = String::new();
+    for path in paths {
+        // a lossy name would not match the item once edited back
+        let name = path
+            .strip_prefix(directory)
+            .map_err(|_| AppError::Path(format!("{} is not listed", path.display())))?
+            .to_str()
+            .ok_or_else(|| {
+                AppError::Path(format!(
+                    "Cannot rename {}, its name is not valid UTF-8",
+                    path.display()
+                ))
+            })?;
+        if name.contains(['\n', '\r']) {
+            return Err(AppError::Path(format!(
+                "Cannot rename {:?}, its name has a line break",
+                name
+            )));
+        }
+        text.push_str(name);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Checks that a typed name stays below the directory shown
+fn check_relative_name(name: &str) -> AppResult<&Path> {
+    let path = Path::new(name);
+    let is_valid = !name.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !is_valid {
+        return Err(AppError::Path(format!("Invalid name \"{}\"", name)));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup(names: &[&str]) -> (TempDir, Vec<PathBuf>) {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = names
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                fs::write(&path, name).unwrap();
+                path
+            })
+            .collect();
+        (temp_dir, paths)
+    }
+
+    #[test]
+    fn test_rename_chain_and_cycle() {
+        let (temp_dir, paths) = setup(&["a", "b", "c", "d"]);
+        let text = buffer_text(temp_dir.path(), &paths).unwrap();
+        assert_eq!(text, "a\nb\nc\nd\n");
+
+        // a and b swap, c takes the name of d which becomes e
+        let plan = RenamePlan::new(temp_dir.path(), &paths, "b\na\nd\ne\n").unwrap();
+        assert_eq!(plan.renames.len(), 4);
+        assert_eq!(plan.n_cycles, 1);
+        assert_eq!(plan.steps.len(), 5);
+        let (operations, result) = plan.apply();
+        result.unwrap();
+        assert_eq!(operations.len(), 5);
+        let read = |name| fs::read_to_string(temp_dir.path().join(name)).unwrap();
+        assert_eq!(read("a"), "b");
+        assert_eq!(read("b"), "a");
+        assert_eq!(read("d"), "c");
+        assert_eq!(read("e"), "d");
+        assert!(!temp_dir.path().join("c").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_buffer_refuses_invalid_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let temp_dir = TempDir::new().unwrap();
+        let line_break = temp_dir.path().join("a\nb");
+        let invalid = temp_dir.path().join(OsStr::from_bytes(b"caf\xe9"));
+        let error = buffer_text(temp_dir.path(), &[line_break]).unwrap_err();
+        assert!(error.to_string().contains("line break"));
+        let error = buffer_text(temp_dir.path(), &[invalid]).unwrap_err();
+        assert!(error.to_string().contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_rename_refuses_collisions() {
+        let (temp_dir, paths) = setup(&["a", "b", "c"]);
+        let originals = &paths[..2];
+        let directory = temp_dir.path();
+
+        // unchanged names are left out
+        let plan = RenamePlan::new(directory, originals, "a\nb\n").unwrap();
+        assert!(plan.renames.is_empty());
+        assert!(RenamePlan::new(directory, originals, "x\nx\n").is_err());
+        assert!(RenamePlan::new(directory, originals, "c\nb\n").is_err());
+        assert!(RenamePlan::new(directory, originals, "a\n").is_err());
+        assert!(RenamePlan::new(directory, originals, "../a\nb\n").is_err());
+        assert!(RenamePlan::new(directory, originals, "missing/a\nb\n").is_err());
+    }
+}
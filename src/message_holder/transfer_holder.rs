@@ -0,0 +1,304 @@
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use crate::message_holder::trash_holder::{
+    copy_path_or_remove, remove_path, rename_no_replace, TrashEntry, TreeSize,
+};
+
+/// Whether pasted items are copied or moved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferMode {
+    Copy,
+    Move,
+}
+
+impl fmt::Display for TransferMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferMode::Copy => write!(f, "copying"),
+            TransferMode::Move => write!(f, "moving"),
+        }
+    }
+}
+
+/// An item to copy or move
+///
+/// # Fields
+///
+/// - `from`: Item to copy or move
+/// - `to`: Path of the item once pasted
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferJob {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Items copied or cut, waiting to be pasted
+///
+/// # Fields
+///
+/// - `paths`: Copied or cut items
+/// - `mode`: Whether the items are copied or moved when pasted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clipboard {
+    pub paths: Vec<PathBuf>,
+    pub mode: TransferMode,
+}
+
+/// Message sent by the worker thread
+#[derive(Debug)]
+enum TransferMessage {
+    Total(TreeSize),
+    Copied(TreeSize),
+    Done(TransferJob),
+    Kept(TransferJob, String),
+    Failed(PathBuf, String),
+}
+
+/// Copies or moves items on a background thread
+///
+/// The total size is measured first so the progress can be shown while
+/// the files are copied. Moves within a filesystem are renames, moves to
+/// another filesystem are copied then removed. Dropping the holder
+/// cancels the transfer after the file being copied.
+///
+/// # Fields
+///
+/// - `mode`: Whether the items are copied or moved
+/// - `receiver`: Progress sent by the worker thread
+/// - `cancel`: Flag asking the worker thread to stop
+/// - `total`: Number of files and size to transfer, once measured
+/// - `done`: Number of files and size transferred so far
+/// - `finished`: Items transferred
+/// - `kept`: Moved items that were copied but could not be removed
+/// - `errors`: Items that could not be transferred, with the reason
+/// - `trashed`: Existing items trashed to be overwritten, journaled with the transfer
+#[derive(Debug)]
+pub struct TransferHolder {
+    pub mode: TransferMode,
+    receiver: Receiver<TransferMessage>,
+    cancel: Arc<AtomicBool>,
+    pub total: Option<TreeSize>,
+    pub done: TreeSize,
+    pub finished: Vec<TransferJob>,
+    pub kept: Vec<TransferJob>,
+    pub errors: Vec<String>,
+    pub trashed: Vec<TrashEntry>,
+}
+
+impl TransferHolder {
+    /// Starts copying or moving items
+    ///
+    /// # Arguments
+    ///
+    /// * `jobs` - Items to transfer, their destinations must not exist
+    /// * `mode` - Whether the items are copied or moved
+    pub fn start(jobs: Vec<TransferJob>, mode: TransferMode) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+        thread::spawn(move || Self::transfer(jobs, mode, &sender, &worker_cancel));
+        Self {
+            mode,
+            receiver,
+            cancel,
+            total: None,
+            done: TreeSize::default(),
+            finished: Vec::new(),
+            kept: Vec::new(),
+            errors: Vec::new(),
+            trashed: Vec::new(),
+        }
+    }
+
+    /// Applies the progress sent since the last call
+    ///
+    /// # Returns
+    ///
+    /// Returns true once every item was transferred or failed
+    pub fn poll(&mut self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(TransferMessage::Total(total)) => self.total = Some(total),
+                Ok(TransferMessage::Copied(copied)) => {
+                    self.done.n_files += copied.n_files;
+                    self.done.size += copied.size;
+                }
+                Ok(TransferMessage::Done(job)) => self.finished.push(job),
+                Ok(TransferMessage::Kept(job, error)) => {
+                    self.errors.push(format!(
+                        "{}: copied to {} but not removed, {}",
+                        job.from.display(),
+                        job.to.display(),
+                        error
+                    ));
+                    self.kept.push(job);
+                }
+                Ok(TransferMessage::Failed(path, error)) => {
+                    self.errors.push(format!("{}: {}", path.display(), error))
+                }
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => return true,
+            }
+        }
+    }
+
+    /// Asks the worker thread to stop after the file being copied
+    ///
+    /// Items transferred so far are kept, the item being copied is removed.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Describes the progress, for the view title
+    pub fn status(&self) -> String {
+        match &self.total {
+            None => format!("{}, measuring", self.mode),
+            Some(total) => format!("{} {} of {}, <Esc> to cancel", self.mode, self.done, total),
+        }
+    }
+
+    fn transfer(
+        jobs: Vec<TransferJob>,
+        mode: TransferMode,
+        sender: &Sender<TransferMessage>,
+        cancel: &AtomicBool,
+    ) {
+        let total = TreeSize::of_all(jobs.iter().map(|job| job.from.as_path()));
+        if sender.send(TransferMessage::Total(total)).is_err() {
+            return;
+        }
+        for job in jobs {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let message = match Self::transfer_one(&job, mode, sender, cancel) {
+                Ok(true) if mode == TransferMode::Move => match remove_path(&job.from) {
+                    Ok(()) => TransferMessage::Done(job),
+                    // both are kept when the source cannot be removed
+                    Err(error) => TransferMessage::Kept(job, error.to_string()),
+                },
+                Ok(_) => TransferMessage::Done(job),
+                Err(error) => TransferMessage::Failed(job.from, error.to_string()),
+            };
+            if sender.send(message).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Copies one item, or renames it when moved within a filesystem
+    ///
+    /// Nothing existing is replaced, what was copied is removed if the
+    /// copy fails.
+    ///
+    /// # Returns
+    ///
+    /// Returns `io::Result<bool>`, whether the item was copied rather than renamed
+    fn transfer_one(
+        job: &TransferJob,
+        mode: TransferMode,
+        sender: &Sender<TransferMessage>,
+        cancel: &AtomicBool,
+    ) -> io::Result<bool> {
+        if job.to.symlink_metadata().is_ok() {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} exists already", job.to.display()),
+            ));
+        }
+        let mut on_file = |size| {
+            let copied = TreeSize { n_files: 1, size };
+            let _ = sender.send(TransferMessage::Copied(copied));
+            !cancel.load(Ordering::Relaxed)
+        };
+        if mode == TransferMode::Move {
+            match rename_no_replace(&job.from, &job.to) {
+                Err(error) if error.kind() == ErrorKind::CrossesDevices => (),
+                Ok(()) => {
+                    let _ = sender.send(TransferMessage::Copied(TreeSize::of(&job.to)));
+                    return Ok(false);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        copy_path_or_remove(&job.from, &job.to, &mut on_file)?;
+        Ok(true)
+    }
+}
+
+impl Drop for TransferHolder {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn wait(transfer_holder: &mut TransferHolder) {
+        while !transfer_holder.poll() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_copy_with_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src/sub")).unwrap();
+        fs::write(temp_dir.path().join("src/a.txt"), "aa").unwrap();
+        fs::write(temp_dir.path().join("src/sub/b.txt"), "bbb").unwrap();
+
+        let job = TransferJob {
+            from: temp_dir.path().join("src"),
+            to: temp_dir.path().join("copy"),
+        };
+        let mut transfer_holder = TransferHolder::start(vec![job.clone()], TransferMode::Copy);
+        wait(&mut transfer_holder);
+        assert_eq!(transfer_holder.finished, vec![job]);
+        assert_eq!(
+            transfer_holder.total,
+            Some(TreeSize {
+                n_files: 2,
+                size: 5
+            })
+        );
+        assert_eq!(transfer_holder.done, transfer_holder.total.unwrap());
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("copy/sub/b.txt")).unwrap(),
+            "bbb"
+        );
+        assert!(temp_dir.path().join("src/a.txt").exists());
+    }
+
+    #[test]
+    fn test_move_reports_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        let jobs = vec![
+            TransferJob {
+                from: temp_dir.path().join("missing.txt"),
+                to: temp_dir.path().join("dest/missing.txt"),
+            },
+            TransferJob {
+                from: temp_dir.path().join("a.txt"),
+                to: temp_dir.path().join("b.txt"),
+            },
+        ];
+        let mut transfer_holder = TransferHolder::start(jobs, TransferMode::Move);
+        wait(&mut transfer_holder);
+        assert_eq!(transfer_holder.errors.len(), 1);
+        assert_eq!(transfer_holder.finished.len(), 1);
+        assert!(!temp_dir.path().join("a.txt").exists());
+        assert!(temp_dir.path().join("b.txt").exists());
+    }
+}
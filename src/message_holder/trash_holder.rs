@@ -0,0 +1,636 @@
+use chrono::Local;
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::app::app_error::{AppError, AppResult};
+
+/// Extension of the files describing trashed items
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+
+/// An item moved to the trash
+///
+/// # Fields
+///
+/// - `original_path`: Where the item was before it was trashed
+/// - `trashed_path`: Where the item is in the trash
+/// - `info_path`: Trash info file recording the original location
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    pub original_path: PathBuf,
+    pub trashed_path: PathBuf,
+    pub info_path: PathBuf,
+}
+
+/// Moves files and directories to the freedesktop trash
+///
+/// Follows the freedesktop.org trash specification: items go to
+/// `files/` and a `.trashinfo` file in `info/` records where they came
+/// from, so desktop file managers can restore them. Items on another
+/// filesystem than the home trash go to the trash at the top of their
+/// own filesystem, `$topdir/.Trash/$uid` or `$topdir/.Trash-$uid`, so
+/// they are never copied.
+///
+/// # Fields
+///
+/// - `trash_dir`: Home trash directory, `$XDG_DATA_HOME/Trash` or `~/.local/share/Trash`
+#[derive(Debug, Clone)]
+pub struct TrashHolder {
+    pub trash_dir: PathBuf,
+}
+
+impl Default for TrashHolder {
+    fn default() -> Self {
+        let data_dir = env::var_os("XDG_DATA_HOME")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+            .unwrap_or_else(env::temp_dir);
+        Self {
+            trash_dir: data_dir.join("Trash"),
+        }
+    }
+}
+
+impl TrashHolder {
+    /// Moves an item to the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File or directory to trash
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<TrashEntry>` which may contain:
+    /// - `AppError::Io`: If the item cannot be moved or the trash cannot be written
+    /// - `AppError::Path`: If the path cannot be resolved or its filesystem has no usable trash
+    pub fn trash(&self, path: &Path) -> AppResult<TrashEntry> {
+        // the parent is resolved but not the item itself, a link is trashed
+        // rather than its target
+        let unable = || AppError::Path(format!("Unable to trash {}", path.display()));
+        let file_name = path.file_name().ok_or_else(unable)?;
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let original_path = parent.canonicalize().map_err(|_| unable())?.join(file_name);
+        let (trash_dir, recorded_path) = self.trash_dir_of(&original_path)?;
+        let file_name = file_name.to_string_lossy().into_owned();
+        let files_dir = trash_dir.join("files");
+        let info_dir = trash_dir.join("info");
+        fs::create_dir_all(&files_dir)?;
+        fs::create_dir_all(&info_dir)?;
+
+        // the info file is created first, it reserves the name in the trash
+        let mut index = 1;
+        let (trashed_path, info_path, mut info_file) = loop {
+            let name = if index == 1 {
+                file_name.clone()
+            } else {
+                format!("{}.{}", file_name, index)
+            };
+            let trashed_path = files_dir.join(&name);
+            let info_path = info_dir.join(format!("{}.{}", name, TRASH_INFO_EXTENSION));
+            if trashed_path.symlink_metadata().is_err() {
+                match OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&info_path)
+                {
+                    Ok(info_file) => break (trashed_path, info_path, info_file),
+                    Err(error) if error.kind() == ErrorKind::AlreadyExists => (),
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            index += 1;
+        };
+        let info = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode_path(&recorded_path),
+            Local::now().format("%Y-%m-%dT%H:%M:%S")
+        );
+        let moved = info_file
+            .write_all(info.as_bytes())
+            .and_then(|_| move_path(&original_path, &trashed_path));
+        if let Err(error) = moved {
+            let _ = fs::remove_file(&info_path);
+            return Err(error.into());
+        }
+        Ok(TrashEntry {
+            original_path,
+            trashed_path,
+            info_path,
+        })
+    }
+
+    /// Returns the trash of the filesystem holding an item
+    ///
+    /// # Arguments
+    ///
+    /// * `original_path` - Resolved path of the item to trash
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<(PathBuf, PathBuf)>`, the trash directory and the
+    /// path to record in the info file, relative to the top directory for
+    /// the trash of another filesystem. It may contain:
+    /// - `AppError::Io`: If the item cannot be read
+    /// - `AppError::Path`: If no trash can be used on the item's filesystem
+    #[cfg(unix)]
+    fn trash_dir_of(&self, original_path: &Path) -> AppResult<(PathBuf, PathBuf)> {
+        use std::os::unix::fs::MetadataExt;
+
+        let device = original_path.symlink_metadata()?.dev();
+        // the home trash may not exist yet, its closest parent tells its filesystem
+        let home_device = self
+            .trash_dir
+            .ancestors()
+            .find_map(|dir| dir.metadata().ok())
+            .map(|meta_data| meta_data.dev());
+        if home_device == Some(device) {
+            return Ok((self.trash_dir.clone(), original_path.to_path_buf()));
+        }
+
+        let unable = |reason: String| {
+            AppError::Path(format!(
+                "Unable to trash {}, {}",
+                original_path.display(),
+                reason
+            ))
+        };
+        // the top directory is the mount point holding the item
+        let topdir = original_path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| {
+                dir.metadata()
+                    .is_ok_and(|meta_data| meta_data.dev() == device)
+            })
+            .last()
+            .ok_or_else(|| unable("its filesystem is not found".into()))?;
+        let uid = unsafe { libc::getuid() };
+        let trash_dir = topdir_trash(topdir, uid)
+            .map_err(|error| unable(format!("no trash on its filesystem: {}", error)))?;
+        let recorded_path = original_path
+            .strip_prefix(topdir)
+            .unwrap_or(original_path)
+            .to_path_buf();
+        Ok((trash_dir, recorded_path))
+    }
+
+    #[cfg(not(unix))]
+    fn trash_dir_of(&self, original_path: &Path) -> AppResult<(PathBuf, PathBuf)> {
+        original_path.symlink_metadata()?;
+        Ok((self.trash_dir.clone(), original_path.to_path_buf()))
+    }
+
+    /// Moves a trashed item back to where it was
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - Item returned by [`TrashHolder::trash`]
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain:
+    /// - `AppError::Path`: If something else now exists at the original location
+    /// - `AppError::Io`: If the item cannot be moved
+    pub fn restore(&self, entry: &TrashEntry) -> AppResult<()> {
+        if entry.original_path.symlink_metadata().is_ok() {
+            return Err(AppError::Path(format!(
+                "Unable to restore {}, it exists already",
+                entry.original_path.display()
+            )));
+        }
+        move_path(&entry.trashed_path, &entry.original_path)?;
+        fs::remove_file(&entry.info_path)?;
+        Ok(())
+    }
+}
+
+/// Returns the trash of a user at the top directory of a filesystem
+///
+/// A shared `.Trash` directory is used if it has the sticky bit and is not
+/// a link, `.Trash-$uid` is used otherwise. The trash is created if needed,
+/// readable by the user only.
+///
+/// # Arguments
+///
+/// * `topdir` - Mount point of the filesystem
+/// * `uid` - User id of the user trashing
+///
+/// # Returns
+///
+/// Returns `io::Result<PathBuf>` which may contain an error if the trash
+/// cannot be created or is not a directory
+#[cfg(unix)]
+fn topdir_trash(topdir: &Path, uid: u32) -> io::Result<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let create_private = |trash_dir: &Path| {
+        match fs::DirBuilder::new().mode(0o700).create(trash_dir) {
+            Err(error) if error.kind() != ErrorKind::AlreadyExists => return Err(error),
+            _ => (),
+        }
+        if trash_dir.symlink_metadata()?.is_dir() {
+            Ok(trash_dir.to_path_buf())
+        } else {
+            Err(io::Error::other(format!(
+                "{} is not a directory",
+                trash_dir.display()
+            )))
+        }
+    };
+    let shared_dir = topdir.join(".Trash");
+    let is_shared = shared_dir
+        .symlink_metadata()
+        .is_ok_and(|meta_data| meta_data.is_dir() && meta_data.permissions().mode() & 0o1000 != 0);
+    if is_shared {
+        if let Ok(trash_dir) = create_private(&shared_dir.join(uid.to_string())) {
+            return Ok(trash_dir);
+        }
+    }
+    create_private(&topdir.join(format!(".Trash-{}", uid)))
+}
+
+/// Number of files and total size of a file or directory tree
+///
+/// Symbolic links are counted as files and not followed.
+///
+/// # Fields
+///
+/// - `n_files`: Number of files
+/// - `size`: Total size of the files in bytes
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TreeSize {
+    pub n_files: u64,
+    pub size: u64,
+}
+
+impl TreeSize {
+    /// Measures a file or directory tree
+    ///
+    /// Entries that cannot be read are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File or directory to measure
+    pub fn of(path: &Path) -> Self {
+        Self::of_all([path])
+    }
+
+    /// Measures several files or directory trees together
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Files or directories to measure
+    pub fn of_all<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Self {
+        Self::of_all_until(paths, &AtomicBool::new(false)).unwrap_or_default()
+    }
+
+    /// Measures several files or directory trees together, unless cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Files or directories to measure
+    /// * `cancel` - Flag stopping the measure once set
+    ///
+    /// # Returns
+    ///
+    /// Returns `Option<Self>`, `None` if the measure was cancelled
+    pub fn of_all_until<'a>(
+        paths: impl IntoIterator<Item = &'a Path>,
+        cancel: &AtomicBool,
+    ) -> Option<Self> {
+        let mut tree_size = Self::default();
+        let mut pending: Vec<PathBuf> = paths.into_iter().map(Path::to_path_buf).collect();
+        while let Some(path) = pending.pop() {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(meta_data) = path.symlink_metadata() else {
+                continue;
+            };
+            if meta_data.is_dir() {
+                if let Ok(read_dir) = fs::read_dir(&path) {
+                    pending.extend(read_dir.flatten().map(|entry| entry.path()));
+                }
+            } else {
+                tree_size.n_files += 1;
+                tree_size.size += meta_data.len();
+            }
+        }
+        Some(tree_size)
+    }
+}
+
+impl fmt::Display for TreeSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+        let mut size = self.size as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        let files = if self.n_files == 1 { "file" } else { "files" };
+        if unit == 0 {
+            write!(f, "{} {}, {} B", self.n_files, files, self.size)
+        } else {
+            write!(f, "{} {}, {:.1} {}", self.n_files, files, size, UNITS[unit])
+        }
+    }
+}
+
+/// Moves a file or directory, copying it when it goes to another filesystem
+///
+/// When the copy fails, what was copied is removed and the item is left
+/// where it was.
+///
+/// # Arguments
+///
+/// * `from` - Item to move
+/// * `to` - New path of the item
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match rename_no_replace(from, to) {
+        Err(error) if error.kind() == ErrorKind::CrossesDevices => {
+            copy_path_or_remove(from, to, &mut |_| true)?;
+            remove_path(from)
+        }
+        result => result,
+    }
+}
+
+/// Renames a file or directory, failing rather than replacing an existing item
+///
+/// The new path is reserved first with an empty file or directory, that
+/// the rename then replaces.
+///
+/// # Arguments
+///
+/// * `from` - Item to rename
+/// * `to` - New path of the item, it must not exist
+pub fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+    let is_dir = from.symlink_metadata()?.is_dir();
+    if is_dir {
+        fs::create_dir(to)?;
+    } else {
+        OpenOptions::new().write(true).create_new(true).open(to)?;
+    }
+    let result = fs::rename(from, to);
+    if result.is_err() {
+        let _ = if is_dir {
+            fs::remove_dir(to)
+        } else {
+            fs::remove_file(to)
+        };
+    }
+    result
+}
+
+/// Copies a file or directory tree, removing what was copied if the copy fails
+///
+/// Nothing existing is replaced, symbolic links are copied as links.
+///
+/// # Arguments
+///
+/// * `from` - Item to copy
+/// * `to` - Path of the copy, it must not exist
+/// * `on_file` - Called with the size of each copied file, the copy stops
+///   with `ErrorKind::Interrupted` when it returns false
+pub fn copy_path_or_remove(
+    from: &Path,
+    to: &Path,
+    on_file: &mut dyn FnMut(u64) -> bool,
+) -> io::Result<()> {
+    let meta_data = from.symlink_metadata()?;
+    if meta_data.is_symlink() {
+        copy_symlink(from, to)?;
+    } else if meta_data.is_dir() {
+        fs::create_dir(to)?;
+    } else {
+        let mut source = File::open(from)?;
+        let mut target = OpenOptions::new().write(true).create_new(true).open(to)?;
+        let copied = io::copy(&mut source, &mut target)
+            .and_then(|_| target.set_permissions(meta_data.permissions()));
+        if let Err(error) = copied {
+            let _ = fs::remove_file(to);
+            return Err(error);
+        }
+    }
+    // `to` was created above, it is removed whatever fails from here
+    let result = if meta_data.is_dir() {
+        copy_entries(from, to, on_file)
+    } else if on_file(meta_data.len()) {
+        Ok(())
+    } else {
+        Err(io::Error::new(ErrorKind::Interrupted, "Copy cancelled"))
+    };
+    if result.is_err() {
+        let _ = remove_path(to);
+    }
+    result
+}
+
+fn copy_entries(from: &Path, to: &Path, on_file: &mut dyn FnMut(u64) -> bool) -> io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_path_or_remove(&entry.path(), &to.join(entry.file_name()), on_file)?;
+    }
+    Ok(())
+}
+
+/// Removes a file or directory tree, symbolic links are removed, not followed
+///
+/// # Arguments
+///
+/// * `path` - Item to remove
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.symlink_metadata()?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+/// Percent-encodes the bytes of a path for a trash info file
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for &byte in path_bytes(path).iter() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    std::borrow::Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    match path.to_string_lossy() {
+        std::borrow::Cow::Borrowed(text) => std::borrow::Cow::Borrowed(text.as_bytes()),
+        std::borrow::Cow::Owned(text) => std::borrow::Cow::Owned(text.into_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trash_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash_holder = TrashHolder {
+            trash_dir: temp_dir.path().join("Trash"),
+        };
+        let path = temp_dir.path().join("my notes.txt");
+        fs::write(&path, "first").unwrap();
+
+        let entry = trash_holder.trash(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&entry.trashed_path).unwrap(), "first");
+        let info = fs::read_to_string(&entry.info_path).unwrap();
+        assert!(info.starts_with("[Trash Info]\nPath=/"));
+        assert!(info.contains("my%20notes.txt\nDeletionDate="));
+
+        // a second item with the same name gets another name in the trash
+        fs::write(&path, "second").unwrap();
+        let second_entry = trash_holder.trash(&path).unwrap();
+        assert!(second_entry.trashed_path.ends_with("my notes.txt.2"));
+
+        trash_holder.restore(&entry).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+        assert!(!entry.info_path.exists());
+        assert!(trash_holder.restore(&second_entry).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_topdir_trash() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = TempDir::new().unwrap();
+        let topdir = temp_dir.path();
+
+        // without a shared trash the user gets a private one
+        let trash_dir = topdir_trash(topdir, 1000).unwrap();
+        assert_eq!(trash_dir, topdir.join(".Trash-1000"));
+        let mode = fs::metadata(&trash_dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        // a shared trash is only used with the sticky bit
+        let shared_dir = topdir.join(".Trash");
+        fs::create_dir(&shared_dir).unwrap();
+        assert_eq!(topdir_trash(topdir, 1000).unwrap(), trash_dir);
+        fs::set_permissions(&shared_dir, fs::Permissions::from_mode(0o1777)).unwrap();
+        assert_eq!(topdir_trash(topdir, 1000).unwrap(), shared_dir.join("1000"));
+
+        // a link is not followed
+        std::os::unix::fs::symlink(&shared_dir, topdir.join(".Trash-1001")).unwrap();
+        fs::set_permissions(&shared_dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(topdir_trash(topdir, 1001).is_err());
+    }
+
+    #[test]
+    fn test_tree_size() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("a/b")).unwrap();
+        fs::write(temp_dir.path().join("a/one"), "1").unwrap();
+        fs::write(temp_dir.path().join("a/b/two"), "22").unwrap();
+        assert_eq!(
+            TreeSize::of(&temp_dir.path().join("a")),
+            TreeSize {
+                n_files: 2,
+                size: 3
+            }
+        );
+        let tree_size = TreeSize {
+            n_files: 12,
+            size: 3 * 1024 * 1024 / 2,
+        };
+        assert_eq!(tree_size.to_string(), "12 files, 1.5 MiB");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_copy_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("from/a")).unwrap();
+        fs::write(temp_dir.path().join("from/a/one.txt"), "1").unwrap();
+        // a socket cannot be copied, the copy fails part way
+        let _socket =
+            std::os::unix::net::UnixListener::bind(temp_dir.path().join("from/socket")).unwrap();
+        let to = temp_dir.path().join("to");
+        assert!(copy_path_or_remove(&temp_dir.path().join("from"), &to, &mut |_| true).is_err());
+        assert!(!to.exists());
+        assert!(temp_dir.path().join("from/a/one.txt").exists());
+    }
+
+    #[test]
+    fn test_move_never_replaces() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "b").unwrap();
+        fs::create_dir_all(temp_dir.path().join("dir/sub")).unwrap();
+        fs::create_dir(temp_dir.path().join("empty")).unwrap();
+
+        let error = move_path(
+            &temp_dir.path().join("a.txt"),
+            &temp_dir.path().join("b.txt"),
+        );
+        assert_eq!(error.unwrap_err().kind(), ErrorKind::AlreadyExists);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("a.txt")).unwrap(),
+            "a"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("b.txt")).unwrap(),
+            "b"
+        );
+        // a plain rename would replace an empty directory
+        assert!(move_path(&temp_dir.path().join("dir"), &temp_dir.path().join("empty")).is_err());
+        assert!(temp_dir.path().join("dir/sub").exists());
+        let error = copy_path_or_remove(
+            &temp_dir.path().join("a.txt"),
+            &temp_dir.path().join("b.txt"),
+            &mut |_| true,
+        );
+        assert!(error.is_err());
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("b.txt")).unwrap(),
+            "b"
+        );
+
+        move_path(&temp_dir.path().join("dir"), &temp_dir.path().join("moved")).unwrap();
+        assert!(temp_dir.path().join("moved/sub").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encode_raw_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9 \xc3\xa9"));
+        assert_eq!(encode_path(path), "/tmp/caf%E9%20%C3%A9");
+    }
+}
@@ -0,0 +1,122 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use crate::app::app_error::{AppError, AppResult};
+
+/// Quiet period after the last filesystem event before changes are reported
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest time changes stay pending while events keep coming in
+pub const WATCH_MAX_WAIT: Duration = Duration::from_secs(1);
+
+/// Watches the displayed directories and the opened file for changes
+///
+/// Uses inotify on Linux. Directories are watched non-recursively, so
+/// only the listings on screen are tracked.
+///
+/// # Fields
+///
+/// - `watcher`: Underlying filesystem watcher
+/// - `receiver`: Filesystem events sent by `watcher`
+/// - `watched`: Currently watched paths
+/// - `pending`: Changed paths not reported yet
+/// - `last_event`: When the last event was received, for debouncing
+/// - `first_event`: When the oldest pending event was received, for the max wait
+#[derive(Debug)]
+pub struct WatchHolder {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<Event>>,
+    watched: HashSet<PathBuf>,
+    pending: HashSet<PathBuf>,
+    last_event: Instant,
+    first_event: Instant,
+}
+
+impl WatchHolder {
+    /// Creates a watcher with nothing watched yet
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<Self>` which may contain `AppError::Io` if the
+    /// platform watcher cannot be created
+    pub fn new() -> AppResult<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let watcher = notify::recommended_watcher(sender).map_err(Self::to_app_error)?;
+        Ok(Self {
+            watcher,
+            receiver,
+            watched: HashSet::new(),
+            pending: HashSet::new(),
+            last_event: Instant::now(),
+            first_event: Instant::now(),
+        })
+    }
+
+    /// Replaces the set of watched paths
+    ///
+    /// Paths that cannot be watched (e.g. removed meanwhile) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Directories and files to watch
+    pub fn watch_paths(&mut self, paths: HashSet<PathBuf>) {
+        if paths == self.watched {
+            return;
+        }
+        for path in self.watched.difference(&paths) {
+            let _ = self.watcher.unwatch(path);
+        }
+        let mut watched = HashSet::new();
+        for path in paths {
+            if self.watched.contains(&path)
+                || self
+                    .watcher
+                    .watch(&path, RecursiveMode::NonRecursive)
+                    .is_ok()
+            {
+                watched.insert(path);
+            }
+        }
+        self.watched = watched;
+    }
+
+    /// Collects pending events and returns the changed paths once quiet
+    ///
+    /// Access events are ignored, so reading a directory or file does not
+    /// report it as changed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the changed paths after `WATCH_DEBOUNCE` without new events,
+    /// or once the oldest change waited `WATCH_MAX_WAIT` so continuously
+    /// written paths are still reported. Returns `None` while nothing
+    /// changed or events are still coming in
+    pub fn poll(&mut self) -> Option<HashSet<PathBuf>> {
+        for event in self.receiver.try_iter().flatten() {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            let now = Instant::now();
+            if self.pending.is_empty() {
+                self.first_event = now;
+            }
+            self.pending.extend(event.paths);
+            self.last_event = now;
+        }
+        if self.pending.is_empty()
+            || (self.last_event.elapsed() < WATCH_DEBOUNCE
+                && self.first_event.elapsed() < WATCH_MAX_WAIT)
+        {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    fn to_app_error(error: notify::Error) -> AppError {
+        AppError::Io(io::Error::other(error))
+    }
+}
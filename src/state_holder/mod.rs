@@ -1,7 +1,8 @@
 //! Application state machine
 //!
 //! This module provides a state machine for managing the application's
-//! input and view modes. It supports state transitions and state restoration.
+//! input and view modes. Modes are kept on a stack so nested views unwind
+//! back to where they were opened from.
 //!
 //! # State Model
 //!
@@ -13,19 +14,47 @@
 //!
 //! ## View Mode
 //! - `Search`: Browsing current directory with search filter
-//! - `FileView`: Viewing a file's contents
+//! - `FileView`: Viewing a file's contents (Edit: typing the log time range)
 //! - `HistoryFolderView`: Browsing cached directories (history)
+//! - `FileHistoryView`: Browsing the git history of the opened file
+//! - `BookmarkView`: Picking a named mark to jump to
+//! - `ConfirmView`: Confirming a destructive operation
+//! - `PromptView`: Typing the name of a renamed or new entry, or a shell command
+//! - `CommandView`: Output of a shell command
+//! - `HelpView`: Every key binding of the mode it was opened from
 //!
 //! # State Transitions
 //!
+//! Legal transitions are declared in [`TRANSITIONS`]. A `Switch` replaces
+//! the current mode, a `Push` (`==>`) opens a nested mode that is left with
+//! [`StateHolder::pop_mode`].
+//!
 //! ```text
-//! [Normal+Search] <---> [Edit+Search]
-//!      |                     |
-//!      v                     v
-//! [Normal+FileView]   [Edit+HistoryFolderView]
+//! [Edit+Search]      <-->  [Normal+Search]
+//! [Normal+Search]    <-->  [Edit+HistoryFolderView]
+//! [Edit+Search]       ==>  [Normal+FileView]
+//! [Normal+Search]     ==>  [Normal+FileView]
+//! [Normal+FileView]   ==>  [Normal+FileHistoryView]
+//! [Normal+Search]     ==>  [Edit+BookmarkView]
+//! [Normal+FileView]   ==>  [Edit+BookmarkView]
+//! [Normal+FileView]   ==>  [Edit+FileView]
+//! [Normal+Search]     ==>  [Normal+ConfirmView]
+//! [Normal+Search]     ==>  [Edit+PromptView]
+//! [Normal+FileView]   ==>  [Edit+PromptView]
+//! [Normal+Search]     ==>  [Normal+CommandView]
+//! [Normal+FileView]   ==>  [Normal+CommandView]
+//! [Normal+FileView]   ==>  [Edit+Search]
+//! [Normal+Search]     ==>  [Normal+HelpView]
+//! [Edit+Search]       ==>  [Normal+HelpView]
+//! [Normal+FileView]   ==>  [Normal+HelpView]
 //! ```
+//!
+//! A search opened from the file view lists the directory over the opened
+//! file, leaving it returns to the file as it was left.
 
+use crate::app::app_error::{AppError, AppResult};
 use InputMode::*;
+use Transition::*;
 use ViewMode::*;
 
 /// Input mode: controls how keyboard input is handled
@@ -48,58 +77,147 @@ pub enum ViewMode {
     FileView,
     /// History/cached directory browsing
     HistoryFolderView,
+    /// Git history of the opened file
+    FileHistoryView,
+    /// Named marks picker
+    BookmarkView,
+    /// Confirmation popup of a destructive operation
+    ConfirmView,
+    /// Name of a renamed or new entry, or a shell command
+    PromptView,
+    /// Output of a shell command
+    CommandView,
+    /// Key bindings of the mode below
+    HelpView,
+}
+
+/// A combination of input and view mode
+pub type Mode = (InputMode, ViewMode);
+
+/// Kind of a transition between two modes
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Transition {
+    /// Replaces the current mode
+    Switch,
+    /// Opens a nested mode on top of the current one
+    Push,
 }
 
-/// Application state holder with state restoration support
+/// Legal transitions as `(from, to, kind)`
+///
+/// Any transition not listed here is rejected with `AppError::State`.
+pub const TRANSITIONS: &[(Mode, Mode, Transition)] = &[
+    ((Edit, Search), (Normal, Search), Switch),
+    ((Normal, Search), (Edit, Search), Switch),
+    ((Normal, Search), (Edit, HistoryFolderView), Switch),
+    ((Edit, HistoryFolderView), (Normal, Search), Switch),
+    ((Edit, Search), (Normal, FileView), Push),
+    ((Normal, Search), (Normal, FileView), Push),
+    ((Normal, FileView), (Normal, FileHistoryView), Push),
+    ((Normal, Search), (Edit, BookmarkView), Push),
+    ((Normal, FileView), (Edit, BookmarkView), Push),
+    ((Normal, FileView), (Edit, FileView), Push),
+    ((Normal, Search), (Normal, ConfirmView), Push),
+    ((Normal, Search), (Edit, PromptView), Push),
+    ((Normal, FileView), (Edit, PromptView), Push),
+    ((Normal, Search), (Normal, CommandView), Push),
+    ((Normal, FileView), (Normal, CommandView), Push),
+    ((Normal, FileView), (Edit, Search), Push),
+    ((Normal, Search), (Normal, HelpView), Push),
+    ((Edit, Search), (Normal, HelpView), Push),
+    ((Normal, FileView), (Normal, HelpView), Push),
+];
+
+/// Application state holder with a stack of nested modes
 ///
 /// # Fields
 ///
 /// - `input_mode`: Current input mode
 /// - `view_mode`: Current view mode
-/// - `prev_input_mode`: Previous input mode (for restoration)
-/// - `prev_view_mode`: Previous view mode (for restoration)
+/// - `mode_stack`: Modes the current one was pushed from, outermost first
 #[derive(Debug, Default, PartialEq)]
 pub struct StateHolder {
     pub input_mode: InputMode,
     pub view_mode: ViewMode,
-    prev_input_mode: InputMode,
-    prev_view_mode: ViewMode,
+    mode_stack: Vec<Mode>,
 }
 
 impl StateHolder {
     /// Transitions to Normal+Search mode
     ///
     /// Used for browsing current directory with keyboard navigation
-    pub fn to_search(&mut self) {
-        self.save_previous_state();
-        self.input_mode = Normal;
-        self.view_mode = Search;
+    pub fn to_search(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, Search)
     }
+
     /// Transitions to Edit+Search mode
     ///
     /// Used for typing search/filter queries
-    pub fn to_search_edit(&mut self) {
-        self.save_previous_state();
-        self.input_mode = Edit;
-        self.view_mode = Search;
+    pub fn to_search_edit(&mut self) -> AppResult<()> {
+        self.transition_to(Edit, Search)
     }
 
     /// Transitions to Edit+HistoryFolderView mode
     ///
     /// Used for searching through cached directory history
-    pub fn to_history_search(&mut self) {
-        self.save_previous_state();
-        self.input_mode = Edit;
-        self.view_mode = HistoryFolderView;
+    pub fn to_history_search(&mut self) -> AppResult<()> {
+        self.transition_to(Edit, HistoryFolderView)
     }
 
     /// Transitions to Normal+FileView mode
     ///
     /// Used for viewing file contents
-    pub fn to_file_view(&mut self) {
-        self.save_previous_state();
-        self.input_mode = Normal;
-        self.view_mode = FileView;
+    pub fn to_file_view(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, FileView)
+    }
+
+    /// Transitions to Normal+FileHistoryView mode
+    ///
+    /// Used for browsing the git history of the opened file
+    pub fn to_file_history(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, FileHistoryView)
+    }
+
+    /// Transitions to Edit+BookmarkView mode
+    ///
+    /// Used for filtering and picking a named mark
+    pub fn to_bookmark_search(&mut self) -> AppResult<()> {
+        self.transition_to(Edit, BookmarkView)
+    }
+
+    /// Transitions to Edit+FileView mode
+    ///
+    /// Used for typing the time range of the log view
+    pub fn to_log_filter(&mut self) -> AppResult<()> {
+        self.transition_to(Edit, FileView)
+    }
+
+    /// Transitions to Normal+ConfirmView mode
+    ///
+    /// Used for confirming a destructive operation before it runs
+    pub fn to_confirm(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, ConfirmView)
+    }
+
+    /// Transitions to Edit+PromptView mode
+    ///
+    /// Used for typing the name of a renamed or new entry, or a shell command
+    pub fn to_prompt(&mut self) -> AppResult<()> {
+        self.transition_to(Edit, PromptView)
+    }
+
+    /// Transitions to Normal+CommandView mode
+    ///
+    /// Used for scrolling through the output of a shell command
+    pub fn to_command_view(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, CommandView)
+    }
+
+    /// Transitions to Normal+HelpView mode
+    ///
+    /// Used for listing every key binding of the current mode
+    pub fn to_help(&mut self) -> AppResult<()> {
+        self.transition_to(Normal, HelpView)
     }
 
     /// Checks if currently in Edit mode
@@ -107,6 +225,16 @@ impl StateHolder {
         self.input_mode == Edit
     }
 
+    /// Checks if currently browsing a directory, typing a filter or not
+    pub fn is_search(&self) -> bool {
+        self.view_mode == Search
+    }
+
+    /// Checks if currently typing a search filter
+    pub fn is_search_edit(&self) -> bool {
+        self.input_mode == Edit && self.view_mode == Search
+    }
+
     /// Checks if currently viewing history
     pub fn is_history_search(&self) -> bool {
         self.view_mode == HistoryFolderView
@@ -117,17 +245,365 @@ impl StateHolder {
         self.view_mode == FileView
     }
 
-    /// Saves the current state for later restoration
-    fn save_previous_state(&mut self) {
-        self.prev_input_mode = self.input_mode;
-        self.prev_view_mode = self.view_mode;
+    /// Checks if currently browsing the git history of a file
+    pub fn is_file_history(&self) -> bool {
+        self.view_mode == FileHistoryView
+    }
+
+    /// Checks if currently picking a named mark
+    pub fn is_bookmark_view(&self) -> bool {
+        self.view_mode == BookmarkView
+    }
+
+    /// Checks if currently confirming a destructive operation
+    pub fn is_confirm_view(&self) -> bool {
+        self.view_mode == ConfirmView
+    }
+
+    /// Checks if currently typing the name of an entry or a shell command
+    pub fn is_prompt_view(&self) -> bool {
+        self.view_mode == PromptView
+    }
+
+    /// Checks if currently showing the output of a shell command
+    pub fn is_command_view(&self) -> bool {
+        self.view_mode == CommandView
+    }
+
+    /// Checks if currently listing key bindings
+    pub fn is_help_view(&self) -> bool {
+        self.view_mode == HelpView
     }
 
-    /// Restores the previous state
+    /// Checks if the opened file is shown, rather than a search opened over it
     ///
-    /// Useful for returning from file view to the previous mode
-    pub fn restore_previous_state(&mut self) {
-        self.input_mode = self.prev_input_mode;
-        self.view_mode = self.prev_view_mode;
+    /// Popups and pickers show what the mode they were opened from shows.
+    pub fn is_over_file_view(&self) -> bool {
+        std::iter::once(&(self.input_mode, self.view_mode))
+            .chain(self.mode_stack.iter().rev())
+            .find_map(|(_, view_mode)| match view_mode {
+                FileView | FileHistoryView => Some(true),
+                Search | HistoryFolderView => Some(false),
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the mode the current one was opened from (if nested)
+    pub fn previous_mode(&self) -> Option<Mode> {
+        self.mode_stack.last().copied()
+    }
+
+    /// Returns the number of nested modes below the current one
+    pub fn depth(&self) -> usize {
+        self.mode_stack.len()
+    }
+
+    /// Transitions to a mode following the transition table
+    ///
+    /// # Arguments
+    ///
+    /// * `input_mode` - Target input mode
+    /// * `view_mode` - Target view mode
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// transition is not declared in [`TRANSITIONS`]
+    pub fn transition_to(&mut self, input_mode: InputMode, view_mode: ViewMode) -> AppResult<()> {
+        let current = (self.input_mode, self.view_mode);
+        let target = (input_mode, view_mode);
+        match Self::lookup(current, target) {
+            Some(Switch) => (),
+            Some(Push) => self.mode_stack.push(current),
+            None => {
+                return Err(AppError::State(format!(
+                    "Illegal transition from {:?}+{:?} to {:?}+{:?}",
+                    current.0, current.1, input_mode, view_mode
+                )))
+            }
+        }
+        self.input_mode = input_mode;
+        self.view_mode = view_mode;
+        Ok(())
+    }
+
+    /// Leaves the current nested mode, returning to the one it was opened from
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// current mode is not nested
+    pub fn pop_mode(&mut self) -> AppResult<()> {
+        let (input_mode, view_mode) = self.mode_stack.pop().ok_or(AppError::State(format!(
+            "No mode to return to from {:?}+{:?}",
+            self.input_mode, self.view_mode
+        )))?;
+        self.input_mode = input_mode;
+        self.view_mode = view_mode;
+        Ok(())
+    }
+
+    /// Leaves every nested mode and switches to Normal+Search
+    ///
+    /// Used when jumping to another location from any mode
+    ///
+    /// # Returns
+    ///
+    /// Returns `AppResult<()>` which may contain `AppError::State` if the
+    /// outermost mode cannot switch to Normal+Search
+    pub fn unwind_to_search(&mut self) -> AppResult<()> {
+        while !self.mode_stack.is_empty() {
+            self.pop_mode()?;
+        }
+        if (self.input_mode, self.view_mode) == (Normal, Search) {
+            return Ok(());
+        }
+        self.to_search()
+    }
+
+    fn lookup(from: Mode, to: Mode) -> Option<Transition> {
+        TRANSITIONS
+            .iter()
+            .find(|(transition_from, transition_to, _)| {
+                *transition_from == from && *transition_to == to
+            })
+            .map(|(_, _, kind)| *kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT_MODES: [InputMode; 2] = [Normal, Edit];
+    const VIEW_MODES: [ViewMode; 9] = [
+        Search,
+        FileView,
+        HistoryFolderView,
+        FileHistoryView,
+        BookmarkView,
+        ConfirmView,
+        PromptView,
+        CommandView,
+        HelpView,
+    ];
+
+    fn state(input_mode: InputMode, view_mode: ViewMode) -> StateHolder {
+        StateHolder {
+            input_mode,
+            view_mode,
+            mode_stack: Vec::new(),
+        }
+    }
+
+    /// A step of a user journey: the mode transitioned to, `None` to go back
+    /// with [`StateHolder::pop_mode`], and the nesting depth expected after it
+    type Step = (Option<Mode>, usize);
+
+    /// Journeys through the modes, each starting at Normal+Search
+    const JOURNEYS: &[&[Step]] = &[
+        // type a filter, browse the history and come back
+        &[
+            (Some((Edit, Search)), 0),
+            (Some((Normal, Search)), 0),
+            (Some((Edit, HistoryFolderView)), 0),
+            (Some((Normal, Search)), 0),
+        ],
+        // open a file while typing, then close it
+        &[
+            (Some((Edit, Search)), 0),
+            (Some((Normal, FileView)), 1),
+            (None, 0),
+        ],
+        // browse the git history of a file
+        &[
+            (Some((Normal, FileView)), 1),
+            (Some((Normal, FileHistoryView)), 2),
+            (None, 1),
+            (None, 0),
+        ],
+        // pick a mark from the listing and from a file
+        &[
+            (Some((Edit, BookmarkView)), 1),
+            (None, 0),
+            (Some((Normal, FileView)), 1),
+            (Some((Edit, BookmarkView)), 2),
+            (None, 1),
+        ],
+        // type the time range of a log
+        &[
+            (Some((Normal, FileView)), 1),
+            (Some((Edit, FileView)), 2),
+            (None, 1),
+        ],
+        // confirm a delete, type a name, run a command
+        &[
+            (Some((Normal, ConfirmView)), 1),
+            (None, 0),
+            (Some((Edit, PromptView)), 1),
+            (None, 0),
+            (Some((Normal, CommandView)), 1),
+            (None, 0),
+        ],
+        // type and run a command on the opened file
+        &[
+            (Some((Normal, FileView)), 1),
+            (Some((Edit, PromptView)), 2),
+            (None, 1),
+            (Some((Normal, CommandView)), 2),
+            (None, 1),
+        ],
+        // search from a file, read the help and go back to the file
+        &[
+            (Some((Normal, FileView)), 1),
+            (Some((Edit, Search)), 2),
+            (Some((Normal, HelpView)), 3),
+            (None, 2),
+            (None, 1),
+            (Some((Normal, HelpView)), 2),
+            (None, 1),
+            (None, 0),
+        ],
+        // read the help of the listing, typing or not
+        &[
+            (Some((Normal, HelpView)), 1),
+            (None, 0),
+            (Some((Edit, Search)), 0),
+            (Some((Normal, HelpView)), 1),
+            (None, 0),
+        ],
+    ];
+
+    #[test]
+    fn test_journeys_use_every_transition() {
+        let mut used = Vec::new();
+        for journey in JOURNEYS {
+            let mut state_holder = state(Normal, Search);
+            for (step, depth) in journey.iter() {
+                let from = (state_holder.input_mode, state_holder.view_mode);
+                match step {
+                    Some((input_mode, view_mode)) => {
+                        state_holder.transition_to(*input_mode, *view_mode).unwrap();
+                        used.push((from, (*input_mode, *view_mode)));
+                    }
+                    None => state_holder.pop_mode().unwrap(),
+                }
+                assert_eq!(state_holder.depth(), *depth, "after {:?}", step);
+            }
+        }
+        // a transition no journey needs is not meant to be legal
+        for (from, to, _) in TRANSITIONS {
+            assert!(
+                used.contains(&(*from, *to)),
+                "{:?} to {:?} unused",
+                from,
+                to
+            );
+        }
+    }
+
+    #[test]
+    fn test_transition_table() {
+        for from_input in INPUT_MODES {
+            for from_view in VIEW_MODES {
+                for to_input in INPUT_MODES {
+                    for to_view in VIEW_MODES {
+                        let kind =
+                            StateHolder::lookup((from_input, from_view), (to_input, to_view));
+                        let mut state_holder = state(from_input, from_view);
+                        let result = state_holder.transition_to(to_input, to_view);
+                        match kind {
+                            None => {
+                                assert!(result.is_err());
+                                assert_eq!(state_holder, state(from_input, from_view));
+                            }
+                            Some(kind) => {
+                                assert!(result.is_ok());
+                                assert_eq!(
+                                    (state_holder.input_mode, state_holder.view_mode),
+                                    (to_input, to_view)
+                                );
+                                assert_eq!(state_holder.depth(), (kind == Push) as usize);
+                                if kind == Push {
+                                    assert_eq!(
+                                        state_holder.previous_mode(),
+                                        Some((from_input, from_view))
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_illegal_transitions() {
+        let mut state_holder = StateHolder::default();
+        assert!(state_holder.to_file_history().is_err());
+        assert!(state_holder.to_bookmark_search().is_err());
+        assert!(state_holder.pop_mode().is_err());
+
+        state_holder.to_search().unwrap();
+        state_holder.to_history_search().unwrap();
+        assert!(state_holder.to_file_view().is_err());
+        assert!(state_holder.to_search_edit().is_err());
+        assert!(state_holder.to_help().is_err());
+
+        state_holder.to_search().unwrap();
+        state_holder.to_help().unwrap();
+        assert!(state_holder.to_help().is_err());
+        assert!(state_holder.to_search_edit().is_err());
+    }
+
+    #[test]
+    fn test_nested_modes_unwind() {
+        let mut state_holder = StateHolder::default();
+        state_holder.to_search().unwrap();
+        state_holder.to_file_view().unwrap();
+        state_holder.to_file_history().unwrap();
+        assert_eq!(state_holder.depth(), 2);
+
+        state_holder.pop_mode().unwrap();
+        assert!(state_holder.is_file_view());
+        state_holder.to_bookmark_search().unwrap();
+        state_holder.pop_mode().unwrap();
+        assert!(state_holder.is_file_view());
+        state_holder.to_log_filter().unwrap();
+        assert!(state_holder.to_file_history().is_err());
+        state_holder.pop_mode().unwrap();
+        state_holder.pop_mode().unwrap();
+        assert_eq!(state_holder, state(Normal, Search));
+        assert!(state_holder.pop_mode().is_err());
+
+        // a search opened from a file hides it, down to the popups over it
+        state_holder.to_file_view().unwrap();
+        state_holder.to_search_edit().unwrap();
+        state_holder.to_search().unwrap();
+        assert!(!state_holder.is_over_file_view());
+        state_holder.to_prompt().unwrap();
+        assert!(!state_holder.is_over_file_view());
+        state_holder.pop_mode().unwrap();
+        state_holder.pop_mode().unwrap();
+        assert!(state_holder.is_over_file_view());
+        state_holder.to_prompt().unwrap();
+        assert!(state_holder.is_over_file_view());
+    }
+
+    #[test]
+    fn test_unwind_to_search() {
+        let mut state_holder = StateHolder::default();
+        state_holder.to_file_view().unwrap();
+        state_holder.to_bookmark_search().unwrap();
+        state_holder.unwind_to_search().unwrap();
+        assert_eq!(state_holder, state(Normal, Search));
+
+        let mut state_holder = StateHolder::default();
+        state_holder.to_search().unwrap();
+        state_holder.to_history_search().unwrap();
+        state_holder.unwind_to_search().unwrap();
+        assert_eq!(state_holder, state(Normal, Search));
     }
 }
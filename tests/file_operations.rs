@@ -0,0 +1,646 @@
+pub mod utils;
+#[cfg(test)]
+mod file_operations_tests {
+    use super::utils::*;
+    use crate::utils::TestFileSystem;
+    use athena_viewer::message_holder::clipboard_helper::osc52_sequence;
+    use std::fs;
+
+    #[test]
+    fn test_delete_asks_and_moves_to_trash() {
+        // setup: a directory with two files, and a trash outside of it
+        let fs = TestFileSystem::new();
+        fs.create_file("work/docs/a.txt", "aaaa");
+        fs.create_file("work/docs/b.txt", "bb");
+        fs.create_file("work/keep.txt", "keep");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_events(vec![
+            events::char('d'),
+            events::char('o'),
+            events::char('c'),
+            events::tab(),
+        ])
+        .unwrap();
+        assert_eq!(app.get_visible_items(), vec!["docs"]);
+
+        // the popup tells what would be deleted, cancelling keeps it
+        app.send_event(events::ctrl_d()).unwrap();
+        assert!(app.app.state_holder.borrow().is_confirm_view());
+        let text = app.rendered_text();
+        assert!(text.contains("Confirm delete"));
+        assert!(text.contains("2 files, 6 B"));
+        app.send_event(events::char('n')).unwrap();
+        assert!(app.is_normal_mode() && app.is_search_view());
+        assert!(fs.path().join("work/docs").exists());
+
+        // confirming moves it to the trash
+        app.send_events(vec![events::ctrl_d(), events::enter()])
+            .unwrap();
+        assert!(!fs.path().join("work/docs").exists());
+        assert_eq!(
+            fs::read_to_string(trash.path().join("Trash/files/docs/a.txt")).unwrap(),
+            "aaaa"
+        );
+        assert!(trash.path().join("Trash/info/docs.trashinfo").exists());
+        assert!(app.app.log_message.contains("to the trash"));
+        assert!(app.get_visible_items().is_empty());
+    }
+
+    #[test]
+    fn test_delete_permanently_and_report_errors() {
+        let fs = TestFileSystem::new();
+        fs.create_file("old.txt", "old");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::char('o'), events::char('l'), events::tab()])
+            .unwrap();
+        assert_eq!(app.get_visible_items(), vec!["old.txt"]);
+
+        // the file vanishing before the confirmation is reported, not ignored
+        app.send_event(events::ctrl_d()).unwrap();
+        fs::remove_file(fs.path().join("old.txt")).unwrap();
+        assert!(app.send_event(events::char('D')).is_err());
+        assert!(app.is_search_view());
+
+        fs.create_file("old.txt", "old");
+        app.send_events(vec![events::char('u'), events::ctrl_d(), events::char('D')])
+            .unwrap();
+        assert!(!fs.path().join("old.txt").exists());
+        assert!(app.app.log_message.starts_with("Deleted"));
+    }
+
+    #[test]
+    fn test_delete_measured_and_run_in_background() {
+        let fs = TestFileSystem::new();
+        fs.create_file("work/docs/a.txt", "aaaa");
+        fs.create_file("work/keep.txt", "keep");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_events(vec![events::char('d'), events::char('o'), events::tab()])
+            .unwrap();
+
+        // the popup opens before the items are measured
+        app.app
+            .handle_normal_search_event(events::ctrl_d())
+            .unwrap();
+        assert!(app.app.message_holder.is_deleting());
+        assert!(app.rendered_text().contains("Measuring..."));
+        app.finish_loading().unwrap();
+        assert!(app.rendered_text().contains("1 file, 4 B"));
+
+        // the items are trashed once the delete started is polled
+        app.app
+            .handle_normal_confirm_view_event(events::enter())
+            .unwrap();
+        assert!(app.app.log_message.starts_with("Moving "));
+        assert!(app.app.log_message.ends_with("docs to the trash"));
+        assert!(app.app.message_holder.delete_holder.is_some());
+        app.finish_loading().unwrap();
+        assert!(!app.app.message_holder.is_deleting());
+        assert!(!fs.path().join("work/docs").exists());
+        assert!(app.app.log_message.contains("to the trash"));
+        assert!(app.get_visible_items().is_empty());
+    }
+
+    #[test]
+    fn test_undo_and_redo_trash() {
+        let fs = TestFileSystem::new();
+        fs.create_file("work/notes.txt", "notes");
+        fs.create_file("work/keep.txt", "keep");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_event(events::tab()).unwrap();
+        assert!(app.send_event(events::char('U')).is_err());
+
+        app.send_events(vec![
+            events::down(),
+            events::down(),
+            events::ctrl_d(),
+            events::char('y'),
+        ])
+        .unwrap();
+        assert!(!fs.path().join("work/notes.txt").exists());
+        assert_eq!(app.get_visible_items(), vec!["..", "keep.txt"]);
+
+        // undo puts the file back and lists it again
+        app.send_event(events::char('U')).unwrap();
+        assert_eq!(
+            fs::read_to_string(fs.path().join("work/notes.txt")).unwrap(),
+            "notes"
+        );
+        assert!(app.app.log_message.starts_with("Undid trash"));
+        assert_eq!(app.get_visible_items(), vec!["..", "keep.txt", "notes.txt"]);
+
+        // redo trashes it again
+        app.send_event(events::ctrl_r()).unwrap();
+        assert!(!fs.path().join("work/notes.txt").exists());
+        assert_eq!(app.get_visible_items(), vec!["..", "keep.txt"]);
+        assert!(app.send_event(events::ctrl_r()).is_err());
+    }
+
+    /// types a name in the input
+    fn type_text(app: &mut TestApp, text: &str) {
+        app.send_events(text.chars().map(events::char).collect())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_rename_and_create_entries() {
+        let fs = TestFileSystem::new();
+        fs.create_file("draft.txt", "draft");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::tab(), events::down()])
+            .unwrap();
+
+        // the current name is edited in the input, the filter comes back after
+        app.send_event(events::char('r')).unwrap();
+        assert!(app.app.state_holder.borrow().is_prompt_view());
+        assert_eq!(app.get_search_input(), "draft.txt");
+        assert!(app.rendered_text().contains("Rename draft.txt"));
+        app.send_events(vec![events::backspace(); 9]).unwrap();
+        type_text(&mut app, "final.txt");
+        app.send_event(events::enter()).unwrap();
+        assert!(app.is_normal_mode() && app.is_search_view());
+        assert_eq!(app.get_search_input(), "");
+        assert_eq!(
+            fs::read_to_string(fs.path().join("final.txt")).unwrap(),
+            "draft"
+        );
+        assert_eq!(app.get_visible_items(), vec!["..", "final.txt"]);
+
+        // renames can be undone
+        app.send_event(events::char('U')).unwrap();
+        assert!(fs.path().join("draft.txt").exists());
+        assert_eq!(app.get_visible_items(), vec!["..", "draft.txt"]);
+
+        // new entries are selected once created
+        app.send_event(events::char('A')).unwrap();
+        type_text(&mut app, "notes");
+        app.send_event(events::enter()).unwrap();
+        assert!(fs.path().join("notes").is_dir());
+        app.send_event(events::char('a')).unwrap();
+        type_text(&mut app, "todo.md");
+        app.send_event(events::enter()).unwrap();
+        assert!(fs.path().join("todo.md").is_file());
+        assert_eq!(app.app.message_holder.raw_highlight_index, 3);
+
+        // existing and invalid names are refused
+        app.send_event(events::char('a')).unwrap();
+        type_text(&mut app, "notes");
+        let error = app.send_event(events::enter()).unwrap_err();
+        assert!(error.to_string().contains("exists already"));
+        app.send_event(events::char('r')).unwrap();
+        assert_eq!(app.get_search_input(), "todo.md");
+        app.send_events(vec![events::backspace(); 7]).unwrap();
+        type_text(&mut app, "notes");
+        let error = app.send_event(events::enter()).unwrap_err();
+        assert!(error.to_string().contains("exists already"));
+        assert!(fs.path().join("todo.md").is_file());
+        assert!(fs.path().join("notes").is_dir());
+        app.send_event(events::char('a')).unwrap();
+        type_text(&mut app, "a/b");
+        assert!(app.send_event(events::enter()).is_err());
+        app.send_events(vec![events::char('a'), events::escape()])
+            .unwrap();
+        assert!(app.is_search_view());
+    }
+
+    #[test]
+    fn test_copy_cut_and_paste() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/report.txt", "new report");
+        fs.create_file("src/data/values.csv", "1,2");
+        fs.create_file("dest/report.txt", "old report");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        let enter = |app: &mut TestApp, name: &str| {
+            app.send_event(events::tab()).unwrap();
+            type_text(app, name);
+            app.send_events(vec![events::tab(), events::enter()])
+                .unwrap();
+        };
+        let select = |app: &mut TestApp, name: &str| {
+            let items = app.get_visible_items();
+            let index = items.iter().position(|item| item == name).unwrap();
+            app.app.message_holder.raw_highlight_index = index as i32;
+        };
+        app.send_event(events::tab()).unwrap();
+        enter(&mut app, "src");
+        assert_eq!(app.get_visible_items(), vec!["..", "data", "report.txt"]);
+
+        // the clipboard is kept while navigating
+        select(&mut app, "data");
+        app.send_event(events::char('y')).unwrap();
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "dest");
+        app.send_event(events::char('p')).unwrap();
+        assert_eq!(
+            fs::read_to_string(fs.path().join("dest/data/values.csv")).unwrap(),
+            "1,2"
+        );
+        assert!(app.app.log_message.starts_with("Copied 1 items"));
+
+        // pasting over an existing item asks what to do
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "src");
+        select(&mut app, "report.txt");
+        app.send_event(events::char('x')).unwrap();
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "dest");
+        app.send_event(events::char('p')).unwrap();
+        assert!(app.app.state_holder.borrow().is_confirm_view());
+        assert!(app.rendered_text().contains("Paste conflict"));
+        app.send_event(events::char('k')).unwrap();
+        assert_eq!(
+            fs::read_to_string(fs.path().join("dest/report (2).txt")).unwrap(),
+            "new report"
+        );
+        assert!(!fs.path().join("src/report.txt").exists());
+        assert!(app.app.message_holder.clipboard.is_none());
+        let mut items = app.get_visible_items();
+        items.sort();
+        assert_eq!(items, vec!["..", "data", "report (2).txt", "report.txt"]);
+
+        // moves are undone like renames
+        app.send_event(events::char('U')).unwrap();
+        assert!(fs.path().join("src/report.txt").exists());
+
+        // overwriting moves the existing item to the trash
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "src");
+        select(&mut app, "report.txt");
+        app.send_event(events::char('y')).unwrap();
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "dest");
+        app.send_events(vec![events::char('p'), events::char('o')])
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(fs.path().join("dest/report.txt")).unwrap(),
+            "new report"
+        );
+        assert!(trash.path().join("Trash/files/report.txt").exists());
+
+        // an overwriting move is undone at once, with the overwritten item
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "src");
+        select(&mut app, "report.txt");
+        app.send_event(events::char('x')).unwrap();
+        app.send_event(events::ctrl_k()).unwrap();
+        enter(&mut app, "dest");
+        app.send_events(vec![events::char('p'), events::char('o')])
+            .unwrap();
+        assert!(!fs.path().join("src/report.txt").exists());
+        app.send_event(events::char('U')).unwrap();
+        assert!(fs.path().join("src/report.txt").exists());
+        assert!(fs.path().join("dest/report.txt").exists());
+
+        // a directory cannot be pasted into itself
+        select(&mut app, "data");
+        app.send_event(events::char('y')).unwrap();
+        enter(&mut app, "data");
+        assert!(app.send_event(events::char('p')).is_err());
+    }
+
+    #[test]
+    fn test_overwrite_holding_source_refused() {
+        let fs = TestFileSystem::new();
+        fs.create_file("a/a/note.txt", "note");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_events(vec![events::char('a'), events::tab(), events::enter()])
+            .unwrap();
+        assert_eq!(app.get_visible_items(), vec!["..", "a"]);
+
+        // the existing a holds the cut a, overwriting it would trash the source
+        app.app.message_holder.raw_highlight_index = 1;
+        app.send_events(vec![events::char('x'), events::ctrl_k(), events::char('p')])
+            .unwrap();
+        assert!(app.app.state_holder.borrow().is_confirm_view());
+        let error = app.send_event(events::char('o')).unwrap_err();
+        assert!(error.to_string().contains("it holds"));
+        assert_eq!(
+            fs::read_to_string(fs.path().join("a/a/note.txt")).unwrap(),
+            "note"
+        );
+        assert!(!trash.path().join("Trash/files/a").exists());
+    }
+
+    #[test]
+    fn test_selection_in_expanded_tree() {
+        let fs = TestFileSystem::new();
+        fs.create_file("work/d/inner.txt", "inner");
+        fs.create_file("work/z.txt", "z");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_events(vec![events::tab(), events::down(), events::char('l')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "d", "d/inner.txt", "z.txt"]
+        );
+
+        // a file inside a selected directory is not handled a second time
+        app.send_event(events::char('V')).unwrap();
+        assert_eq!(
+            app.app.message_holder.folder_holder.multi_selection.len(),
+            3
+        );
+        let paths = app.app.message_holder.selected_paths().unwrap();
+        assert_eq!(
+            paths,
+            vec![fs.path().join("work/d"), fs.path().join("work/z.txt")]
+        );
+        app.send_event(events::ctrl_d()).unwrap();
+        assert!(app.rendered_text().contains("Delete 2 items, 1 directory?"));
+        app.send_event(events::enter()).unwrap();
+        assert!(!fs.path().join("work/d").exists());
+        assert!(!fs.path().join("work/z.txt").exists());
+        assert_eq!(
+            fs::read_to_string(trash.path().join("Trash/files/d/inner.txt")).unwrap(),
+            "inner"
+        );
+        assert!(app.app.log_message.contains("2 items"));
+    }
+
+    #[test]
+    fn test_multi_selection_batch_operations() {
+        let fs = TestFileSystem::new();
+        fs.create_file("work/a.txt", "a");
+        fs.create_file("work/b.txt", "b");
+        fs.create_file("work/c.md", "c");
+        let trash = TestFileSystem::new();
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        app.app.message_holder.trash_holder.trash_dir = trash.path().join("Trash");
+        app.send_event(events::tab()).unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "a.txt", "b.txt", "c.md"]
+        );
+
+        // space selects and moves down, ".." cannot be selected
+        app.send_events(vec![events::char(' '), events::char(' ')])
+            .unwrap();
+        assert_eq!(
+            app.app.message_holder.folder_holder.multi_selection.len(),
+            1
+        );
+        assert!(app.rendered_text().contains("(1 selected)"));
+
+        // the selection survives filtering, inverting only touches what is listed
+        app.send_events(vec![
+            events::tab(),
+            events::char('t'),
+            events::char('x'),
+            events::tab(),
+        ])
+        .unwrap();
+        assert_eq!(app.get_visible_items(), vec!["a.txt", "b.txt"]);
+        app.send_event(events::char('v')).unwrap();
+        let selection = &app.app.message_holder.folder_holder.multi_selection;
+        assert_eq!(
+            selection.iter().cloned().collect::<Vec<_>>(),
+            vec![fs.path().join("work/b.txt")]
+        );
+        app.send_event(events::char('V')).unwrap();
+        assert_eq!(
+            app.app.message_holder.folder_holder.multi_selection.len(),
+            2
+        );
+
+        // the selection is deleted at once, and restored by one undo
+        app.send_event(events::ctrl_d()).unwrap();
+        let text = app.rendered_text();
+        assert!(text.contains("Delete 2 items"));
+        assert!(text.contains("2 files, 2 B"));
+        app.send_event(events::enter()).unwrap();
+        assert!(!fs.path().join("work/a.txt").exists());
+        assert!(!fs.path().join("work/b.txt").exists());
+        assert!(app.app.log_message.contains("2 items"));
+        assert!(app
+            .app
+            .message_holder
+            .folder_holder
+            .multi_selection
+            .is_empty());
+        app.send_event(events::char('U')).unwrap();
+        assert!(fs.path().join("work/a.txt").exists());
+        assert!(fs.path().join("work/b.txt").exists());
+        assert!(app.app.log_message.starts_with("Undid 2 file operations"));
+
+        // the selection is copied at once, escape clears it
+        app.send_events(vec![events::char('V'), events::char('y')])
+            .unwrap();
+        assert!(app.app.log_message.starts_with("Copied 2 items"));
+
+        // the paths of the selection are yanked to the terminal clipboard
+        app.send_event(events::char('Y')).unwrap();
+        assert_eq!(app.app.log_message, "Copied 2 paths");
+        let mut output = Vec::new();
+        app.app.handle_clipboard(&mut output).unwrap();
+        let paths = format!(
+            "{}\n{}",
+            fs.path().join("work/a.txt").display(),
+            fs.path().join("work/b.txt").display()
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), osc52_sequence(&paths));
+        app.send_event(events::escape()).unwrap();
+        assert_eq!(app.app.log_message, "Selection cleared");
+        assert!(app
+            .app
+            .message_holder
+            .folder_holder
+            .multi_selection
+            .is_empty());
+    }
+
+    #[test]
+    fn test_bulk_rename_in_editor() {
+        let fs = TestFileSystem::new();
+        fs.create_file("a.txt", "a");
+        fs.create_file("b.txt", "b");
+        fs.create_file("notes.md", "notes");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::char('t'), events::char('x'), events::tab()])
+            .unwrap();
+
+        // the filtered names are listed, the swap is previewed before it runs
+        app.send_event(events::char('R')).unwrap();
+        app.edit_in_editor(|text| {
+            assert_eq!(text, "a.txt\nb.txt\n");
+            "b.txt\na.txt\n".into()
+        })
+        .unwrap();
+        assert!(app.app.state_holder.borrow().is_confirm_view());
+        let text = app.rendered_text();
+        assert!(text.contains("Confirm rename"));
+        assert!(text.contains("a.txt -> b.txt"));
+        assert!(text.contains("1 cycles go through a temporary name"));
+        app.send_event(events::enter()).unwrap();
+        assert_eq!(fs::read_to_string(fs.path().join("a.txt")).unwrap(), "b");
+        assert_eq!(fs::read_to_string(fs.path().join("b.txt")).unwrap(), "a");
+        assert_eq!(app.app.log_message, "Renamed 2 items");
+
+        // one undo reverts the whole rename
+        app.send_event(events::char('U')).unwrap();
+        assert_eq!(fs::read_to_string(fs.path().join("a.txt")).unwrap(), "a");
+        let mut items = app.get_visible_items();
+        items.sort();
+        assert_eq!(items, vec!["a.txt", "b.txt"]);
+
+        // collisions are refused, nothing is renamed
+        app.send_event(events::char('R')).unwrap();
+        assert!(app.edit_in_editor(|_| "notes.md\nb.txt\n".into()).is_err());
+        app.send_event(events::char('R')).unwrap();
+        assert!(app.edit_in_editor(|_| "c.txt\nc.txt\n".into()).is_err());
+        assert!(app.is_search_view());
+        assert!(fs.path().join("a.txt").exists());
+        assert!(fs.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_open_in_editor_and_reload() {
+        let fs = TestFileSystem::new();
+        fs.create_file("notes.txt", "one\ntwo\nthree\n");
+        fs.create_file("todo.txt", "todo");
+        fs.create_dir("docs");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('n'),
+            events::char('o'),
+            events::char('t'),
+            events::enter(),
+        ])
+        .unwrap();
+        assert!(app.is_file_view());
+
+        // the editor opens the file at the first line shown
+        app.send_events(vec![events::tab(), events::down(), events::char('e')])
+            .unwrap();
+        let request = app.app.pending_editor.clone().unwrap();
+        assert_eq!(request.paths, vec![fs.path().join("notes.txt")]);
+        assert_eq!(request.line, Some(2));
+
+        // the file is read again once edited, at the same place
+        let n_rows = app
+            .app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .unwrap()
+            .n_rows;
+        app.edit_in_editor(|text| format!("{}four\nfive\n", text))
+            .unwrap();
+        assert!(app.is_file_view());
+        let file_text_info = app.app.message_holder.file_text_info.as_ref().unwrap();
+        assert_eq!(file_text_info.n_rows, n_rows + 2);
+        assert_eq!(app.get_scroll_positions().0, 1);
+        assert!(app.app.log_message.starts_with("Edited"));
+
+        // in the folder view the selected files are opened, not the directories
+        app.send_event(events::char('q')).unwrap();
+        // clears the filter kept from before the file was opened
+        app.send_events(vec![events::char('t'), events::backspace()])
+            .unwrap();
+        app.send_events(vec![events::tab(), events::char('V'), events::char('o')])
+            .unwrap();
+        let request = app.app.pending_editor.clone().unwrap();
+        assert_eq!(request.line, None);
+        assert_eq!(request.paths.len(), 2);
+        app.edit_in_editor(|text| text.into()).unwrap();
+        assert!(app
+            .app
+            .log_message
+            .ends_with("Edited 2 files, skipped 1 directory"));
+
+        // only directories are refused
+        app.send_events(vec![events::escape(), events::tab()])
+            .unwrap();
+        type_text(&mut app, "doc");
+        app.send_events(vec![events::tab(), events::char('V')])
+            .unwrap();
+        assert_eq!(app.get_visible_items(), vec!["docs"]);
+        assert!(app.send_event(events::char('o')).is_err());
+    }
+
+    #[test]
+    fn test_run_command_on_selection() {
+        let fs = TestFileSystem::new();
+        fs.create_file("a.txt", "aaaa");
+        fs.create_file("b.txt", "bb");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::tab(), events::char('V'), events::char('!')])
+            .unwrap();
+        assert!(app.is_edit_mode());
+
+        // the selected paths replace %s, the output is shown once read
+        let typed = "wc -c %s; touch made.txt";
+        app.send_events(typed.chars().map(events::char).collect())
+            .unwrap();
+        app.send_event(events::enter()).unwrap();
+        assert!(app.app.state_holder.borrow().is_command_view());
+        let command_holder = app.app.message_holder.command_holder.as_ref().unwrap();
+        assert!(command_holder
+            .lines
+            .iter()
+            .any(|line| line.contains("a.txt")));
+        assert!(command_holder
+            .lines
+            .iter()
+            .any(|line| line.contains("total")));
+        assert_eq!(
+            app.app.log_message,
+            format!("{} ended with exit code 0", command_holder.command.command)
+        );
+        assert!(app.rendered_text().contains("b.txt"));
+
+        // the output scrolls like a file
+        app.send_events(vec![
+            events::char('j'),
+            events::char('j'),
+            events::char('k'),
+        ])
+        .unwrap();
+        let command_holder = app.app.message_holder.command_holder.as_ref().unwrap();
+        assert_eq!(command_holder.vertical_scroll, 1);
+
+        // the listing shows the files the command created
+        app.send_event(events::char('q')).unwrap();
+        assert!(app.is_search_view());
+        assert!(app.app.message_holder.command_holder.is_none());
+        assert!(app.get_visible_items().contains(&"made.txt".to_string()));
+
+        // %l has no value outside of the file view
+        app.send_event(events::char('!')).unwrap();
+        app.send_events("echo %l".chars().map(events::char).collect())
+            .unwrap();
+        assert!(app.send_event(events::enter()).is_err());
+        assert!(app.is_search_view());
+
+        // the parent directory shortcut is reported rather than a missing selection
+        app.send_event(events::escape()).unwrap();
+        app.app.message_holder.raw_highlight_index = 0;
+        app.send_event(events::char('!')).unwrap();
+        app.send_events("cat %s".chars().map(events::char).collect())
+            .unwrap();
+        let error = app.send_event(events::enter()).unwrap_err();
+        assert!(error.to_string().contains("parent directory shortcut"));
+    }
+}
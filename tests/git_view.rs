@@ -0,0 +1,73 @@
+pub mod utils;
+#[cfg(test)]
+mod git_view_tests {
+    use super::utils::*;
+    use crate::utils::TestFileSystem;
+    use athena_viewer::state_holder::ViewMode;
+
+    #[test]
+    fn test_file_history_view() {
+        // setup: a file changed by three commits
+        let fs = TestFileSystem::new();
+        fs.git(&["init", "-q"]);
+        fs.create_file("notes.txt", "first\n");
+        fs.git(&["add", "notes.txt"]);
+        fs.git(&["commit", "-q", "-m", "add notes"]);
+        fs.create_file("notes.txt", "second\n");
+        fs.git(&["commit", "-q", "-a", "-m", "rewrite notes"]);
+        let long_notes: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        fs.create_file("notes.txt", &long_notes);
+        fs.git(&["commit", "-q", "-a", "-m", "grow notes"]);
+
+        // open the file, then its history
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::char('n'), events::char('o'), events::enter()])
+            .unwrap();
+        assert!(app.is_file_view());
+        app.send_event(events::char('g')).unwrap();
+        assert_eq!(app.get_view_mode(), ViewMode::FileHistoryView);
+
+        // commits are listed newest first
+        let file_history_holder = app.app.message_holder.file_history_holder.as_ref().unwrap();
+        let subjects: Vec<&str> = file_history_holder
+            .commits
+            .iter()
+            .map(|commit| commit.subject.as_str())
+            .collect();
+        assert_eq!(subjects, vec!["grow notes", "rewrite notes", "add notes"]);
+        let text = app.rendered_text();
+        assert!(text.contains("History of"));
+        assert!(text.contains("rewrite notes"));
+
+        // the content of an older commit
+        app.send_events(vec![events::char('j'), events::enter()])
+            .unwrap();
+        assert!(app.rendered_text().contains("second"));
+        app.send_event(events::char('q')).unwrap();
+
+        // the newest content scrolls by line and by page
+        app.send_events(vec![events::char('k'), events::enter()])
+            .unwrap();
+        app.send_events(vec![events::char('j'), events::page_down()])
+            .unwrap();
+        let file_history_holder = app.app.message_holder.file_history_holder.as_ref().unwrap();
+        assert_eq!(file_history_holder.vertical_scroll, 31);
+        app.send_event(events::end()).unwrap();
+        let file_history_holder = app.app.message_holder.file_history_holder.as_ref().unwrap();
+        let n_rows = file_history_holder.revision.as_ref().unwrap().n_rows;
+        assert_eq!(file_history_holder.vertical_scroll, n_rows - 30);
+        assert!(app.rendered_text().contains("line 71"));
+
+        // the diff of the commit
+        app.send_event(events::char('d')).unwrap();
+        let file_history_holder = app.app.message_holder.file_history_holder.as_ref().unwrap();
+        assert!(file_history_holder.show_diff);
+        assert_eq!(file_history_holder.vertical_scroll, 0);
+        assert!(app.rendered_text().contains("+line 0"));
+
+        // back to the list, then to the file
+        app.send_events(vec![events::char('q'), events::char('q')])
+            .unwrap();
+        assert!(app.is_file_view());
+    }
+}
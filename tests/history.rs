@@ -45,7 +45,7 @@ mod history_tests {
         ])
         .unwrap();
 
-        app.send_events(vec![events::tab(), events::char('h')])
+        app.send_events(vec![events::tab(), events::char('H')])
             .unwrap();
         assert!(app.is_history_view());
         let mut history = Vec::new();
@@ -95,7 +95,7 @@ mod history_tests {
         ])
         .unwrap();
 
-        app.send_events(vec![events::tab(), events::char('h')])
+        app.send_events(vec![events::tab(), events::char('H')])
             .unwrap();
         assert!(app.is_history_view());
         let mut history = Vec::new();
@@ -120,4 +120,176 @@ mod history_tests {
         app.send_events(vec![events::enter()]).unwrap();
         assert_eq!(app.get_visible_items(), history);
     }
+
+    #[test]
+    fn test_history_persisted_across_sessions() {
+        use athena_viewer::message_holder::history_store::HistoryStore;
+
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+        let state_dir = TestFileSystem::new();
+        let history_store = HistoryStore::new(state_dir.path().join("history"), 10);
+
+        // first session: visit src and src/nested
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.load_history(history_store.clone()).unwrap();
+        app.send_events(vec![
+            events::char('s'),
+            events::char('r'),
+            events::char('c'),
+            events::enter(),
+            events::char('n'),
+            events::char('e'),
+            events::char('s'),
+            events::enter(),
+        ])
+        .unwrap();
+        app.app.message_holder.save_history().unwrap();
+
+        // second session starts at the root, history comes back in LRU order
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.load_history(history_store).unwrap();
+        app.send_events(vec![events::tab(), events::char('H')])
+            .unwrap();
+        let mut history = Vec::new();
+        history.push(fs.path().to_str().unwrap().to_string());
+        for s in ["src/nested", "src"] {
+            history.push(format!("{}/{}", fs.path().display(), s));
+        }
+        assert_eq!(app.get_visible_items(), history);
+
+        // removed folders are pruned when selected
+        fs.remove_folder("src/nested");
+        app.send_events(vec![events::down(), events::enter()])
+            .unwrap();
+        history.remove(1);
+        assert_eq!(app.get_visible_items(), history);
+
+        // restored folders are read when visited
+        app.send_event(events::enter()).unwrap();
+        assert!(app.is_search_view());
+        assert_eq!(app.get_visible_items(), vec!["..", "lib.rs", "module.rs"]);
+    }
+
+    #[test]
+    fn test_history_drops_missing_folders_on_load() {
+        use athena_viewer::message_holder::file_sorter::SortMode;
+        use athena_viewer::message_holder::history_store::{HistoryEntry, HistoryStore, VisitInfo};
+        use std::path::PathBuf;
+
+        // setup: a history shared with another root, with a removed folder
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+        let other = TestFileSystem::new();
+        other.create_dir("a");
+        let state_dir = TestFileSystem::new();
+        let history_store = HistoryStore::new(state_dir.path().join("history"), 4);
+        let entry = |path: PathBuf| HistoryEntry {
+            path,
+            sort_mode: SortMode::default(),
+            visit_info: VisitInfo::default(),
+        };
+        history_store
+            .save(&[
+                entry(other.path().join("a")),
+                entry(fs.path().join("missing")),
+                entry(fs.path().join("src")),
+                entry(other.path().join("removed")),
+            ])
+            .unwrap();
+
+        // removed folders are dropped when loaded
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.load_history(history_store.clone()).unwrap();
+        app.send_events(vec![events::tab(), events::char('H')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec![
+                fs.path().display().to_string(),
+                fs.path().join("src").display().to_string(),
+            ]
+        );
+
+        // other roots are saved back, without the removed ones
+        app.app.message_holder.save_history().unwrap();
+        let paths: Vec<PathBuf> = history_store
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                fs.path().to_path_buf(),
+                fs.path().join("src"),
+                other.path().join("a"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_keeps_other_roots_in_recency_order() {
+        use athena_viewer::message_holder::file_sorter::SortMode;
+        use athena_viewer::message_holder::history_store::{HistoryEntry, HistoryStore, VisitInfo};
+        use std::path::PathBuf;
+
+        // setup: a history shared with another root, with a removed folder
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+        let other = TestFileSystem::new();
+        other.create_dir("a");
+        other.create_dir("b");
+        let state_dir = TestFileSystem::new();
+        let history_store = HistoryStore::new(state_dir.path().join("history"), 4);
+        let entry = |path: PathBuf, last_visit| HistoryEntry {
+            path,
+            sort_mode: SortMode::default(),
+            visit_info: VisitInfo {
+                count: 1,
+                last_visit,
+            },
+        };
+        history_store
+            .save(&[
+                entry(other.path().join("a"), 300),
+                entry(fs.path().join("missing"), 250),
+                entry(fs.path().join("src"), 200),
+                entry(other.path().join("b"), 100),
+            ])
+            .unwrap();
+
+        // removed folders are dropped when loaded
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.load_history(history_store.clone()).unwrap();
+        app.send_events(vec![events::tab(), events::char('H')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec![
+                fs.path().display().to_string(),
+                fs.path().join("src").display().to_string(),
+            ]
+        );
+
+        // other roots are saved back between folders by last visit
+        app.app.message_holder.save_history().unwrap();
+        let paths: Vec<PathBuf> = history_store
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                fs.path().to_path_buf(),
+                other.path().join("a"),
+                fs.path().join("src"),
+                other.path().join("b"),
+            ]
+        );
+    }
 }
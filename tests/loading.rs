@@ -0,0 +1,94 @@
+pub mod utils;
+#[cfg(test)]
+mod loading_tests {
+    use super::utils::*;
+    use crate::utils::TestFileSystem;
+    use std::time::Duration;
+
+    #[test]
+    fn test_load_directory_in_background() {
+        // setup: a directory with a few files
+        let fs = TestFileSystem::new();
+        fs.create_file("big/a.txt", "a");
+        fs.create_file("big/b.txt", "b");
+        fs.create_file("big/c.txt", "c");
+
+        // never wait for reads, so they stay in progress until polled
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.message_holder.folder_holder.load_budget = Duration::ZERO;
+        app.send_events(vec![
+            events::char('b'),
+            events::char('i'),
+            events::char('g'),
+        ])
+        .unwrap();
+        app.app.handle_edit_search_event(events::enter()).unwrap();
+
+        // the directory is entered right away and read in the background
+        assert_eq!(app.get_current_directory(), fs.path().join("big"));
+        assert!(app.app.message_holder.folder_holder.is_loading());
+        assert!(app.rendered_text().contains("loading"));
+
+        // polling lists the entries once read
+        app.finish_loading().unwrap();
+        assert!(!app.rendered_text().contains("loading"));
+        let items = app.get_visible_items();
+        assert_eq!(items.len(), 4);
+        assert!(items.contains(&"c.txt".to_string()));
+
+        // a refresh can be cancelled, keeping the current listing
+        app.send_event(events::tab()).unwrap();
+        assert!(app.is_normal_mode());
+        app.app
+            .handle_normal_search_event(events::char('u'))
+            .unwrap();
+        assert!(app.app.message_holder.folder_holder.is_loading());
+        app.app
+            .handle_normal_search_event(events::escape())
+            .unwrap();
+        assert!(!app.app.message_holder.folder_holder.is_loading());
+        assert_eq!(app.app.log_message, "Loading cancelled");
+        assert_eq!(app.get_visible_items().len(), 4);
+    }
+
+    #[test]
+    fn test_expand_to_depth_with_budget() {
+        // setup: directories two levels deep
+        let fs = TestFileSystem::new();
+        fs.create_file("d1/x.txt", "x");
+        fs.create_file("d1/sub/y.txt", "y");
+        fs.create_file("d2/z.txt", "z");
+        fs.create_file("top.txt", "top");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::tab(), events::char('E'), events::char('2')])
+            .unwrap();
+        let mut items = app.get_visible_items();
+        items.sort();
+        assert_eq!(
+            items,
+            vec!["..", "d1/sub/y.txt", "d1/x.txt", "d2/z.txt", "top.txt"]
+        );
+        assert!(!app.rendered_text().contains("truncated"));
+
+        // back to the plain listing
+        app.send_events(vec![events::char('E'), events::char('0')])
+            .unwrap();
+        let mut items = app.get_visible_items();
+        items.sort();
+        assert_eq!(items, vec!["..", "d1", "d2", "top.txt"]);
+
+        // the expansion stops at the entry budget, before a whole directory
+        // in name order, and the directories cut off stay listed
+        app.app.message_holder.folder_holder.expand_budget = 4;
+        app.send_event(events::char('e')).unwrap();
+        let mut items = app.get_visible_items();
+        items.sort();
+        assert_eq!(items, vec!["..", "d1/sub", "d1/x.txt", "d2", "top.txt"]);
+        assert!(app.rendered_text().contains("truncated at 4 entries"));
+
+        // collapsing clears the notice
+        app.send_event(events::char('c')).unwrap();
+        assert!(!app.rendered_text().contains("truncated"));
+    }
+}
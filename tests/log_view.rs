@@ -0,0 +1,99 @@
+pub mod utils;
+#[cfg(test)]
+mod log_view_tests {
+    use super::utils::*;
+    use crate::utils::TestFileSystem;
+    use athena_viewer::state_holder::{InputMode, ViewMode};
+
+    /// returns the displayed lines of the opened file
+    fn shown_lines(app: &TestApp) -> Vec<String> {
+        app.app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .unwrap()
+            .formatted_text
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_log_view_filters() {
+        // setup: a log with several levels
+        let fs = TestFileSystem::new();
+        fs.create_file(
+            "service.log",
+            "2024-05-01 10:00:00 INFO started\n\
+             2024-05-01 10:05:00 ERROR failed\n\
+             2024-05-01 10:10:00 WARN retrying\n",
+        );
+
+        // log files open in the log view
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('s'),
+            events::char('e'),
+            events::char('r'),
+            events::enter(),
+        ])
+        .unwrap();
+        assert!(app.app.message_holder.log_holder.is_some());
+        // like the plain view, the empty last line is shown
+        assert_eq!(shown_lines(&app).len(), 4);
+
+        // cycle the minimum level up to WARN
+        app.send_events(vec![
+            events::char('v'),
+            events::char('v'),
+            events::char('v'),
+        ])
+        .unwrap();
+        assert_eq!(
+            shown_lines(&app),
+            vec![
+                "2024-05-01 10:05:00 ERROR failed",
+                "2024-05-01 10:10:00 WARN retrying",
+                ""
+            ]
+        );
+
+        // type a time range
+        app.send_event(events::char('t')).unwrap();
+        assert_eq!(
+            (app.get_input_mode(), app.get_view_mode()),
+            (InputMode::Edit, ViewMode::FileView)
+        );
+        for c in "2024-05-01 10:06..".chars() {
+            app.send_event(events::char(c)).unwrap();
+        }
+        app.send_event(events::enter()).unwrap();
+        assert!(app.is_file_view() && app.is_normal_mode());
+        assert_eq!(
+            shown_lines(&app),
+            vec!["2024-05-01 10:10:00 WARN retrying", ""]
+        );
+
+        // an invalid range keeps the filter
+        app.send_event(events::char('t')).unwrap();
+        app.send_event(events::char('x')).unwrap();
+        assert!(app.send_event(events::enter()).is_err());
+        app.send_event(events::escape()).unwrap();
+        assert!(app.is_normal_mode());
+        assert_eq!(shown_lines(&app).len(), 2);
+
+        // the plain view shows every line
+        app.send_event(events::char('L')).unwrap();
+        assert!(app.app.message_holder.log_holder.is_none());
+        assert_eq!(
+            app.app
+                .message_holder
+                .file_text_info
+                .as_ref()
+                .unwrap()
+                .n_rows,
+            4
+        );
+        assert!(app.send_event(events::char('v')).is_err());
+    }
+}
@@ -3,6 +3,8 @@ pub mod utils;
 mod navigation_tests {
     use super::utils::*;
     use crate::utils::TestFileSystem;
+    use athena_viewer::message_holder::preview_holder::PREVIEW_DEBOUNCE;
+    use athena_viewer::state_holder::{InputMode, ViewMode};
 
     /// only test the backend, no ui involved
     #[test]
@@ -191,7 +193,8 @@ mod navigation_tests {
         assert!(app.is_normal_mode());
         app.send_event(events::char('e')).unwrap();
 
-        let mut visible_items_exp = vec![
+        // listing order, each directory replaced by its content
+        let visible_items_exp = vec![
             "..",
             ".gitkeep",
             "README.md",
@@ -200,10 +203,625 @@ mod navigation_tests {
             "src/module.rs",
             "src/nested",
         ];
-        visible_items_exp.sort();
         let visible_items_act = app.get_visible_items();
 
         assert_eq!(visible_items_act, visible_items_exp);
-        // navigate down to and enter 'src/' directory
+
+        // refreshing re-reads the folder at the same expansion
+        fs.create_file("src/added.rs", "");
+        app.send_event(events::char('u')).unwrap();
+        let mut visible_items_exp = visible_items_exp;
+        visible_items_exp.insert(4, "src/added.rs");
+        assert_eq!(app.get_visible_items(), visible_items_exp);
+    }
+
+    #[test]
+    fn test_folder_expand_keeps_directory_position() {
+        // setup: b/ sorts between the files a.txt and c.txt
+        let fs = TestFileSystem::new();
+        fs.create_file("a.txt", "");
+        fs.create_file("b/x.txt", "");
+        fs.create_file("c.txt", "");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::tab(), events::char('e')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "a.txt", "b/x.txt", "c.txt"]
+        );
+
+        // reverse order applies to the expanded content in place
+        app.send_event(events::char('S')).unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "c.txt", "b/x.txt", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn test_folder_sort_directories_first() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![events::tab(), events::char('D')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "empty", "src", ".gitkeep", "README.md", "main.rs"]
+        );
+
+        // reverse keeps ".." and directories on top
+        app.send_event(events::char('S')).unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "src", "empty", "main.rs", "README.md", ".gitkeep"]
+        );
+
+        // sort mode is remembered when coming back to the folder
+        app.send_events(vec![
+            events::tab(),
+            events::char('s'),
+            events::char('r'),
+            events::char('c'),
+            events::enter(),
+            events::tab(),
+            events::ctrl_k(),
+        ])
+        .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "src", "empty", "main.rs", "README.md", ".gitkeep"]
+        );
+    }
+
+    #[test]
+    fn test_bookmark_outside_start_directory() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+        let store_path =
+            fs.create_file("bookmarks", &format!("o\t{}\t0\t0\n", fs.path().display()));
+
+        // create app in a subdirectory, the mark points to its parent
+        let mut app = TestApp::new(fs.path().join("src")).unwrap();
+        app.app.load_bookmarks(store_path).unwrap();
+        app.send_events(vec![
+            events::char('l'),
+            events::char('i'),
+            events::char('b'),
+            events::enter(),
+        ])
+        .unwrap();
+        assert!(app.is_file_view());
+
+        // the jump is refused and the file stays opened
+        assert!(app
+            .send_events(vec![events::char('\''), events::char('o')])
+            .is_err());
+        assert!(app.is_file_view());
+        assert!(app.get_opened_file().unwrap().ends_with("lib.rs"));
+
+        // the mark is not offered in the picker
+        app.send_event(events::char('q')).unwrap();
+        assert!(app.is_search_view());
+        app.send_event(events::char('M')).unwrap();
+        assert!(app
+            .app
+            .message_holder
+            .bookmark_holder
+            .selected_mark_holder
+            .is_empty());
+    }
+
+    #[test]
+    fn test_bookmark_mark_and_jump() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+
+        // mark README.md at its second line
+        app.send_events(vec![
+            events::char('R'),
+            events::char('E'),
+            events::char('A'),
+            events::enter(),
+            events::char('j'),
+            events::char('m'),
+            events::char('r'),
+            events::char('q'),
+        ])
+        .unwrap();
+
+        // mark src directory
+        app.send_events(vec![
+            events::char('s'),
+            events::char('r'),
+            events::char('c'),
+            events::enter(),
+            events::tab(),
+            events::char('m'),
+            events::char('s'),
+        ])
+        .unwrap();
+        assert!(app.get_current_directory().ends_with("src"));
+
+        // jump back to the file and scroll position
+        app.send_events(vec![events::char('\''), events::char('r')])
+            .unwrap();
+        assert!(app.is_file_view());
+        assert!(app.get_opened_file().unwrap().ends_with("README.md"));
+        assert_eq!(app.get_scroll_positions(), (1, 0));
+        assert_eq!(
+            app.get_current_directory(),
+            fs.path().canonicalize().unwrap()
+        );
+
+        // pick the directory mark from the bookmark picker
+        app.send_event(events::char('M')).unwrap();
+        assert!(app.is_edit_mode());
+        assert_eq!(app.get_view_mode(), ViewMode::BookmarkView);
+        app.send_events(vec![events::down(), events::enter()])
+            .unwrap();
+        assert!(app.is_search_view());
+        assert!(app.get_opened_file().is_none());
+        assert!(app.get_current_directory().ends_with("src"));
+
+        // unset marks are reported
+        assert!(app
+            .send_events(vec![events::char('\''), events::char('x')])
+            .is_err());
+    }
+
+    #[test]
+    fn test_jump_to_removed_location_dropped() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+
+        // a failed move to the parent is not recorded
+        app.send_events(vec![events::tab(), events::ctrl_k()])
+            .unwrap_err();
+        assert!(app.send_event(events::ctrl_o()).is_err());
+
+        // enter empty, then go back to the root
+        app.send_events(vec![
+            events::tab(),
+            events::char('e'),
+            events::char('m'),
+            events::char('p'),
+            events::enter(),
+        ])
+        .unwrap();
+        assert!(app.get_current_directory().ends_with("empty"));
+        app.send_events(vec![events::tab(), events::ctrl_o()])
+            .unwrap();
+        assert_eq!(app.get_current_directory(), fs.path());
+
+        // the removed directory is dropped instead of shown
+        fs.remove_folder("empty");
+        assert!(app.send_event(events::ctrl_i()).is_err());
+        assert_eq!(app.get_current_directory(), fs.path());
+        assert!(app.is_search_view());
+        assert!(app.send_event(events::ctrl_i()).is_err());
+        assert!(app.send_event(events::ctrl_o()).is_err());
+    }
+
+    #[test]
+    fn test_draw_unknown_mode_logged() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        let mut state_holder = app.app.state_holder.borrow_mut();
+        state_holder.input_mode = InputMode::Normal;
+        state_holder.view_mode = ViewMode::HistoryFolderView;
+        drop(state_holder);
+
+        // the frame is drawn and the problem shown instead of panicking
+        app.app.render(&mut app.terminal).unwrap();
+        assert!(app.app.log_message.contains("Unexpected mode"));
+    }
+
+    #[test]
+    fn test_jump_back_and_forward() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        let src_index = app
+            .get_visible_items()
+            .iter()
+            .position(|item| item == "src")
+            .unwrap();
+
+        // open src/lib.rs through the filter
+        app.send_events(vec![
+            events::char('s'),
+            events::char('r'),
+            events::char('c'),
+            events::enter(),
+        ])
+        .unwrap();
+        let lib_index = app
+            .get_visible_items()
+            .iter()
+            .position(|item| item == "lib.rs")
+            .unwrap();
+        app.send_events(vec![
+            events::char('l'),
+            events::char('i'),
+            events::char('b'),
+            events::enter(),
+        ])
+        .unwrap();
+        assert!(app.is_file_view());
+
+        // back to src with lib.rs selected
+        app.send_event(events::ctrl_o()).unwrap();
+        assert!(app.is_search_view());
+        assert!(app.get_opened_file().is_none());
+        assert!(app.get_current_directory().ends_with("src"));
+        assert_eq!(app.app.message_holder.raw_highlight_index, lib_index as i32);
+
+        // back to the root with src selected
+        app.send_event(events::ctrl_o()).unwrap();
+        assert_eq!(app.get_current_directory(), fs.path());
+        assert_eq!(app.app.message_holder.raw_highlight_index, src_index as i32);
+        assert!(app.send_event(events::ctrl_o()).is_err());
+
+        // forward to src, then to the file again, Ctrl+N works without
+        // keyboard enhancement
+        app.send_event(events::ctrl_i()).unwrap();
+        assert!(app.get_current_directory().ends_with("src"));
+        app.send_event(events::ctrl_n()).unwrap();
+        assert!(app.is_file_view());
+        assert!(app.get_opened_file().unwrap().ends_with("lib.rs"));
+        assert!(app.send_event(events::ctrl_i()).is_err());
+    }
+
+    #[test]
+    fn test_selection_and_scroll_remembered() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        let selected_item = |app: &TestApp| {
+            let index = app.app.message_holder.raw_highlight_index as usize;
+            app.get_visible_items()[index].clone()
+        };
+
+        // go down to src/nested through the filter, then back up
+        app.send_events(vec![
+            events::char('s'),
+            events::char('r'),
+            events::char('c'),
+            events::enter(),
+            events::char('n'),
+            events::char('e'),
+            events::char('s'),
+            events::enter(),
+            events::tab(),
+            events::ctrl_k(),
+        ])
+        .unwrap();
+        assert!(app.get_current_directory().ends_with("src"));
+        assert_eq!(selected_item(&app), "nested");
+
+        app.send_event(events::ctrl_k()).unwrap();
+        assert_eq!(app.get_current_directory(), fs.path());
+        assert_eq!(selected_item(&app), "src");
+
+        // scroll position is restored when reopening a file
+        app.send_events(vec![
+            events::tab(),
+            events::char('R'),
+            events::char('E'),
+            events::char('A'),
+            events::enter(),
+            events::char('j'),
+            events::char('q'),
+        ])
+        .unwrap();
+        assert_eq!(app.get_scroll_positions(), (0, 0));
+        app.send_event(events::enter()).unwrap();
+        assert!(app.get_opened_file().unwrap().ends_with("README.md"));
+        assert_eq!(app.get_scroll_positions(), (1, 0));
+    }
+
+    #[test]
+    fn test_search_and_help_over_file_view() {
+        // setup: a log long enough to scroll
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+        let content: String = (0..40)
+            .map(|i| format!("2024-05-01T10:{i:02}:00Z WARN line {i}\n"))
+            .collect();
+        fs.create_file("service.log", &content);
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('s'),
+            events::char('e'),
+            events::char('r'),
+            events::enter(),
+            events::char('v'),
+            events::char('j'),
+            events::char('j'),
+        ])
+        .unwrap();
+        assert!(app.is_file_view());
+        let level = app
+            .app
+            .message_holder
+            .log_holder
+            .as_ref()
+            .unwrap()
+            .min_level;
+        assert!(level.is_some());
+        assert_eq!(app.get_scroll_positions(), (2, 0));
+
+        // the search opened over the file lists the folder, filter restored
+        app.send_event(events::char('/')).unwrap();
+        assert!(app.is_edit_mode() && app.is_search_view());
+        assert_eq!(app.get_search_input(), "ser");
+        app.send_events(vec![events::backspace(); 3]).unwrap();
+        assert!(app.rendered_text().contains("README.md"));
+
+        // the help lists the keys of the search it was opened from
+        app.send_event(events::f1()).unwrap();
+        assert_eq!(
+            (app.get_input_mode(), app.get_view_mode()),
+            (InputMode::Normal, ViewMode::HelpView)
+        );
+        let text = app.rendered_text();
+        assert!(text.contains("Keys of"), "{text}");
+        assert!(text.contains("FileSearch"), "{text}");
+
+        // popping back leaves the file as it was
+        app.send_event(events::escape()).unwrap();
+        assert!(app.is_edit_mode() && app.is_search_view());
+        app.send_event(events::escape()).unwrap();
+        assert!(app.is_normal_mode() && app.is_file_view());
+        assert!(app.get_opened_file().unwrap().ends_with("service.log"));
+        assert_eq!(app.get_scroll_positions(), (2, 0));
+        assert_eq!(
+            app.app
+                .message_holder
+                .log_holder
+                .as_ref()
+                .unwrap()
+                .min_level,
+            level
+        );
+        assert!(app.rendered_text().contains("line 2"));
+
+        // the file view has its own help
+        app.send_event(events::char('?')).unwrap();
+        assert!(app.rendered_text().contains("Git History"));
+        app.send_event(events::char('q')).unwrap();
+        assert!(app.is_file_view());
+    }
+
+    #[test]
+    fn test_tree_expand_collapse_and_filter() {
+        // setup: a directory two levels deep
+        let fs = TestFileSystem::new();
+        fs.create_file("a.txt", "a");
+        fs.create_file("src/lib.rs", "lib");
+        fs.create_file("src/nested/deep.rs", "deep");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        let selected_item = |app: &TestApp| {
+            let index = app.app.message_holder.raw_highlight_index as usize;
+            app.get_visible_items()[index].clone()
+        };
+        // expand src, then src/nested, in place
+        app.send_events(vec![
+            events::tab(),
+            events::down(),
+            events::down(),
+            events::char('l'),
+        ])
+        .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["..", "a.txt", "src", "src/lib.rs", "src/nested"]
+        );
+        let text = app.rendered_text();
+        assert!(text.contains("├── lib.rs"));
+        assert!(text.contains("└── nested"));
+
+        app.send_events(vec![events::down(), events::down(), events::char('l')])
+            .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec![
+                "..",
+                "a.txt",
+                "src",
+                "src/lib.rs",
+                "src/nested",
+                "src/nested/deep.rs"
+            ]
+        );
+        assert!(app.rendered_text().contains("    └── deep.rs"));
+
+        // filtering keeps the ancestors of matches
+        app.send_events(vec![
+            events::tab(),
+            events::char('d'),
+            events::char('e'),
+            events::char('e'),
+            events::char('p'),
+        ])
+        .unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec!["src", "src/nested", "src/nested/deep.rs"]
+        );
+        app.send_events(vec![events::backspace(); 4]).unwrap();
+        app.send_event(events::tab()).unwrap();
+        assert_eq!(app.get_visible_items().len(), 6);
+
+        // collapsing from a child selects and collapses its parent
+        app.send_events(vec![events::down(); 5]).unwrap();
+        assert_eq!(selected_item(&app), "src/nested/deep.rs");
+        app.send_event(events::char('h')).unwrap();
+        assert_eq!(selected_item(&app), "src/nested");
+        assert_eq!(app.get_visible_items().len(), 5);
+        app.send_event(events::left()).unwrap();
+        assert_eq!(selected_item(&app), "src");
+        assert_eq!(app.get_visible_items(), vec!["..", "a.txt", "src"]);
+
+        // Enter toggles the directories inside the tree, and enters the
+        // directories of the current directory, even expanded
+        app.send_events(vec![events::char('l'), events::down(), events::down()])
+            .unwrap();
+        assert_eq!(selected_item(&app), "src/nested");
+        app.send_event(events::enter()).unwrap();
+        assert_eq!(app.get_visible_items().len(), 6);
+        app.send_event(events::enter()).unwrap();
+        assert_eq!(app.get_visible_items().len(), 5);
+        app.send_events(vec![events::up(), events::up()]).unwrap();
+        assert_eq!(selected_item(&app), "src");
+        app.send_event(events::enter()).unwrap();
+        assert!(app.get_current_directory().ends_with("src"));
+    }
+
+    #[test]
+    fn test_columns_view() {
+        // setup: a file and a directory next to each other, and a sibling of the start directory
+        let fs = TestFileSystem::new();
+        fs.create_file("work/notes.txt", "first note\nsecond note\n");
+        fs.create_file("work/src/lib.rs", "pub fn helper() {}");
+        fs.create_dir("archive");
+
+        let mut app = TestApp::new(fs.path().join("work")).unwrap();
+        let rendered_text = |app: &mut TestApp| {
+            // directory previews are read in the background, once the selection settled
+            app.advance_clock(PREVIEW_DEBOUNCE);
+            app.render_frame();
+            app.finish_loading().unwrap();
+            app.rendered_text()
+        };
+        app.send_events(vec![events::tab(), events::char('C'), events::down()])
+            .unwrap();
+        assert!(app.app.message_holder.show_columns);
+
+        // the parent pane lists the siblings outside the start directory, the preview shows the file
+        let text = rendered_text(&mut app);
+        assert!(text.contains("archive"));
+        assert!(text.contains("first note"));
+
+        // the preview follows the selection
+        app.send_event(events::down()).unwrap();
+        assert_eq!(app.get_visible_items()[2], "src");
+        assert!(rendered_text(&mut app).contains("lib.rs"));
+
+        app.send_event(events::char('C')).unwrap();
+        assert!(!rendered_text(&mut app).contains("first note"));
+
+        // previews do not show up in the history
+        app.send_event(events::char('H')).unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec![fs.path().join("work").display().to_string()]
+        );
+    }
+
+    #[test]
+    fn test_quick_look_while_typing() {
+        // setup: a text file and a binary file
+        let fs = TestFileSystem::new();
+        fs.create_file("notes.txt", "first note\nsecond note\n");
+        fs.create_file("image.bin", "\0PNG");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+
+        // the preview is off until turned on
+        app.send_events(vec![events::char('n'), events::char('o')])
+            .unwrap();
+        app.advance_clock(PREVIEW_DEBOUNCE);
+        assert!(!app.rendered_text().contains("first note"));
+        app.send_event(events::ctrl_p()).unwrap();
+        assert!(app.app.message_holder.show_quick_look);
+
+        // the preview is made once the selection settles
+        assert!(!app.rendered_text().contains("first note"));
+        app.advance_clock(PREVIEW_DEBOUNCE / 2);
+        assert!(!app.rendered_text().contains("first note"));
+        app.advance_clock(PREVIEW_DEBOUNCE / 2);
+        assert!(app.rendered_text().contains("first note"));
+
+        // binary files are not shown
+        app.send_events(vec![events::ctrl_c(), events::char('i'), events::char('m')])
+            .unwrap();
+        app.advance_clock(PREVIEW_DEBOUNCE);
+        assert!(app.rendered_text().contains("Binary file, no preview"));
+
+        // the preview can be turned off
+        app.send_event(events::ctrl_p()).unwrap();
+        assert!(!app.rendered_text().contains("Binary file"));
+    }
+
+    #[test]
+    fn test_quick_look_directory_debounced() {
+        // setup: a directory next to the start one
+        let fs = TestFileSystem::new();
+        fs.create_file("logs/today.txt", "today");
+        fs.create_file("notes.txt", "first note\n");
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        let rendered_text = |app: &mut TestApp| {
+            app.render_frame();
+            app.finish_loading().unwrap();
+            app.rendered_text()
+        };
+
+        // a highlighted directory is only read once the selection settles
+        app.send_events(vec![events::ctrl_p(), events::char('l'), events::char('o')])
+            .unwrap();
+        assert!(!rendered_text(&mut app).contains("today.txt"));
+        app.advance_clock(PREVIEW_DEBOUNCE);
+        assert!(rendered_text(&mut app).contains("today.txt"));
+
+        // leaving the search with a preview pending stops waiting for it
+        app.send_events(vec![
+            events::ctrl_c(),
+            events::char('n'),
+            events::char('o'),
+            events::char('t'),
+        ])
+        .unwrap();
+        app.send_event(events::tab()).unwrap();
+        app.advance_clock(PREVIEW_DEBOUNCE);
+        assert!(!app
+            .app
+            .message_holder
+            .preview_holder
+            .is_due(&fs.path().join("notes.txt")));
+
+        // previewed directories do not show up in the history
+        app.send_event(events::char('H')).unwrap();
+        assert_eq!(
+            app.get_visible_items(),
+            vec![fs.path().display().to_string()]
+        );
     }
 }
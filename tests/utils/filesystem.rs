@@ -1,6 +1,7 @@
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use tempfile::TempDir;
 
 pub struct TestFileSystem {
@@ -61,7 +62,32 @@ impl TestFileSystem {
         self.create_dir("empty");
     }
 
+    /// run a git command in the root, with a fixed identity for commits
+    pub fn git(&self, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&self.root_path)
+            .args([
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=tester@example.com",
+            ])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
     pub fn path(&self) -> &Path {
         &self.root_path
     }
 }
+
+impl Default for TestFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
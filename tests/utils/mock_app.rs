@@ -1,14 +1,32 @@
-use athena_viewer::app::app_error::AppResult;
+use athena_viewer::app::app_error::{AppError, AppResult};
 use athena_viewer::app::App;
+use athena_viewer::message_holder::preview_holder::Clock;
 use athena_viewer::state_holder::{InputMode, ViewMode};
 use ratatui::backend::TestBackend;
 use ratatui::crossterm::event::Event;
 use ratatui::Terminal;
+use std::cell::Cell;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// time after which background work is considered stuck
+const LOADING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// clock of the preview debounce, only moved forward by the tests
+#[derive(Debug, Clone)]
+pub struct TestClock(Rc<Cell<Instant>>);
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
 
 pub struct TestApp {
     pub app: App,
     pub terminal: Terminal<TestBackend>,
+    clock: TestClock,
 }
 
 impl TestApp {
@@ -17,9 +35,21 @@ impl TestApp {
         // change to test directory
 
         let terminal = super::mock_terminal::create_test_terminal();
-        let app = App::new(start_dir)?;
+        let mut app = App::new(start_dir)?;
+        let clock = TestClock(Rc::new(Cell::new(Instant::now())));
+        app.message_holder.preview_holder.set_clock(clock.clone());
+
+        Ok(Self {
+            app,
+            terminal,
+            clock,
+        })
+    }
 
-        Ok(Self { app, terminal })
+    /// move the preview clock forward, then let the app catch up as the main loop would
+    pub fn advance_clock(&mut self, duration: Duration) {
+        self.clock.0.set(self.clock.0.get() + duration);
+        self.app.handle_preview();
     }
 
     /// send an event to the app and process it
@@ -36,13 +66,73 @@ impl TestApp {
         match (input_mode, view_mode) {
             (Normal, Search) => self.app.handle_normal_search_event(event)?,
             (Normal, FileView) => self.app.handle_normal_file_view_event(event)?,
+            (Normal, FileHistoryView) => self.app.handle_normal_file_history_view_event(event)?,
+            (Normal, ConfirmView) => self.app.handle_normal_confirm_view_event(event)?,
+            (Normal, CommandView) => self.app.handle_normal_command_view_event(event)?,
+            (Normal, HelpView) => self.app.handle_normal_help_view_event(event)?,
+            (Edit, PromptView) => self.app.handle_edit_prompt_view_event(event)?,
             (Edit, HistoryFolderView) => self.app.handle_edit_history_folder_view_event(event)?,
             (Edit, Search) => self.app.handle_edit_search_event(event)?,
-            _ => (),
+            (Edit, BookmarkView) => self.app.handle_edit_bookmark_view_event(event)?,
+            (Edit, FileView) => self.app.handle_edit_file_view_event(event)?,
+            (input_mode, view_mode) => {
+                return Err(AppError::State(format!(
+                    "Unexpected mode {:?}+{:?}",
+                    input_mode, view_mode
+                )))
+            }
         }
+        self.finish_loading()?;
+        self.app.handle_preview();
         Ok(())
     }
 
+    /// wait for background directory reads, git status, pastes, deletes, commands and previews, as the main loop would
+    ///
+    /// fails if they are still running after `LOADING_TIMEOUT`, so a stuck worker fails the test
+    pub fn finish_loading(&mut self) -> AppResult<()> {
+        let deadline = Instant::now() + LOADING_TIMEOUT;
+        while self.app.message_holder.folder_holder.is_loading()
+            || self
+                .app
+                .message_holder
+                .folder_holder
+                .is_git_status_loading()
+            || self.app.message_holder.is_file_git_loading()
+            || self.app.message_holder.is_transferring()
+            || self.app.message_holder.is_deleting()
+            || self.app.message_holder.is_command_running()
+            || self.app.message_holder.preview_holder.is_loading()
+        {
+            if Instant::now() > deadline {
+                return Err(AppError::State(format!(
+                    "Background work still running after {:?}",
+                    LOADING_TIMEOUT
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+            self.app.handle_transfers()?;
+            self.app.handle_command_output()?;
+            self.app.handle_directory_loads()?;
+            self.app.handle_git_status();
+            self.app.handle_preview();
+        }
+        Ok(())
+    }
+
+    /// edit the file asked for in the external editor, as the main loop would
+    pub fn edit_in_editor(&mut self, edit: impl FnOnce(&str) -> String) -> AppResult<()> {
+        let request = self
+            .app
+            .pending_editor
+            .take()
+            .ok_or(AppError::State("No editor was asked for".into()))?;
+        let path = &request.paths[0];
+        let text = std::fs::read_to_string(path)?;
+        std::fs::write(path, edit(&text))?;
+        self.app.finish_editor(request, Ok(()))
+    }
+
     /// send a sequence of events
     pub fn send_events(&mut self, events: Vec<Event>) -> AppResult<()> {
         for event in events {
@@ -139,4 +229,11 @@ impl TestApp {
             .draw(|frame| self.app.draw(frame).unwrap())
             .unwrap();
     }
+
+    /// render the current frame and return its text
+    pub fn rendered_text(&mut self) -> String {
+        self.render_frame();
+        let buffer = self.terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
 }
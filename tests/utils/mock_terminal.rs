@@ -24,12 +24,27 @@ pub mod events {
     pub fn ctrl_c() -> Event {
         key_with_modifiers(KeyCode::Char('c'), KeyModifiers::CONTROL)
     }
+    pub fn ctrl_p() -> Event {
+        key_with_modifiers(KeyCode::Char('p'), KeyModifiers::CONTROL)
+    }
     pub fn ctrl_d() -> Event {
         key_with_modifiers(KeyCode::Char('d'), KeyModifiers::CONTROL)
     }
     pub fn ctrl_k() -> Event {
         key_with_modifiers(KeyCode::Char('k'), KeyModifiers::CONTROL)
     }
+    pub fn ctrl_o() -> Event {
+        key_with_modifiers(KeyCode::Char('o'), KeyModifiers::CONTROL)
+    }
+    pub fn ctrl_i() -> Event {
+        key_with_modifiers(KeyCode::Char('i'), KeyModifiers::CONTROL)
+    }
+    pub fn ctrl_n() -> Event {
+        key_with_modifiers(KeyCode::Char('n'), KeyModifiers::CONTROL)
+    }
+    pub fn ctrl_r() -> Event {
+        key_with_modifiers(KeyCode::Char('r'), KeyModifiers::CONTROL)
+    }
     pub fn ctrl_z() -> Event {
         key_with_modifiers(KeyCode::Char('z'), KeyModifiers::CONTROL)
     }
@@ -42,6 +57,12 @@ pub mod events {
     pub fn escape() -> Event {
         key(KeyCode::Esc)
     }
+    pub fn backspace() -> Event {
+        key(KeyCode::Backspace)
+    }
+    pub fn f1() -> Event {
+        key(KeyCode::F(1))
+    }
 
     // navigation
     pub fn down() -> Event {
@@ -0,0 +1,221 @@
+pub mod utils;
+#[cfg(test)]
+mod watch_tests {
+    use super::utils::*;
+    use crate::utils::TestFileSystem;
+    use athena_viewer::message_holder::git_helper::LineChange;
+    use athena_viewer::message_holder::watch_holder::WatchHolder;
+    use std::collections::HashSet;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// polls the watcher until `done` holds or the timeout is reached
+    fn wait_for_changes(app: &mut TestApp, done: impl Fn(&TestApp) -> bool) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            app.app.handle_file_changes().unwrap();
+            if done(app) {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn test_directory_change_refreshes_listing() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.app.watch_files().unwrap();
+
+        // select src, then add a file before it
+        app.send_event(events::tab()).unwrap();
+        let src_index = app
+            .get_visible_items()
+            .iter()
+            .position(|item| item == "src")
+            .unwrap();
+        app.app.message_holder.raw_highlight_index = src_index as i32;
+        fs.create_file("a_new.txt", "new");
+
+        assert!(wait_for_changes(&mut app, |app| app
+            .get_visible_items()
+            .contains(&"a_new.txt".to_string())));
+        let index = app.app.message_holder.raw_highlight_index as usize;
+        assert_eq!(app.get_visible_items()[index], "src");
+    }
+
+    #[test]
+    fn test_continuous_writes_are_reported() {
+        // setup: watch a directory with a file being written to
+        let fs = TestFileSystem::new();
+        let log_path = fs.create_file("service.log", "");
+        let mut watch_holder = WatchHolder::new().unwrap();
+        watch_holder.watch_paths(HashSet::from([fs.path().to_path_buf()]));
+
+        // write every 100ms for 3s, changes are reported while writing
+        let start = Instant::now();
+        let mut reported = None;
+        while start.elapsed() < Duration::from_secs(3) {
+            let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+            file.write_all(b"line\n").unwrap();
+            if watch_holder.poll().is_some() {
+                reported = Some(start.elapsed());
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert!(reported.is_some_and(|elapsed| elapsed < Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_opened_file_reloads() {
+        // setup: create test filesystem
+        let fs = TestFileSystem::new();
+        fs.create_nested_structure();
+
+        // create app in test directory
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('R'),
+            events::char('E'),
+            events::char('A'),
+            events::enter(),
+            events::char('j'),
+        ])
+        .unwrap();
+        app.app.watch_files().unwrap();
+        assert_eq!(app.get_scroll_positions(), (1, 0));
+        let n_rows = app
+            .app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .unwrap()
+            .n_rows;
+
+        fs.create_file(
+            "README.md",
+            "# Test Project\nThis is a readme.\nMore\nLines",
+        );
+        assert!(wait_for_changes(&mut app, |app| app
+            .app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .is_some_and(|info| info.n_rows == n_rows + 2)));
+        assert!(app.is_file_view());
+        assert_eq!(app.get_scroll_positions(), (1, 0));
+    }
+
+    #[test]
+    fn test_follow_appends_and_pauses() {
+        // setup: a log longer than the screen
+        let fs = TestFileSystem::new();
+        let lines: String = (0..50).map(|i| format!("line {}\n", i)).collect();
+        let log_path = fs.create_file("service.log", &lines);
+
+        // open the log and follow it, through the log view
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('s'),
+            events::char('e'),
+            events::char('r'),
+            events::enter(),
+            events::char('F'),
+        ])
+        .unwrap();
+        app.render_frame();
+        assert!(app.app.message_holder.log_holder.is_some());
+        let (end_scroll, _) = app.get_scroll_positions();
+        assert!(end_scroll > 0);
+
+        // appended lines keep the view at the end
+        let append = |text: &str| {
+            let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+            file.write_all(text.as_bytes()).unwrap();
+        };
+        append("line 50\nline 51\n");
+        app.app.handle_follow().unwrap();
+        app.render_frame();
+        assert_eq!(app.get_scroll_positions(), (end_scroll + 2, 0));
+
+        // scrolling pauses following
+        app.send_event(events::char('k')).unwrap();
+        append("line 52\n");
+        app.app.handle_follow().unwrap();
+        app.render_frame();
+        assert_eq!(app.get_scroll_positions(), (end_scroll + 1, 0));
+
+        // resuming jumps back to the end
+        app.send_event(events::char('F')).unwrap();
+        app.render_frame();
+        assert_eq!(app.get_scroll_positions(), (end_scroll + 3, 0));
+
+        // a truncated file is read again
+        fs.create_file("service.log", "fresh\n");
+        app.app.handle_follow().unwrap();
+        app.render_frame();
+        assert_eq!(app.get_scroll_positions(), (0, 0));
+        let n_rows = app
+            .app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .unwrap()
+            .n_rows;
+        assert_eq!(n_rows, 2);
+    }
+
+    #[test]
+    fn test_follow_marks_appended_lines_and_stops_on_error() {
+        // setup: a tracked file
+        let fs = TestFileSystem::new();
+        fs.git(&["init", "-q"]);
+        let log_path = fs.create_file("tracked.txt", "line 0\nline 1\n");
+        fs.git(&["add", "tracked.txt"]);
+        fs.git(&["commit", "-q", "-m", "add tracked"]);
+
+        let mut app = TestApp::new(fs.path().to_path_buf()).unwrap();
+        app.send_events(vec![
+            events::char('t'),
+            events::char('r'),
+            events::enter(),
+            events::char('F'),
+        ])
+        .unwrap();
+        assert!(app.app.message_holder.is_following());
+
+        // appended lines are marked as added in the gutter
+        let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+        file.write_all(b"line 2\nline 3\n").unwrap();
+        app.app.handle_follow().unwrap();
+        app.finish_loading().unwrap();
+        let n_rows = app
+            .app
+            .message_holder
+            .file_text_info
+            .as_ref()
+            .unwrap()
+            .n_rows;
+        let file_git_info = app.app.message_holder.file_git_info.as_ref().unwrap();
+        assert_eq!(file_git_info.line_changes.len(), n_rows);
+        assert_eq!(file_git_info.line_changes[1], None);
+        assert_eq!(file_git_info.line_changes[2], Some(LineChange::Added));
+        assert_eq!(file_git_info.line_changes[3], Some(LineChange::Added));
+
+        // a file that cannot be read anymore stops following, reported once
+        std::fs::remove_file(&log_path).unwrap();
+        std::fs::create_dir(&log_path).unwrap();
+        let error = app.app.handle_follow().unwrap_err();
+        assert!(error.to_string().contains("Follow stopped"), "{error}");
+        assert!(!app.app.message_holder.is_following());
+        app.app.handle_follow().unwrap();
+    }
+}